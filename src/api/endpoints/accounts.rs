@@ -1,15 +1,21 @@
-use crate::*;
+use crate::{types::parse_lovelace, *};
 use blockfrost_openapi::models::{
     account_addresses_assets_inner::AccountAddressesAssetsInner,
     account_addresses_content_inner::AccountAddressesContentInner,
-    account_addresses_total::AccountAddressesTotal, account_content::AccountContent,
+    account_addresses_total::AccountAddressesTotal,
+    account_content::AccountContent,
     account_delegation_content_inner::AccountDelegationContentInner,
     account_history_content_inner::AccountHistoryContentInner,
-    account_mir_content_inner::AccountMirContentInner,
-    account_registration_content_inner::AccountRegistrationContentInner,
+    account_registration_content_inner::{AccountRegistrationContentInner, Action},
     account_reward_content_inner::AccountRewardContentInner,
-    account_withdrawal_content_inner::AccountWithdrawalContentInner,
+    block_content::BlockContent,
 };
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+// Transactions resolved at a time by `accounts_full_history`
+const HISTORY_CONCURRENCY: usize = 8;
 
 impl BlockfrostAPI {
     pub async fn accounts(&self, stake_address: &str) -> BlockfrostResult<AccountContent> {
@@ -17,6 +23,28 @@ impl BlockfrostAPI {
             .await
     }
 
+    /// [`accounts`](Self::accounts) with the amounts as [`Lovelace`].
+    ///
+    /// A deregistered account is still returned, with `active` false and no `pool_id`. Stake
+    /// addresses that never appeared on chain fail with a `404` response error.
+    pub async fn accounts_by_stake_address(
+        &self, stake_address: &str,
+    ) -> BlockfrostResult<Account> {
+        self.call_endpoint(format!("/accounts/{}", stake_address).as_str())
+            .await
+    }
+
+    /// Bech32 ID of the pool the account currently delegates to, `None` if it doesn't delegate.
+    ///
+    /// A deregistered account is `None` even when the API still reports its last pool.
+    pub async fn accounts_current_pool(
+        &self, stake_address: &str,
+    ) -> BlockfrostResult<Option<String>> {
+        let account = self.accounts_by_stake_address(stake_address).await?;
+
+        Ok(account.pool_id.filter(|_| account.active))
+    }
+
     /// Reward history of a specific account.
     pub async fn accounts_rewards(
         &self, stake_address: &str, pagination: Pagination,
@@ -59,9 +87,10 @@ impl BlockfrostAPI {
         .await
     }
 
+    /// Reward withdrawals of a specific account, kept apart from the rewards earned.
     pub async fn accounts_withdrawals(
         &self, stake_address: &str, pagination: Pagination,
-    ) -> BlockfrostResult<Vec<AccountWithdrawalContentInner>> {
+    ) -> BlockfrostResult<Vec<AccountWithdrawal>> {
         self.call_paged_endpoint(
             format!("/accounts/{}/withdrawals", stake_address).as_str(),
             pagination,
@@ -69,9 +98,10 @@ impl BlockfrostAPI {
         .await
     }
 
+    /// Move Instantaneous Rewards (MIRs) paid to a specific account.
     pub async fn accounts_mirs(
         &self, stake_address: &str, pagination: Pagination,
-    ) -> BlockfrostResult<Vec<AccountMirContentInner>> {
+    ) -> BlockfrostResult<Vec<AccountMir>> {
         self.call_paged_endpoint(
             format!("/accounts/{}/mirs", stake_address).as_str(),
             pagination,
@@ -89,6 +119,7 @@ impl BlockfrostAPI {
         .await
     }
 
+    /// Assets held by all addresses associated with a specific account, summed by unit.
     pub async fn accounts_addresses_assets(
         &self, stake_address: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<AccountAddressesAssetsInner>> {
@@ -99,18 +130,420 @@ impl BlockfrostAPI {
         .await
     }
 
+    /// Stream every page of [`accounts_addresses_assets`](Self::accounts_addresses_assets).
+    pub fn accounts_addresses_assets_all(
+        &self, stake_address: &str,
+    ) -> Lister<'_, AccountAddressesAssetsInner> {
+        Lister::new(
+            self,
+            format!("/accounts/{}/addresses/assets", stake_address),
+        )
+    }
+
     pub async fn accounts_addresses_total(
         &self, stake_address: &str,
     ) -> BlockfrostResult<AccountAddressesTotal> {
         self.call_endpoint(format!("/accounts/{}/addresses/total", stake_address).as_str())
             .await
     }
+
+    /// Per epoch summary of an account, joining its [`history`](Self::accounts_history) and
+    /// [`rewards`](Self::accounts_rewards), sorted by epoch.
+    ///
+    /// Fetches every page of both lists. Epochs with stake but no rewards have zero `rewards`,
+    /// epochs with rewards but no stake recorded have no `active_stake`.
+    pub async fn accounts_epoch_summaries(
+        &self, stake_address: &str,
+    ) -> BlockfrostResult<Vec<AccountEpochSummary>> {
+        let history = Lister::<AccountHistoryContentInner>::new(
+            self,
+            format!("/accounts/{}/history", stake_address),
+        );
+        let rewards = Lister::<AccountRewardContentInner>::new(
+            self,
+            format!("/accounts/{}/rewards", stake_address),
+        );
+        let (history, rewards) = futures::try_join!(history.collect_all(), rewards.collect_all())?;
+
+        let mut summaries = BTreeMap::new();
+        let new_summary = |epoch, pool_id| AccountEpochSummary {
+            epoch,
+            active_stake: None,
+            pool_id,
+            rewards: Lovelace(0),
+        };
+
+        for entry in history {
+            let active_stake = parse_lovelace(&entry.amount)?;
+            let summary = summaries
+                .entry(entry.active_epoch)
+                .or_insert_with(|| new_summary(entry.active_epoch, entry.pool_id));
+            summary.active_stake = Some(active_stake);
+        }
+        for reward in rewards {
+            let amount = parse_lovelace(&reward.amount)?;
+            let summary = summaries
+                .entry(reward.epoch)
+                .or_insert_with(|| new_summary(reward.epoch, reward.pool_id));
+            summary.rewards.0 += amount.0;
+        }
+
+        Ok(summaries.into_values().collect())
+    }
+
+    /// Every registration, delegation, reward, withdrawal and MIR of an account as a single
+    /// timeline, oldest first.
+    ///
+    /// Fetches every page of the five lists, then the transaction and block of each
+    /// certificate, withdrawal and MIR (two requests each) to find where it sits on chain.
+    /// Events are sorted by epoch, then by block and position in the block. Rewards come after
+    /// the transactions of the epoch they were earned in.
+    pub async fn accounts_full_history(
+        &self, stake_address: &str,
+    ) -> BlockfrostResult<AccountFullHistory> {
+        let list = |suffix: &str| format!("/accounts/{}/{}", stake_address, suffix);
+        let (registrations, delegations, rewards, withdrawals, mirs) = futures::try_join!(
+            Lister::<AccountRegistrationContentInner>::new(self, list("registrations"))
+                .collect_all(),
+            Lister::<AccountDelegationContentInner>::new(self, list("delegations")).collect_all(),
+            Lister::<AccountRewardContentInner>::new(self, list("rewards")).collect_all(),
+            Lister::<AccountWithdrawal>::new(self, list("withdrawals")).collect_all(),
+            Lister::<AccountMir>::new(self, list("mirs")).collect_all(),
+        )?;
+
+        let tx_hashes: BTreeSet<String> = registrations
+            .iter()
+            .map(|registration| &registration.tx_hash)
+            .chain(delegations.iter().map(|delegation| &delegation.tx_hash))
+            .chain(withdrawals.iter().map(|withdrawal| &withdrawal.tx_hash))
+            .chain(mirs.iter().map(|mir| &mir.tx_hash))
+            .cloned()
+            .collect();
+        let positions: HashMap<String, (i32, i32, i32)> = stream::iter(tx_hashes)
+            .map(|tx_hash| async move {
+                let transaction = self.transaction_by_hash(&tx_hash).await?;
+                let block = self.blocks_by_id(&transaction.block).await?;
+                let position = (
+                    block.epoch.unwrap_or_default(),
+                    block_height(&block),
+                    transaction.index,
+                );
+
+                Ok::<_, BlockfrostError>((tx_hash, position))
+            })
+            .buffered(HISTORY_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        let mut events = Vec::new();
+        let position = |tx_hash: &str| positions[tx_hash];
+
+        for registration in &registrations {
+            let (epoch, height, index) = position(&registration.tx_hash);
+            let tx_hash = registration.tx_hash.clone();
+            let event = match registration.action {
+                Action::Registered => AccountEvent::Registered { epoch, tx_hash },
+                Action::Deregistered => AccountEvent::Deregistered { epoch, tx_hash },
+            };
+            events.push(((epoch, 0, height, index), event));
+        }
+        for delegation in delegations {
+            let (epoch, height, index) = position(&delegation.tx_hash);
+            let event = AccountEvent::Delegated {
+                epoch,
+                tx_hash: delegation.tx_hash,
+                pool_id: delegation.pool_id,
+                active_epoch: delegation.active_epoch,
+            };
+            events.push(((epoch, 0, height, index), event));
+        }
+        for withdrawal in withdrawals {
+            let (epoch, height, index) = position(&withdrawal.tx_hash);
+            let event = AccountEvent::Withdrew {
+                epoch,
+                tx_hash: withdrawal.tx_hash,
+                amount: withdrawal.amount,
+            };
+            events.push(((epoch, 0, height, index), event));
+        }
+        for mir in mirs {
+            let (epoch, height, index) = position(&mir.tx_hash);
+            let event = AccountEvent::Mir {
+                epoch,
+                tx_hash: mir.tx_hash,
+                amount: mir.amount,
+            };
+            events.push(((epoch, 0, height, index), event));
+        }
+        for reward in rewards {
+            let event = AccountEvent::RewardPaid {
+                epoch: reward.epoch,
+                pool_id: reward.pool_id,
+                amount: parse_lovelace(&reward.amount)?,
+                reward_type: reward.r#type,
+            };
+            events.push(((reward.epoch, 1, 0, 0), event));
+        }
+
+        // Stable, so rewards of the same epoch keep the order they were listed in
+        events.sort_by_key(|(key, _)| *key);
+
+        Ok(AccountFullHistory {
+            stake_address: stake_address.to_string(),
+            events: events.into_iter().map(|(_, event)| event).collect(),
+        })
+    }
+}
+
+// Byron epoch boundary blocks have no height, they hold no transactions anyway
+fn block_height(block: &BlockContent) -> i32 {
+    block.height.unwrap_or_default()
 }
+
+/// Stake and rewards of an account in one epoch, created by
+/// [`accounts_epoch_summaries`](BlockfrostAPI::accounts_epoch_summaries).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AccountEpochSummary {
+    pub epoch: i32,
+    /// Active stake in this epoch, `None` if the history has no entry for it.
+    pub active_stake: Option<Lovelace>,
+    /// Bech32 ID of the pool delegated to, taken from the rewards when there is no stake entry.
+    pub pool_id: String,
+    /// Sum of the rewards of every type (member, leader, refunds) for this epoch.
+    pub rewards: Lovelace,
+}
+
+/// Timeline of an account, created by
+/// [`accounts_full_history`](BlockfrostAPI::accounts_full_history).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AccountFullHistory {
+    pub stake_address: String,
+    /// Every event of the account, oldest first.
+    pub events: Vec<AccountEvent>,
+}
+
+/// One entry of an [`AccountFullHistory`], `epoch` is the epoch of the transaction, or the one
+/// the reward was earned in.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AccountEvent {
+    Registered {
+        epoch: i32,
+        tx_hash: String,
+    },
+    Deregistered {
+        epoch: i32,
+        tx_hash: String,
+    },
+    Delegated {
+        epoch: i32,
+        tx_hash: String,
+        /// Bech32 ID of the pool delegated to.
+        pool_id: String,
+        /// First epoch in which the delegation counts.
+        active_epoch: i32,
+    },
+    RewardPaid {
+        epoch: i32,
+        /// Bech32 ID of the pool the reward comes from.
+        pool_id: String,
+        amount: Lovelace,
+        reward_type: RewardType,
+    },
+    Withdrew {
+        epoch: i32,
+        tx_hash: String,
+        amount: Lovelace,
+    },
+    Mir {
+        epoch: i32,
+        tx_hash: String,
+        amount: Lovelace,
+    },
+}
+
+impl AccountEvent {
+    pub fn epoch(&self) -> i32 {
+        match self {
+            AccountEvent::Registered { epoch, .. }
+            | AccountEvent::Deregistered { epoch, .. }
+            | AccountEvent::Delegated { epoch, .. }
+            | AccountEvent::RewardPaid { epoch, .. }
+            | AccountEvent::Withdrew { epoch, .. }
+            | AccountEvent::Mir { epoch, .. } => *epoch,
+        }
+    }
+}
+
+/// Stake account, returned by
+/// [`accounts_by_stake_address`](BlockfrostAPI::accounts_by_stake_address).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct Account {
+    /// Bech32 stake address.
+    pub stake_address: String,
+    /// Whether the stake address is registered.
+    pub active: bool,
+    /// Epoch of the most recent registration or deregistration.
+    pub active_epoch: Option<Integer>,
+    /// Balance of every address of the account, rewards included.
+    pub controlled_amount: Lovelace,
+    pub rewards_sum: Lovelace,
+    pub withdrawals_sum: Lovelace,
+    /// Sum of the MIRs paid from the reserves.
+    pub reserves_sum: Lovelace,
+    /// Sum of the MIRs paid from the treasury.
+    pub treasury_sum: Lovelace,
+    /// Rewards not withdrawn yet.
+    pub withdrawable_amount: Lovelace,
+    /// Bech32 ID of the pool the account delegates to, `None` if it doesn't delegate.
+    pub pool_id: Option<String>,
+}
+
+/// Reward withdrawal of an account, returned by
+/// [`accounts_withdrawals`](BlockfrostAPI::accounts_withdrawals).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct AccountWithdrawal {
+    /// Hash of the transaction containing the withdrawal.
+    pub tx_hash: String,
+    /// Withdrawal amount.
+    pub amount: Lovelace,
+}
+
+/// Move Instantaneous Reward (MIR) of an account, returned by
+/// [`accounts_mirs`](BlockfrostAPI::accounts_mirs).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct AccountMir {
+    /// Hash of the transaction containing the MIR certificate.
+    pub tx_hash: String,
+    /// MIR amount.
+    pub amount: Lovelace,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::{MockResponse, MockServer};
+    use futures::StreamExt;
     use serde_json::json;
 
+    #[tokio::test]
+    async fn test_accounts_by_stake_address() {
+        let delegated = json!({
+            "stake_address": "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7",
+            "active": true,
+            "active_epoch": 412,
+            "controlled_amount": "619154618165",
+            "rewards_sum": "319154618165",
+            "withdrawals_sum": "12125369253",
+            "reserves_sum": "319154618165",
+            "treasury_sum": "12000000",
+            "withdrawable_amount": "319154618165",
+            "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy"
+        });
+        let deregistered = json!({
+            "stake_address": "stake1u9ylzsgxaa6xctf4juup682ar3juj85n8tx3hthnljg47zctvm3rc",
+            "active": false,
+            "active_epoch": 320,
+            "controlled_amount": "0",
+            "rewards_sum": "5250000",
+            "withdrawals_sum": "5250000",
+            "reserves_sum": "0",
+            "treasury_sum": "0",
+            "withdrawable_amount": "0",
+            "pool_id": null
+        });
+        let server = MockServer::start(vec![
+            MockResponse::new(200, delegated.to_string()),
+            MockResponse::new(200, deregistered.to_string()),
+        ]);
+        let api = server.api();
+
+        let account = api
+            .accounts_by_stake_address(
+                "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7",
+            )
+            .await
+            .unwrap();
+        assert!(account.active);
+        assert_eq!(account.active_epoch, Some(412));
+        assert_eq!(account.withdrawable_amount, Lovelace(319154618165));
+        assert_eq!(
+            account.pool_id.as_deref(),
+            Some("pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy")
+        );
+
+        let account = api
+            .accounts_by_stake_address(
+                "stake1u9ylzsgxaa6xctf4juup682ar3juj85n8tx3hthnljg47zctvm3rc",
+            )
+            .await
+            .unwrap();
+        assert!(!account.active);
+        assert_eq!(account.withdrawable_amount, Lovelace(0));
+        assert_eq!(account.pool_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_accounts_current_pool() {
+        const POOL_ID: &str = "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy";
+        let account = |active: bool, pool_id: Option<&str>| {
+            json!({
+                "stake_address": "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7",
+                "active": active,
+                "active_epoch": 412,
+                "controlled_amount": "619154618165",
+                "rewards_sum": "0",
+                "withdrawals_sum": "0",
+                "reserves_sum": "0",
+                "treasury_sum": "0",
+                "withdrawable_amount": "0",
+                "pool_id": pool_id
+            })
+            .to_string()
+        };
+        let server = MockServer::start(vec![
+            MockResponse::new(200, account(true, Some(POOL_ID))),
+            MockResponse::new(200, account(true, None)),
+            MockResponse::new(200, account(false, Some(POOL_ID))),
+        ]);
+        let api = server.api();
+        let stake_address = "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7";
+
+        let delegated = api.accounts_current_pool(stake_address).await.unwrap();
+        assert_eq!(delegated.as_deref(), Some(POOL_ID));
+
+        let undelegated = api.accounts_current_pool(stake_address).await.unwrap();
+        assert_eq!(undelegated, None);
+
+        let deregistered = api.accounts_current_pool(stake_address).await.unwrap();
+        assert_eq!(deregistered, None);
+    }
+
+    #[tokio::test]
+    async fn test_accounts_by_stake_address_unknown() {
+        let error = json!({
+            "status_code": 404,
+            "error": "Not Found",
+            "message": "The requested component has not been found."
+        });
+        let server = MockServer::start(vec![MockResponse::new(404, error.to_string())]);
+
+        let result = server
+            .api()
+            .accounts_by_stake_address(
+                "stake1u9ylzsgxaa6xctf4juup682ar3juj85n8tx3hthnljg47zctvm3rc",
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 404
+        ));
+    }
+
     #[tokio::test]
     async fn test_accounts() {
         let json_value = json!({
@@ -228,7 +661,10 @@ mod tests {
             }
         ]);
 
-        serde_json::from_value::<Vec<AccountWithdrawalContentInner>>(json_value).unwrap();
+        let withdrawals = serde_json::from_value::<Vec<AccountWithdrawal>>(json_value).unwrap();
+
+        assert_eq!(withdrawals[0].amount, Lovelace(454541212442));
+        assert_eq!(withdrawals[1].amount, Lovelace(97846969));
     }
 
     #[tokio::test]
@@ -244,7 +680,111 @@ mod tests {
             }
         ]);
 
-        serde_json::from_value::<Vec<AccountMirContentInner>>(json_value).unwrap();
+        let mirs = serde_json::from_value::<Vec<AccountMir>>(json_value).unwrap();
+
+        assert_eq!(
+            mirs[0].tx_hash,
+            "48a9625c841eea0dd2bb6cf551eabe6523b7290c9ce34be74eedef2dd8f7ecc5"
+        );
+        assert_eq!(mirs[1].amount, Lovelace(97846969));
+    }
+
+    #[tokio::test]
+    async fn test_accounts_withdrawals_paginated() {
+        let withdrawals = json!([{
+            "tx_hash": "48a9625c841eea0dd2bb6cf551eabe6523b7290c9ce34be74eedef2dd8f7ecc5",
+            "amount": "454541212442"
+        }]);
+        let server = MockServer::start(vec![MockResponse::new(200, withdrawals.to_string())]);
+
+        let result = server
+            .api()
+            .accounts_withdrawals(
+                "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7",
+                Pagination::new(Order::Desc, 2, 10),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result[0].amount, Lovelace(454541212442));
+        assert!(server.requests()[0].contains("/withdrawals?page=2&count=10&order=desc"));
+    }
+
+    #[tokio::test]
+    async fn test_accounts_full_history() {
+        use blockfrost_openapi::models::tx_content::TxContent;
+
+        // Transaction "t{n}" is in block "b{n}", at height n in epoch n / 10
+        let server = MockServer::start_with(|_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let path = path.split('?').next().unwrap();
+            let list = path.rsplit('/').next().unwrap();
+            let first_page = request.contains("page=1&");
+            let body = match list {
+                _ if path.starts_with("/accounts/") && !first_page => json!([]),
+                "registrations" => json!([{ "tx_hash": "t100", "action": "registered" }]),
+                "delegations" => json!([{
+                    "active_epoch": 12, "tx_hash": "t101", "amount": "1",
+                    "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy"
+                }]),
+                "rewards" => json!([{
+                    "epoch": 12, "amount": "3586329", "type": "member",
+                    "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy"
+                }]),
+                "withdrawals" => json!([{ "tx_hash": "t135", "amount": "3586329" }]),
+                "mirs" => json!([{ "tx_hash": "t105", "amount": "1000000" }]),
+                _ if path.starts_with("/txs/t") => {
+                    let transaction = TxContent {
+                        block: format!("b{}", &path[6..]),
+                        ..Default::default()
+                    };
+                    serde_json::to_value(transaction).unwrap()
+                }
+                _ => {
+                    let height: i32 = path[9..].parse().unwrap();
+                    let block = BlockContent {
+                        height: Some(height),
+                        epoch: Some(height / 10),
+                        ..Default::default()
+                    };
+                    serde_json::to_value(block).unwrap()
+                }
+            };
+            MockResponse::new(200, body.to_string())
+        });
+
+        let history = server
+            .api()
+            .accounts_full_history("stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7")
+            .await
+            .unwrap();
+
+        let events: Vec<_> = history
+            .events
+            .iter()
+            .map(|event| {
+                let kind = serde_json::to_value(event).unwrap()["event"].clone();
+                (event.epoch(), kind.as_str().unwrap().to_string())
+            })
+            .collect();
+        assert_eq!(
+            events,
+            [
+                (10, "registered".to_string()),
+                (10, "delegated".to_string()),
+                (10, "mir".to_string()),
+                (12, "reward_paid".to_string()),
+                (13, "withdrew".to_string()),
+            ]
+        );
+        assert!(matches!(
+            history.events[3],
+            AccountEvent::RewardPaid {
+                amount: Lovelace(3586329),
+                reward_type: RewardType::Member,
+                ..
+            }
+        ));
     }
 
     #[tokio::test]
@@ -282,4 +822,86 @@ mod tests {
 
         serde_json::from_value::<Vec<AccountAddressesAssetsInner>>(json_value).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_accounts_addresses_assets_all() {
+        let unit = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e";
+        let full_page = json!(vec![json!({ "unit": unit, "quantity": "1" }); 100]);
+        let last_page = json!([
+            {
+                "unit": "d5e6bf0500378d4f0da4e8dde6becec7621cd8cbf5cbb9b87013d4cc537061636542756433343132",
+                "quantity": "1"
+            },
+            {
+                "unit": unit,
+                "quantity": "125"
+            },
+            {
+                "unit": "6804edf9712d2b619edb6ac86861fe93a730693183a262b165fcc1ba1bc99cad",
+                "quantity": "18605647"
+            }
+        ]);
+        let server = MockServer::start(vec![
+            MockResponse::new(200, full_page.to_string()),
+            MockResponse::new(200, last_page.to_string()),
+        ]);
+        let api = server.api();
+
+        let pages: Vec<_> = api
+            .accounts_addresses_assets_all(
+                "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7",
+            )
+            .collect()
+            .await;
+
+        assert_eq!(pages.len(), 2);
+        let assets: Vec<AccountAddressesAssetsInner> =
+            pages.into_iter().flat_map(Result::unwrap).collect();
+        assert_eq!(assets.len(), 103);
+        assert_eq!(assets[102].quantity, "18605647");
+
+        let requests = server.requests();
+        assert!(requests[0].contains("/addresses/assets?page=1&count=100&order=asc"));
+        assert!(requests[1].contains("/addresses/assets?page=2&count=100&order=asc"));
+    }
+
+    #[tokio::test]
+    async fn test_accounts_epoch_summaries() {
+        let pool_id = "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy";
+        let server = MockServer::start_with(move |_, request| {
+            let body = if request.contains("/history") {
+                json!([
+                    { "active_epoch": 210, "amount": "12695385", "pool_id": pool_id },
+                    { "active_epoch": 211, "amount": "22695385", "pool_id": pool_id }
+                ])
+            } else {
+                json!([
+                    { "epoch": 211, "amount": "1000", "pool_id": pool_id, "type": "member" },
+                    { "epoch": 211, "amount": "500", "pool_id": pool_id, "type": "leader" },
+                    { "epoch": 212, "amount": "2000", "pool_id": pool_id, "type": "member" }
+                ])
+            };
+            MockResponse::new(200, body.to_string())
+        });
+
+        let summaries = server
+            .api()
+            .accounts_epoch_summaries("stake1u9t3a0tcwune5xrnfjg4q7cpvjlgx9lcv0cuqf5mhfjwrvcwrulda")
+            .await
+            .unwrap();
+
+        let epochs: Vec<_> = summaries
+            .iter()
+            .map(|summary| (summary.epoch, summary.active_stake, summary.rewards))
+            .collect();
+        assert_eq!(
+            epochs,
+            [
+                (210, Some(Lovelace(12695385)), Lovelace(0)),
+                (211, Some(Lovelace(22695385)), Lovelace(1500)),
+                (212, None, Lovelace(2000)),
+            ]
+        );
+        assert_eq!(summaries[2].pool_id, pool_id);
+    }
 }
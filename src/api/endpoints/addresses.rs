@@ -3,11 +3,20 @@ use blockfrost_openapi::models::{
     address_content_total::AddressContentTotal,
     address_transactions_content_inner::AddressTransactionsContentInner,
     address_utxo_content_inner::AddressUtxoContentInner,
+    tx_content_output_amount_inner::TxContentOutputAmountInner,
 };
 
 use crate::*;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 impl BlockfrostAPI {
+    /// Balance and kind of an address.
+    ///
+    /// `type` ([`AddressType`]) tells Byron legacy addresses apart from Shelley ones, and
+    /// `script` is true when the address is controlled by a script rather than a key.
     pub async fn addresses(&self, address: &str) -> BlockfrostResult<AddressContent> {
         self.call_endpoint(format!("/addresses/{}", address).as_str())
             .await
@@ -42,6 +51,28 @@ impl BlockfrostAPI {
         .await
     }
 
+    /// [`addresses_utxos`](Self::addresses_utxos) as [`Output`]s, without the `tx_hash` and
+    /// `output_index` locating each UTxO.
+    pub async fn addresses_utxos_outputs(
+        &self, address: &str, pagination: Pagination,
+    ) -> BlockfrostResult<Vec<Output>> {
+        let utxos = self.addresses_utxos(address, pagination).await?;
+
+        Ok(utxos.into_iter().map(Output::from).collect())
+    }
+
+    /// [`addresses_utxos_asset`](Self::addresses_utxos_asset) as [`Output`]s, without the
+    /// `tx_hash` and `output_index` locating each UTxO.
+    pub async fn addresses_utxos_asset_outputs(
+        &self, address: &str, asset: &str, pagination: Pagination,
+    ) -> BlockfrostResult<Vec<Output>> {
+        let utxos = self
+            .addresses_utxos_asset(address, asset, pagination)
+            .await?;
+
+        Ok(utxos.into_iter().map(Output::from).collect())
+    }
+
     pub async fn addresses_transactions(
         &self, address: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<AddressTransactionsContentInner>> {
@@ -51,13 +82,382 @@ impl BlockfrostAPI {
         )
         .await
     }
+
+    /// Wait for a new UTxO of at least `min_lovelace` Lovelace at `address`, checking its UTxOs
+    /// every `poll_interval`.
+    ///
+    /// UTxOs already at the address on the first check are ignored, so only deposits made after
+    /// the call are returned. An address that was never used counts as having no UTxOs. Every
+    /// check goes through the rate limiter like other requests, after `timeout` this fails with
+    /// [`BlockfrostError::Timeout`]. Time is kept by the [`Clock`] of the settings.
+    pub async fn addresses_wait_for_payment(
+        &self, address: &str, min_lovelace: u64, poll_interval: Duration, timeout: Duration,
+    ) -> BlockfrostResult<AddressUtxoContentInner> {
+        let clock = &self.settings.clock;
+        let deadline = clock.now() + timeout;
+        let mut seen: Option<HashSet<(String, i32)>> = None;
+
+        loop {
+            let utxos = Lister::<AddressUtxoContentInner>::new(
+                self,
+                format!("/addresses/{}/utxos", address),
+            )
+            .collect_all()
+            .await;
+            let utxos = match utxos {
+                Err(error) if error.is_not_found() => Vec::new(),
+                utxos => utxos?,
+            };
+
+            match &mut seen {
+                None => {
+                    seen = Some(utxos.iter().map(utxo_id).collect());
+                }
+                Some(seen) => {
+                    for utxo in utxos {
+                        if !seen.insert(utxo_id(&utxo)) {
+                            continue;
+                        }
+                        let lovelace =
+                            utxo.amount
+                                .lovelace()
+                                .map_err(|error| BlockfrostError::Parsing {
+                                    message: error.to_string(),
+                                })?;
+                        if lovelace >= min_lovelace {
+                            return Ok(utxo);
+                        }
+                    }
+                }
+            }
+
+            let now = clock.now();
+            if now >= deadline {
+                return Err(BlockfrostError::Timeout {
+                    message: format!(
+                        "no payment of at least {} Lovelace to {} within {:?}",
+                        min_lovelace, address, timeout
+                    ),
+                });
+            }
+            clock.sleep(poll_interval.min(deadline - now)).await;
+        }
+    }
+}
+
+fn utxo_id(utxo: &AddressUtxoContentInner) -> (String, i32) {
+    (utxo.tx_hash.clone(), utxo.output_index)
+}
+
+/// Lookups in the `amount` lists of outputs, UTXOs and totals, one entry per unit.
+///
+/// Implemented for slices, so it works on any `Vec<TxContentOutputAmountInner>` too. Like
+/// [`balance`](Self::balance), every lookup fails on a quantity that is not an unsigned integer
+/// and on a sum that overflows, instead of counting it as 0 or wrapping around.
+pub trait AmountsExt {
+    /// Lovelace in the amounts, 0 if there is none.
+    fn lovelace(&self) -> Result<u64, BalanceError>;
+
+    /// Every native asset, as its unit (policy ID followed by the hex asset name) and quantity.
+    fn assets(&self) -> impl Iterator<Item = Result<(&str, u128), BalanceError>>;
+
+    /// Quantity of `unit`, summed if it appears more than once, 0 if it is absent.
+    fn total_asset(&self, unit: &str) -> Result<u128, BalanceError>;
+
+    /// Quantity of every unit, see [`add_to_balance`](Self::add_to_balance).
+    fn balance(&self) -> Result<Balance, BalanceError> {
+        let mut balance = Balance::new();
+        self.add_to_balance(&mut balance)?;
+        Ok(balance)
+    }
+
+    /// Add these amounts to `balance`, to sum the amounts of many outputs or UTXOs.
+    ///
+    /// Fails instead of wrapping around if a unit overflows `u128`, or if a quantity is not an
+    /// unsigned integer. `balance` may then be partially updated.
+    fn add_to_balance(&self, balance: &mut Balance) -> Result<(), BalanceError>;
+}
+
+impl AmountsExt for [TxContentOutputAmountInner] {
+    fn lovelace(&self) -> Result<u64, BalanceError> {
+        let lovelace = self.total_asset("lovelace")?;
+
+        u64::try_from(lovelace).map_err(|_| BalanceError::Overflow {
+            unit: "lovelace".to_string(),
+        })
+    }
+
+    fn assets(&self) -> impl Iterator<Item = Result<(&str, u128), BalanceError>> {
+        self.iter()
+            .filter(|amount| amount.unit != "lovelace")
+            .map(|amount| Ok((amount.unit.as_str(), parse_quantity(amount)?)))
+    }
+
+    fn total_asset(&self, unit: &str) -> Result<u128, BalanceError> {
+        self.iter()
+            .filter(|amount| amount.unit == unit)
+            .try_fold(0u128, |total, amount| {
+                total
+                    .checked_add(parse_quantity(amount)?)
+                    .ok_or_else(|| BalanceError::Overflow {
+                        unit: unit.to_string(),
+                    })
+            })
+    }
+
+    fn add_to_balance(&self, balance: &mut Balance) -> Result<(), BalanceError> {
+        for amount in self {
+            let quantity = parse_quantity(amount)?;
+            let total = balance.entry(amount.unit.clone()).or_default();
+
+            *total = total
+                .checked_add(quantity)
+                .ok_or_else(|| BalanceError::Overflow {
+                    unit: amount.unit.clone(),
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// Display of one entry of an `amount` list.
+pub trait AmountExt {
+    /// Quantity in display units with `decimals`, so `"1000000"` with 6 decimals is
+    /// `"1.000000"`, see [`format_asset_amount`].
+    ///
+    /// Fails like [`AmountsExt`] if the quantity is not an unsigned integer.
+    fn display_with(&self, decimals: u8) -> Result<String, BalanceError>;
+}
+
+impl AmountExt for TxContentOutputAmountInner {
+    fn display_with(&self, decimals: u8) -> Result<String, BalanceError> {
+        Ok(format_asset_amount(parse_quantity(self)?, decimals))
+    }
+}
+
+fn parse_quantity(amount: &TxContentOutputAmountInner) -> Result<u128, BalanceError> {
+    amount
+        .quantity
+        .parse()
+        .map_err(|_| BalanceError::InvalidQuantity {
+            unit: amount.unit.clone(),
+            quantity: amount.quantity.clone(),
+        })
+}
+
+/// Quantity of each unit, built by [`AmountsExt::balance`].
+pub type Balance = HashMap<String, u128>;
+
+/// Error of the [`Balance`] arithmetic, which never wraps around.
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BalanceError {
+    #[error("quantity of {unit} does not fit in the balance")]
+    Overflow { unit: String },
+    #[error("invalid quantity '{quantity}' of {unit}")]
+    InvalidQuantity { unit: String, quantity: String },
+}
+
+/// Change of each unit from `before` to `after` (`after - before`), for reconciliation.
+///
+/// Units with the same quantity on both sides are left out. Fails if a change does not fit in
+/// an `i128`.
+pub fn balance_diff(
+    before: &Balance, after: &Balance,
+) -> Result<HashMap<String, i128>, BalanceError> {
+    let units: HashSet<&String> = before.keys().chain(after.keys()).collect();
+    let mut diff = HashMap::new();
+
+    for unit in units {
+        let quantity = |balance: &Balance| balance.get(unit).copied().unwrap_or_default();
+        let (before, after) = (quantity(before), quantity(after));
+        let change = if after >= before {
+            i128::try_from(after - before).ok()
+        } else {
+            i128::try_from(before - after).ok().map(|change| -change)
+        };
+
+        match change {
+            Some(0) => {}
+            Some(change) => {
+                diff.insert(unit.clone(), change);
+            }
+            None => return Err(BalanceError::Overflow { unit: unit.clone() }),
+        }
+    }
+    Ok(diff)
+}
+
+/// Net flow helpers for [`addresses_total`](BlockfrostAPI::addresses_total).
+pub trait AddressContentTotalExt {
+    /// Lovelace received minus Lovelace sent.
+    fn net_ada(&self) -> i128 {
+        self.net_quantity("lovelace")
+    }
+
+    /// Quantity of `unit` received minus quantity sent.
+    fn net_quantity(&self, unit: &str) -> i128;
+}
+
+impl AddressContentTotalExt for AddressContentTotal {
+    fn net_quantity(&self, unit: &str) -> i128 {
+        // Quantities are unsigned integers, at most 2^64 - 1 each
+        let sum = |amounts: &[TxContentOutputAmountInner]| -> i128 {
+            amounts
+                .iter()
+                .filter(|amount| amount.unit == unit)
+                .filter_map(|amount| amount.quantity.parse::<i128>().ok())
+                .sum()
+        };
+
+        sum(&self.received_sum) - sum(&self.sent_sum)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::{MockResponse, MockServer};
     use blockfrost_openapi::models::tx_content_output_amount_inner::TxContentOutputAmountInner;
+    use futures::future::BoxFuture;
     use serde_json::json;
+    use std::{
+        sync::{Arc, Mutex},
+        time::Instant,
+    };
+
+    #[test]
+    fn test_amounts() {
+        let nutcoin = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e";
+        let hosky = "a0028f350aaabe0545fdcb56b039bfb08e4bb4d8c4d7c3c7d481c235484f534b59";
+        let amounts: Vec<TxContentOutputAmountInner> = serde_json::from_value(json!([
+            { "unit": "lovelace", "quantity": "1444443" },
+            { "unit": nutcoin, "quantity": "12" },
+            { "unit": hosky, "quantity": "340282366920938463463374607431768211455" }
+        ]))
+        .unwrap();
+
+        assert_eq!(amounts.lovelace(), Ok(1444443));
+        assert_eq!(
+            amounts.assets().collect::<Result<Vec<_>, _>>(),
+            Ok(vec![(nutcoin, 12), (hosky, u128::MAX)])
+        );
+        assert_eq!(amounts.total_asset(nutcoin), Ok(12));
+        assert_eq!(amounts.total_asset("lovelace"), Ok(1444443));
+        assert_eq!(amounts.total_asset(&nutcoin[..56]), Ok(0));
+        assert_eq!(amounts[2..].lovelace(), Ok(0));
+    }
+
+    #[test]
+    fn test_amount_display_with() {
+        let amount = |quantity: &str| TxContentOutputAmountInner {
+            unit: "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e".into(),
+            quantity: quantity.into(),
+        };
+
+        assert_eq!(
+            amount("1000000").display_with(6),
+            Ok("1.000000".to_string())
+        );
+        assert_eq!(amount("12345").display_with(2), Ok("123.45".to_string()));
+        assert_eq!(amount("1").display_with(0), Ok("1".to_string()));
+        assert!(amount("1.5").display_with(6).is_err());
+    }
+
+    #[test]
+    fn test_amounts_invalid() {
+        let nutcoin = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e";
+        let amounts: Vec<TxContentOutputAmountInner> = serde_json::from_value(json!([
+            { "unit": "lovelace", "quantity": "18446744073709551615" },
+            { "unit": "lovelace", "quantity": "1" },
+            { "unit": nutcoin, "quantity": "-12" }
+        ]))
+        .unwrap();
+        let invalid = BalanceError::InvalidQuantity {
+            unit: nutcoin.to_string(),
+            quantity: "-12".to_string(),
+        };
+
+        assert_eq!(
+            amounts.lovelace(),
+            Err(BalanceError::Overflow {
+                unit: "lovelace".to_string()
+            })
+        );
+        assert_eq!(amounts[1..].lovelace(), Ok(1));
+        assert_eq!(amounts.assets().next(), Some(Err(invalid.clone())));
+        assert_eq!(amounts.total_asset(nutcoin), Err(invalid));
+    }
+
+    #[test]
+    fn test_balance() {
+        let amounts = |quantities: &[(&str, String)]| -> Vec<TxContentOutputAmountInner> {
+            quantities
+                .iter()
+                .map(|(unit, quantity)| TxContentOutputAmountInner {
+                    unit: unit.to_string(),
+                    quantity: quantity.clone(),
+                })
+                .collect()
+        };
+        let near_max = (u128::MAX - 1).to_string();
+
+        let mut balance = amounts(&[("lovelace", "5".into()), ("nutcoin", near_max.clone())])
+            .balance()
+            .unwrap();
+        amounts(&[("nutcoin", "1".into()), ("lovelace", "2".into())])
+            .add_to_balance(&mut balance)
+            .unwrap();
+        assert_eq!(balance["lovelace"], 7);
+        assert_eq!(balance["nutcoin"], u128::MAX);
+
+        assert_eq!(
+            amounts(&[("nutcoin", "1".into())]).add_to_balance(&mut balance),
+            Err(BalanceError::Overflow {
+                unit: "nutcoin".into()
+            })
+        );
+        assert_eq!(
+            amounts(&[("lovelace", "-1".into())]).balance(),
+            Err(BalanceError::InvalidQuantity {
+                unit: "lovelace".into(),
+                quantity: "-1".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_balance_diff() {
+        let before = Balance::from([
+            ("lovelace".to_string(), 10),
+            ("nutcoin".to_string(), 3),
+            ("hosky".to_string(), 1),
+        ]);
+        let after = Balance::from([
+            ("lovelace".to_string(), 4),
+            ("nutcoin".to_string(), 3),
+            ("sundae".to_string(), 8),
+        ]);
+
+        let diff = balance_diff(&before, &after).unwrap();
+
+        assert_eq!(
+            diff,
+            HashMap::from([
+                ("lovelace".to_string(), -6),
+                ("hosky".to_string(), -1),
+                ("sundae".to_string(), 8),
+            ])
+        );
+        assert_eq!(
+            balance_diff(
+                &Balance::new(),
+                &Balance::from([("nutcoin".into(), u128::MAX)])
+            ),
+            Err(BalanceError::Overflow {
+                unit: "nutcoin".into()
+            })
+        );
+    }
 
     #[tokio::test]
     async fn test_address() {
@@ -77,7 +477,46 @@ mod tests {
             "type": "shelley",
             "script": false
         });
-        serde_json::from_value::<AddressContent>(json_value).unwrap();
+        let address = serde_json::from_value::<AddressContent>(json_value).unwrap();
+
+        assert_eq!(address.r#type, AddressType::Shelley);
+        assert_eq!(address.amount.len(), 2);
+        assert!(address.stake_address.is_some());
+        assert!(!address.script);
+    }
+
+    #[test]
+    fn test_address_byron() {
+        let json_value = json!({
+            "address": "Ae2tdPwUPEZ6RPFwbZVchpBHqrJSUaJEtbHGBMxnh9yDbL55C1MaBbi22Dy",
+            "amount": [
+                {
+                    "unit": "lovelace",
+                    "quantity": "1000000"
+                }
+            ],
+            "stake_address": null,
+            "type": "byron",
+            "script": false
+        });
+        let address = serde_json::from_value::<AddressContent>(json_value).unwrap();
+
+        assert_eq!(address.r#type, AddressType::Byron);
+        assert_eq!(address.stake_address, None);
+    }
+
+    #[test]
+    fn test_address_script() {
+        let json_value = json!({
+            "address": "addr1w8433zk2shufk42hn4x7zznjjuqwwyfmxffcjszw5l2ulesdt3jff",
+            "amount": [],
+            "stake_address": null,
+            "type": "shelley",
+            "script": true
+        });
+        let address = serde_json::from_value::<AddressContent>(json_value).unwrap();
+
+        assert!(address.script);
     }
 
     #[tokio::test]
@@ -107,7 +546,33 @@ mod tests {
             "tx_count": 12
         });
 
-        serde_json::from_value::<AddressContentTotal>(json_value).unwrap();
+        let total = serde_json::from_value::<AddressContentTotal>(json_value).unwrap();
+
+        assert_eq!(total.tx_count, 12);
+        assert_eq!(total.net_ada(), 0);
+    }
+
+    #[test]
+    fn test_address_total_net_flow() {
+        let nutcoin = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e";
+        let json_value = json!({
+            "address": "addr1qxqs59lphg8g6qndelq8xwqn60ag3aeyfcp33c2kdp46a09re5df3pzwwmyq946axfcejy5n4x0y99wqpgtp2gd0k09qsgy6pz",
+            "received_sum": [
+                { "unit": "lovelace", "quantity": "42000000" },
+                { "unit": nutcoin, "quantity": "12" }
+            ],
+            "sent_sum": [
+                { "unit": "lovelace", "quantity": "50000000" },
+                { "unit": nutcoin, "quantity": "2" }
+            ],
+            "tx_count": 3
+        });
+
+        let total = serde_json::from_value::<AddressContentTotal>(json_value).unwrap();
+
+        assert_eq!(total.net_ada(), -8000000);
+        assert_eq!(total.net_quantity(nutcoin), 10);
+        assert_eq!(total.net_quantity("unknown"), 0);
     }
 
     #[tokio::test]
@@ -170,6 +635,33 @@ mod tests {
         serde_json::from_value::<Vec<AddressUtxoContentInner>>(json_value).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_addresses_utxos_outputs() {
+        let utxos = json!([{
+            "address": "addr1qxqs59lphg8g6qndelq8xwqn60ag3aeyfcp33c2kdp46a09re5df3pzwwmyq946axfcejy5n4x0y99wqpgtp2gd0k09qsgy6pz",
+            "tx_hash": "39a7a284c2a0948189dc45dec670211cd4d72f7b66c5726c08d9b3df11e44d58",
+            "tx_index": 0,
+            "output_index": 0,
+            "amount": [{ "unit": "lovelace", "quantity": "42000000" }],
+            "block": "7eb8e27d18686c7db9a18f8bbcfe34e3fed6e047afaa2d969904d15e934847e6",
+            "data_hash": null,
+            "inline_datum": "0x68656c6c6f",
+            "reference_script_hash": null
+        }]);
+        let server = MockServer::start(vec![MockResponse::new(200, utxos.to_string())]);
+
+        let outputs = server
+            .api()
+            .addresses_utxos_outputs("addr1", Pagination::default())
+            .await
+            .unwrap();
+
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].inline_datum.as_deref(), Some("0x68656c6c6f"));
+        assert_eq!(outputs[0].amount.lovelace(), Ok(42000000));
+        assert!(!outputs[0].collateral);
+    }
+
     #[tokio::test]
     async fn test_address_transaction() {
         let json_value = json!([
@@ -204,4 +696,106 @@ mod tests {
 
         serde_json::from_value::<TxContentOutputAmountInner>(json_value).unwrap();
     }
+
+    fn utxo(tx_hash: &str, lovelace: u64) -> JsonValue {
+        json!({
+            "address": "addr1qxqs59lphg8g6qndelq8xwqn60ag3aeyfcp33c2kdp46a09re5df3pzwwmyq946axfcejy5n4x0y99wqpgtp2gd0k09qsgy6pz",
+            "tx_hash": tx_hash,
+            "tx_index": 0,
+            "output_index": 0,
+            "amount": [{ "unit": "lovelace", "quantity": lovelace.to_string() }],
+            "block": "7eb8e27d18686c7db9a18f8bbcfe34e3fed6e047afaa2d969904d15e934847e6",
+            "data_hash": null,
+            "inline_datum": null,
+            "reference_script_hash": null
+        })
+    }
+
+    #[tokio::test]
+    async fn test_addresses_wait_for_payment() {
+        let old = utxo(
+            "39a7a284c2a0948189dc45dec670211cd4d72f7b66c5726c08d9b3df11e44d58",
+            50_000_000,
+        );
+        let small = utxo("a1b2c3", 1_000_000);
+        let big = utxo("d4e5f6", 5_000_000);
+        let server = MockServer::start(vec![
+            MockResponse::new(200, json!([old]).to_string()),
+            MockResponse::new(200, json!([old, small]).to_string()),
+            MockResponse::new(200, json!([old, small, big]).to_string()),
+        ]);
+        let api = server.api();
+
+        let payment = api
+            .addresses_wait_for_payment(
+                "addr1",
+                2_000_000,
+                Duration::from_millis(10),
+                Duration::from_secs(10),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(payment.tx_hash, "d4e5f6");
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_addresses_wait_for_payment_timeout() {
+        let not_found = json!({
+            "status_code": 404,
+            "error": "Not Found",
+            "message": "The requested component has not been found."
+        });
+        let server = MockServer::start(vec![MockResponse::new(404, not_found.to_string())]);
+        let api = server.api();
+
+        let result = api
+            .addresses_wait_for_payment(
+                "addr1",
+                1,
+                Duration::from_millis(10),
+                Duration::from_millis(50),
+            )
+            .await;
+
+        assert!(matches!(result, Err(BlockfrostError::Timeout { .. })));
+        assert!(server.requests().len() > 1);
+    }
+
+    // Clock whose sleeps only move its time forward
+    #[derive(Debug)]
+    struct ManualClock(Mutex<Instant>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+
+        fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+            *self.0.lock().unwrap() += duration;
+            Box::pin(async {})
+        }
+    }
+
+    #[tokio::test]
+    async fn test_addresses_wait_for_payment_with_clock() {
+        let server = MockServer::start(vec![MockResponse::new(200, "[]")]);
+        let mut settings = BlockFrostSettings::new();
+        settings.set_clock(Arc::new(ManualClock(Mutex::new(Instant::now()))));
+        let api = server.api_with_settings(settings);
+
+        let result = api
+            .addresses_wait_for_payment(
+                "addr1",
+                1,
+                Duration::from_secs(60),
+                Duration::from_secs(600),
+            )
+            .await;
+
+        assert!(matches!(result, Err(BlockfrostError::Timeout { .. })));
+        // A check at the start and after each of the 10 polls
+        assert_eq!(server.requests().len(), 11);
+    }
 }
@@ -1,9 +1,15 @@
-use crate::*;
+use crate::{utils::decode_hex, *};
 use blockfrost_openapi::models::{
     asset::Asset, asset_addresses_inner::AssetAddressesInner,
     asset_history_inner::AssetHistoryInner, asset_policy_inner::AssetPolicyInner,
     asset_transactions_inner::AssetTransactionsInner, assets_inner::AssetsInner,
+    tx_content::TxContent,
 };
+use futures::{stream, StreamExt, TryStreamExt};
+use std::collections::HashSet;
+
+// Assets fetched at a time by `assets_details_cached`
+const DETAILS_CONCURRENCY: usize = 8;
 
 impl BlockfrostAPI {
     pub async fn assets_by_id(&self, asset: &str) -> BlockfrostResult<Asset> {
@@ -11,6 +17,15 @@ impl BlockfrostAPI {
             .await
     }
 
+    /// The transaction that first minted `asset`, from its `initial_mint_tx_hash`, to trace
+    /// where an NFT comes from.
+    pub async fn assets_mint_transaction(&self, asset: &str) -> BlockfrostResult<TxContent> {
+        let details = self.assets_by_id(asset).await?;
+
+        self.transaction_by_hash(&details.initial_mint_tx_hash)
+            .await
+    }
+
     pub async fn assets(&self, pagination: Pagination) -> BlockfrostResult<Vec<AssetsInner>> {
         self.call_paged_endpoint("/assets", pagination).await
     }
@@ -42,16 +57,118 @@ impl BlockfrostAPI {
     pub async fn assets_policy_by_id(
         &self, policy_id: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<AssetPolicyInner>> {
+        self.check_hex("policy_id", policy_id, 28)?;
         self.call_paged_endpoint(format!("/assets/policy/{}", policy_id).as_str(), pagination)
             .await
     }
+
+    /// Stream every page of [`assets_policy_by_id`](Self::assets_policy_by_id).
+    pub fn assets_policy_by_id_all(&self, policy_id: &str) -> Lister<'_, AssetPolicyInner> {
+        Lister::new(self, format!("/assets/policy/{}", policy_id))
+    }
+
+    /// Details ([`assets_by_id`](Self::assets_by_id)) of every asset under a policy, fetching up
+    /// to `concurrency` assets at a time.
+    ///
+    /// This is one request per asset on top of the listing, large collections benefit from a
+    /// [`rate limit`](BlockFrostSettings::rate_limit).
+    pub async fn assets_policy_details(
+        &self, policy_id: &str, concurrency: usize,
+    ) -> BlockfrostResult<Vec<Asset>> {
+        let assets = self
+            .assets_policy_by_id_all(policy_id)
+            .collect_all()
+            .await?;
+
+        stream::iter(assets)
+            .map(|asset| async move { self.assets_by_id(&asset.asset).await })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await
+    }
+
+    /// Details of each asset in `units`, for example to render a portfolio.
+    ///
+    /// Units are fetched concurrently and only once each, so the result has one entry per unique
+    /// unit, in the order they first appear. Every entry carries its own result, a missing asset
+    /// does not fail the others.
+    pub async fn assets_details_cached(
+        &self, units: &[&str],
+    ) -> Vec<(String, BlockfrostResult<Asset>)> {
+        let mut seen = HashSet::new();
+        let units = units.iter().filter(|unit| seen.insert(**unit));
+
+        stream::iter(units)
+            .map(|unit| async move { (unit.to_string(), self.assets_by_id(unit).await) })
+            .buffered(DETAILS_CONCURRENCY)
+            .collect()
+            .await
+    }
+}
+
+/// Readable names for [`Asset`], whose `asset_name` is hex encoded.
+pub trait AssetExt {
+    /// The asset name decoded as UTF-8, `None` if it is missing, not valid hex or binary.
+    fn asset_name_utf8(&self) -> Option<String>;
+
+    /// The asset name decoded as UTF-8 with invalid sequences replaced by `U+FFFD`, empty if
+    /// the asset has no name.
+    fn asset_name_utf8_lossy(&self) -> String;
+
+    /// Decimals registered for the asset in the token registry, 0 if it has none.
+    fn decimals(&self) -> u8;
+
+    /// `quantity` of this asset with its registered decimals, see [`format_asset_amount`].
+    fn format_quantity(&self, quantity: u128) -> String {
+        format_asset_amount(quantity, self.decimals())
+    }
+}
+
+impl AssetExt for Asset {
+    fn asset_name_utf8(&self) -> Option<String> {
+        String::from_utf8(decode_hex(self.asset_name.as_deref()?)?).ok()
+    }
+
+    fn asset_name_utf8_lossy(&self) -> String {
+        let bytes = self.asset_name.as_deref().and_then(decode_hex);
+
+        String::from_utf8_lossy(&bytes.unwrap_or_default()).into_owned()
+    }
+
+    fn decimals(&self) -> u8 {
+        let decimals = self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.decimals);
+
+        decimals
+            .and_then(|decimals| u8::try_from(decimals).ok())
+            .unwrap_or(0)
+    }
+}
+
+/// Quantity of a fungible token in display units, `quantity` being in the smallest unit.
+///
+/// `1000000` with 6 decimals is `"1.000000"`, all the decimals are kept. With 0 decimals, as
+/// for NFTs, the quantity is a plain integer.
+pub fn format_asset_amount(quantity: u128, decimals: u8) -> String {
+    let decimals = usize::from(decimals);
+    if decimals == 0 {
+        return quantity.to_string();
+    }
+    let digits = format!("{:0>width$}", quantity, width = decimals + 1);
+    let (units, fraction) = digits.split_at(digits.len() - decimals);
+
+    format!("{}.{}", units, fraction)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::{MockResponse, MockServer};
     use blockfrost_openapi::models::{
-        asset::Asset, asset_transactions_inner::AssetTransactionsInner, assets_inner::AssetsInner,
+        asset::Asset, asset_metadata::AssetMetadata,
+        asset_transactions_inner::AssetTransactionsInner, assets_inner::AssetsInner,
     };
     use serde_json::json;
 
@@ -75,6 +192,56 @@ mod tests {
         serde_json::from_value::<Vec<AssetsInner>>(json_value).unwrap();
     }
 
+    #[test]
+    fn test_asset_name_utf8() {
+        let named = |asset_name: &str| Asset {
+            asset_name: Some(asset_name.to_string()),
+            ..Default::default()
+        };
+
+        let ascii = named("6e7574636f696e");
+        assert_eq!(ascii.asset_name_utf8().as_deref(), Some("nutcoin"));
+        assert_eq!(ascii.asset_name_utf8_lossy(), "nutcoin");
+
+        let unicode = named("f09f8c99e69c88");
+        assert_eq!(unicode.asset_name_utf8().as_deref(), Some("🌙月"));
+
+        let binary = named("000de1404e7574ff");
+        assert_eq!(binary.asset_name_utf8(), None);
+        assert_eq!(binary.asset_name_utf8_lossy(), "\0\r\u{fffd}@Nut\u{fffd}");
+
+        assert_eq!(named("6e7").asset_name_utf8(), None);
+        assert_eq!(Asset::default().asset_name_utf8_lossy(), "");
+    }
+
+    #[test]
+    fn test_format_asset_amount() {
+        assert_eq!(format_asset_amount(1000000, 6), "1.000000");
+        assert_eq!(format_asset_amount(1234567, 6), "1.234567");
+        assert_eq!(format_asset_amount(42, 6), "0.000042");
+        assert_eq!(format_asset_amount(0, 6), "0.000000");
+        assert_eq!(format_asset_amount(12345, 2), "123.45");
+        assert_eq!(format_asset_amount(7, 1), "0.7");
+        assert_eq!(format_asset_amount(1, 0), "1");
+        assert_eq!(format_asset_amount(0, 0), "0");
+        assert_eq!(
+            format_asset_amount(u128::MAX, 18),
+            "340282366920938463463.374607431768211455"
+        );
+
+        let with_decimals = |decimals| Asset {
+            metadata: Some(Box::new(AssetMetadata {
+                decimals,
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        assert_eq!(with_decimals(Some(6)).format_quantity(12000), "0.012000");
+        assert_eq!(with_decimals(None).format_quantity(12000), "12000");
+        assert_eq!(with_decimals(Some(-1)).decimals(), 0);
+        assert_eq!(Asset::default().format_quantity(1), "1");
+    }
+
     #[tokio::test]
     async fn test_asset_details() {
         let json_value = json!({
@@ -100,7 +267,39 @@ mod tests {
             }
         });
 
-        serde_json::from_value::<Asset>(json_value).unwrap();
+        let asset = serde_json::from_value::<Asset>(json_value).unwrap();
+
+        assert_eq!(
+            asset.initial_mint_tx_hash,
+            "6804edf9712d2b619edb6ac86861fe93a730693183a262b165fcc1ba1bc99cad"
+        );
+        assert_eq!(asset.mint_or_burn_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_assets_mint_transaction() {
+        let unit = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e";
+        let mint_tx = "6804edf9712d2b619edb6ac86861fe93a730693183a262b165fcc1ba1bc99cad";
+        let server = MockServer::start_with(move |_, request| {
+            let body = if request.starts_with("GET /assets/") {
+                serde_json::to_string(&Asset {
+                    asset: unit.to_string(),
+                    initial_mint_tx_hash: mint_tx.to_string(),
+                    ..Default::default()
+                })
+            } else {
+                serde_json::to_string(&TxContent {
+                    hash: mint_tx.to_string(),
+                    ..Default::default()
+                })
+            };
+            MockResponse::new(200, body.unwrap())
+        });
+
+        let transaction = server.api().assets_mint_transaction(unit).await.unwrap();
+
+        assert_eq!(transaction.hash, mint_tx);
+        assert!(server.requests()[1].starts_with(&format!("GET /txs/{} ", mint_tx)));
     }
 
     #[tokio::test]
@@ -191,4 +390,99 @@ mod tests {
 
         serde_json::from_value::<Vec<AssetPolicyInner>>(json_value).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_assets_policy_details() {
+        let policy_id = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a7";
+        let server = MockServer::start_with(move |_, request| {
+            let body = if request.contains("/assets/policy/") {
+                json!([
+                    { "asset": format!("{}01", policy_id), "quantity": "1" },
+                    { "asset": format!("{}02", policy_id), "quantity": "1" }
+                ])
+            } else {
+                let asset = request
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap()
+                    .trim_start_matches("/assets/");
+                json!({
+                    "asset": asset,
+                    "policy_id": policy_id,
+                    "asset_name": &asset[policy_id.len()..],
+                    "fingerprint": "asset1pkpwyknlvul7az0xx8czhl60pyel45rpje4z8w",
+                    "quantity": "1",
+                    "initial_mint_tx_hash": "6804edf9712d2b619edb6ac86861fe93a730693183a262b165fcc1ba1bc99cad",
+                    "mint_or_burn_count": 1,
+                    "onchain_metadata": null,
+                    "metadata": null
+                })
+            };
+            MockResponse::new(200, body.to_string())
+        });
+
+        let assets = server
+            .api()
+            .assets_policy_details(policy_id, 4)
+            .await
+            .unwrap();
+
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0].asset_name.as_deref(), Some("01"));
+        assert_eq!(assets[1].asset_name.as_deref(), Some("02"));
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_assets_details_cached() {
+        let policy_id = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a7";
+        let server = MockServer::start_with(move |_, request| {
+            let asset = request
+                .split_whitespace()
+                .nth(1)
+                .unwrap()
+                .trim_start_matches("/assets/");
+            if asset.ends_with("ff") {
+                let not_found = json!({
+                    "status_code": 404,
+                    "error": "Not Found",
+                    "message": "The requested component has not been found."
+                });
+                return MockResponse::new(404, not_found.to_string());
+            }
+            let body = json!({
+                "asset": asset,
+                "policy_id": policy_id,
+                "asset_name": &asset[policy_id.len()..],
+                "fingerprint": "asset1pkpwyknlvul7az0xx8czhl60pyel45rpje4z8w",
+                "quantity": "1",
+                "initial_mint_tx_hash": "6804edf9712d2b619edb6ac86861fe93a730693183a262b165fcc1ba1bc99cad",
+                "mint_or_burn_count": 1,
+                "onchain_metadata": null,
+                "metadata": null
+            });
+            MockResponse::new(200, body.to_string())
+        });
+        let first = format!("{}01", policy_id);
+        let missing = format!("{}ff", policy_id);
+        let second = format!("{}02", policy_id);
+
+        let details = server
+            .api()
+            .assets_details_cached(&[&first, &missing, &first, &second])
+            .await;
+
+        let units: Vec<_> = details.iter().map(|(unit, _)| unit.as_str()).collect();
+        assert_eq!(units, [&first, &missing, &second]);
+        assert_eq!(
+            details[0].1.as_ref().unwrap().asset_name.as_deref(),
+            Some("01")
+        );
+        assert!(matches!(
+            &details[1].1,
+            Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 404
+        ));
+        assert!(details[2].1.is_ok());
+        assert_eq!(server.requests().len(), 3);
+    }
 }
@@ -1,28 +1,72 @@
-use crate::*;
+use crate::{json_error, url::Url, *};
 use blockfrost_openapi::models::{
     block_content::BlockContent, block_content_addresses_inner::BlockContentAddressesInner,
+    tx_content::TxContent,
 };
+use futures::{future, stream, FutureExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    time::Duration,
+};
+
+// Transactions fetched at a time by `blocks_latest_transactions_full`
+const TXS_CONCURRENCY: usize = 8;
+
+// Blocks remembered by `blocks_follow` to find where a rollback lands, the security parameter
+// of the chain: no rollback goes deeper
+const FOLLOW_HISTORY: usize = 2160;
+
+// Largest page of `blocks_previous`, used by `recent_block_producers`
+const PREVIOUS_PAGE_SIZE: usize = 100;
 
 impl BlockfrostAPI {
     pub async fn blocks_latest(&self) -> BlockfrostResult<BlockContent> {
-        self.call_endpoint("/blocks/latest").await
+        self.call_block_endpoint("/blocks/latest").await
     }
 
+    /// Position of the chain tip, the latest block from [`blocks_latest`](Self::blocks_latest).
+    pub async fn tip(&self) -> BlockfrostResult<Tip> {
+        self.blocks_latest().await.map(Tip::from)
+    }
+
+    /// The current tip if it is a different block than `last`, for chain-follow loops.
+    ///
+    /// The new tip is usually higher, but can also be at the same height or lower after a
+    /// rollback.
+    pub async fn tip_changed_since(&self, last: &Tip) -> BlockfrostResult<Option<Tip>> {
+        let tip = self.tip().await?;
+
+        Ok((tip.hash != last.hash).then_some(tip))
+    }
+
+    /// Block by hash or number.
+    ///
+    /// Byron epoch boundary blocks have no `height`, `slot` or `epoch_slot`, keep that in mind
+    /// when scanning from genesis.
+    ///
+    /// Like every method returning blocks, `output`, `fees` and `block_vrf` are read leniently:
+    /// a number is kept in its string form and any other unexpected value becomes `None`, with a
+    /// warning on stderr unless [`warn_lenient_fields`](BlockFrostSettings::warn_lenient_fields)
+    /// is off, instead of failing the whole block.
     pub async fn blocks_by_id(&self, hash_or_number: &str) -> BlockfrostResult<BlockContent> {
-        self.call_endpoint(format!("/blocks/{}", hash_or_number).as_str())
+        self.call_block_endpoint(format!("/blocks/{}", hash_or_number).as_str())
             .await
     }
 
     pub async fn blocks_slot(&self, slot_number: i64) -> BlockfrostResult<BlockContent> {
-        self.call_endpoint(format!("/blocks/slot/{}", slot_number).as_str())
+        self.call_block_endpoint(format!("/blocks/slot/{}", slot_number).as_str())
             .await
     }
 
     pub async fn blocks_by_epoch_and_slot(
         &self, epoch_number: i32, slot_number: i64,
     ) -> BlockfrostResult<BlockContent> {
-        self.call_endpoint(format!("/blocks/epoch/{}/slot/{}", epoch_number, slot_number).as_str())
-            .await
+        self.call_block_endpoint(
+            format!("/blocks/epoch/{}/slot/{}", epoch_number, slot_number).as_str(),
+        )
+        .await
     }
 
     pub async fn blocks_latest_txs(&self, pagination: Pagination) -> BlockfrostResult<Vec<String>> {
@@ -30,10 +74,208 @@ impl BlockfrostAPI {
             .await
     }
 
+    /// Details ([`transaction_by_hash`](Self::transaction_by_hash)) of every transaction in the
+    /// latest block, in block order.
+    ///
+    /// The tip is read once and the transactions of that block are then listed by its hash, so
+    /// they always belong to the returned block even if a new block arrives between the calls.
+    /// That block may however no longer be the tip by the time this returns.
+    ///
+    /// Transactions are fetched concurrently, each going through the
+    /// [`rate limit`](BlockFrostSettings::rate_limit). Every entry carries its own result, one
+    /// failed transaction does not fail the others.
+    pub async fn blocks_latest_transactions_full(
+        &self,
+    ) -> BlockfrostResult<(BlockContent, Vec<(String, BlockfrostResult<TxContent>)>)> {
+        let block = self.blocks_latest().await?;
+        let hashes = Lister::<String>::new(self, format!("/blocks/{}/txs", block.hash))
+            .collect_all()
+            .await?;
+
+        let transactions = stream::iter(hashes)
+            .map(|hash| async move {
+                let transaction = self.transaction_by_hash(&hash).await;
+                (hash, transaction)
+            })
+            .buffered(TXS_CONCURRENCY)
+            .collect()
+            .await;
+
+        Ok((block, transactions))
+    }
+
+    /// Every block with a `time` from `start` to `end` (UNIX seconds, both included), in
+    /// ascending order.
+    ///
+    /// The first block is found by a binary search over block heights, about 25 requests on
+    /// mainnet, the listing then follows [`blocks_next`](Self::blocks_next) from the block just
+    /// before it and stops at the first block past `end`. The listing is empty when the chain
+    /// has no block at or after `start`.
+    pub async fn blocks_in_time_range(
+        &self, start: i64, end: i64,
+    ) -> BlockfrostResult<Lister<'_, BlockContent>> {
+        let prefetch = self.settings.prefetch;
+        let empty = || Lister::from_fn(1, |_| async { Ok(Vec::new()) }.boxed());
+
+        let tip = self.blocks_latest().await?;
+        let Some(tip_height) = tip.height else {
+            return Ok(empty());
+        };
+        if (tip.time as i64) < start || start > end {
+            return Ok(empty());
+        }
+
+        // First height whose block is at or after `start`
+        let (mut low, mut high) = (1, tip_height);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let block = self.blocks_by_id(&middle.to_string()).await?;
+            if (block.time as i64) < start {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        let first = self.blocks_by_id(&low.to_string()).await?;
+        let Some(anchor) = first.previous_block else {
+            return Ok(empty());
+        };
+
+        Ok(Lister::from_fn(prefetch, move |pagination| {
+            let anchor = anchor.clone();
+            async move {
+                let mut blocks = self.blocks_next(&anchor, pagination).await?;
+                blocks.retain(|block| block.time as i64 <= end);
+                Ok(blocks)
+            }
+            .boxed()
+        }))
+    }
+
+    /// Follow the chain forward from the block `from_hash`, yielding every block after it and
+    /// the rollbacks that happen meanwhile. Never ends on its own: at the tip the next blocks
+    /// are polled every `poll_interval`, waiting on the [`Clock`] of the settings.
+    ///
+    /// A rollback is noticed when the last followed block is no longer found (Blockfrost drops
+    /// blocks that left the chain). The stream then walks back through the blocks it yielded,
+    /// emits [`ChainEvent::Rollback`] with the newest one still on chain and continues forward
+    /// on the new fork. Consumers should undo every block after that hash.
+    ///
+    /// The stream ends right after yielding an error, restart it from the last block handled.
+    pub fn blocks_follow(
+        &self, from_hash: &str, poll_interval: Duration,
+    ) -> impl Stream<Item = BlockfrostResult<ChainEvent>> + '_ {
+        let follower = Follower {
+            history: VecDeque::from([from_hash.to_string()]),
+            pending: VecDeque::new(),
+            finished: false,
+        };
+
+        stream::unfold(follower, move |mut follower| async move {
+            loop {
+                if let Some(event) = follower.pending.pop_front() {
+                    return Some((Ok(event), follower));
+                }
+                if follower.finished {
+                    return None;
+                }
+                if let Err(error) = self.follow_step(&mut follower, poll_interval).await {
+                    follower.finished = true;
+                    return Some((Err(error), follower));
+                }
+            }
+        })
+    }
+
+    /// [`blocks_follow`](Self::blocks_follow) along with the hashes of the transactions of each
+    /// block, empty for rollbacks.
+    ///
+    /// Costs one more request per block with transactions, one per 100 transactions. They are
+    /// sent for up to 8 blocks at once, ahead of the consumer, and go through the rate limiter
+    /// like any other request. As with `blocks_follow`, the stream ends after an error.
+    pub fn blocks_follow_with_txs(
+        &self, from_hash: &str, poll_interval: Duration,
+    ) -> impl Stream<Item = BlockfrostResult<(ChainEvent, Vec<String>)>> + '_ {
+        self.blocks_follow(from_hash, poll_interval)
+            .map(move |event| async move {
+                let event = event?;
+                let hashes = match &event {
+                    ChainEvent::RollForward(block) if block.tx_count > 0 => {
+                        Lister::<String>::new(self, format!("/blocks/{}/txs", block.hash))
+                            .collect_all()
+                            .await?
+                    }
+                    _ => Vec::new(),
+                };
+                Ok((event, hashes))
+            })
+            .buffered(TXS_CONCURRENCY)
+            .scan(false, |failed, item: BlockfrostResult<_>| {
+                let item = (!*failed).then_some(item);
+                *failed = matches!(item, Some(Err(_)));
+                future::ready(item)
+            })
+    }
+
+    // Queue the blocks after the last followed one, or the rollback if it left the chain
+    async fn follow_step(
+        &self, follower: &mut Follower, poll_interval: Duration,
+    ) -> BlockfrostResult<()> {
+        let last = follower.history.back().cloned().unwrap_or_default();
+
+        let blocks = match self.blocks_next(&last, Pagination::default()).await {
+            Ok(blocks) => blocks,
+            Err(error) if error.is_not_found() => {
+                return self.follow_rollback(follower, error).await
+            }
+            Err(error) => return Err(error),
+        };
+        if blocks.is_empty() {
+            self.settings.clock.sleep(poll_interval).await;
+        }
+
+        for block in blocks {
+            follower.history.push_back(block.hash.clone());
+            if follower.history.len() > FOLLOW_HISTORY {
+                follower.history.pop_front();
+            }
+            follower
+                .pending
+                .push_back(ChainEvent::RollForward(Box::new(block)));
+        }
+        Ok(())
+    }
+
+    // Drop followed blocks until one is still on chain, failing with `error` if none is
+    async fn follow_rollback(
+        &self, follower: &mut Follower, mut error: BlockfrostError,
+    ) -> BlockfrostResult<()> {
+        follower.history.pop_back();
+
+        while let Some(hash) = follower.history.back() {
+            match self.blocks_by_id(hash).await {
+                Ok(_) => {
+                    follower
+                        .pending
+                        .push_back(ChainEvent::Rollback(hash.clone()));
+                    return Ok(());
+                }
+                Err(not_found) if not_found.is_not_found() => {
+                    follower.history.pop_back();
+                    error = not_found;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Err(error)
+    }
+
     pub async fn blocks_next(
         &self, hash_or_number: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<BlockContent>> {
-        self.call_paged_endpoint(
+        self.call_blocks_endpoint(
             format!("/blocks/{}/next", hash_or_number).as_str(),
             pagination,
         )
@@ -43,13 +285,79 @@ impl BlockfrostAPI {
     pub async fn blocks_previous(
         &self, hash_or_number: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<BlockContent>> {
-        self.call_paged_endpoint(
+        self.call_blocks_endpoint(
             format!("/blocks/{}/previous", hash_or_number).as_str(),
             pagination,
         )
         .await
     }
 
+    /// Number of blocks minted by each pool in the last `n` blocks, the tip included, most
+    /// blocks first.
+    ///
+    /// Blocks without a pool slot leader (Byron genesis delegates, epoch boundary blocks) are
+    /// left out of the tally. Fetches the tip then `n / 100` pages of
+    /// [`blocks_previous`](Self::blocks_previous).
+    pub async fn recent_block_producers(&self, n: usize) -> BlockfrostResult<Vec<(String, usize)>> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        let tip = self.blocks_latest().await?;
+        let mut blocks = vec![tip.typed_slot_leader()];
+
+        for page in 1.. {
+            if blocks.len() >= n {
+                break;
+            }
+            let previous = self
+                .blocks_previous(
+                    &tip.hash,
+                    Pagination::new(Order::Asc, page, PREVIOUS_PAGE_SIZE),
+                )
+                .await?;
+            let is_last = previous.len() < PREVIOUS_PAGE_SIZE;
+
+            // Each page is in ascending order, the block closest to the tip last
+
+            blocks.extend(
+                previous
+                    .iter()
+                    .rev()
+                    .take(n - blocks.len())
+                    .map(BlockContentExt::typed_slot_leader),
+            );
+            if is_last {
+                break;
+            }
+        }
+
+        Ok(tally_producers(&blocks))
+    }
+
+    // `call_endpoint` for a block, see `LENIENT_BLOCK_FIELDS`
+    async fn call_block_endpoint(&self, endpoint: &str) -> BlockfrostResult<BlockContent> {
+        let block: JsonValue = self.call_endpoint(endpoint).await?;
+
+        parse_block(
+            &Url::from_endpoint(&self.base_url, endpoint)?,
+            block,
+            self.settings.warn_lenient_fields,
+        )
+    }
+
+    // `call_paged_endpoint` for blocks, see `LENIENT_BLOCK_FIELDS`
+    async fn call_blocks_endpoint(
+        &self, endpoint: &str, pagination: Pagination,
+    ) -> BlockfrostResult<Vec<BlockContent>> {
+        let blocks: Vec<JsonValue> = self.call_paged_endpoint(endpoint, pagination).await?;
+        let url = Url::from_endpoint(&self.base_url, endpoint)?;
+
+        blocks
+            .into_iter()
+            .map(|block| parse_block(&url, block, self.settings.warn_lenient_fields))
+            .collect()
+    }
+
     pub async fn blocks_txs(
         &self, hash_or_number: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<String>> {
@@ -71,11 +379,542 @@ impl BlockfrostAPI {
     }
 }
 
+/// Chain tip, created by [`tip`](BlockfrostAPI::tip).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct Tip {
+    /// Hash of the latest block.
+    pub hash: String,
+    /// Block number.
+    pub height: Option<i32>,
+    /// Slot number.
+    pub slot: Option<i32>,
+    /// Epoch number.
+    pub epoch: Option<i32>,
+    /// Unix time of the block.
+    pub block_time: i32,
+}
+
+impl From<BlockContent> for Tip {
+    fn from(block: BlockContent) -> Self {
+        Self {
+            hash: block.hash,
+            height: block.height,
+            slot: block.slot,
+            epoch: block.epoch,
+            block_time: block.time,
+        }
+    }
+}
+
+// Fields of `BlockContent` the API has sent in different shapes over time. Numbers are read as
+// their string form, anything else than a string or null becomes null with a warning, instead of
+// failing the whole block. The warning follows `warn_lenient_fields`.
+const LENIENT_BLOCK_FIELDS: [&str; 3] = ["output", "fees", "block_vrf"];
+
+fn parse_block(url: &str, mut block: JsonValue, warn: bool) -> BlockfrostResult<BlockContent> {
+    for warning in coerce_block_fields(&mut block) {
+        if warn {
+            eprintln!("Warning: {} in block from {}, read as null.", warning, url);
+        }
+    }
+
+    BlockContent::deserialize(&block).map_err(|reason| json_error(url, &block, reason))
+}
+
+// Rewrites `LENIENT_BLOCK_FIELDS` in place, returns a warning for each value replaced by null
+fn coerce_block_fields(block: &mut JsonValue) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if let Some(fields) = block.as_object_mut() {
+        for name in LENIENT_BLOCK_FIELDS {
+            let value = fields.entry(name).or_insert(JsonValue::Null);
+            match value {
+                JsonValue::Null | JsonValue::String(_) => {}
+                JsonValue::Number(number) => *value = JsonValue::String(number.to_string()),
+                _ => {
+                    warnings.push(format!("unexpected {} {}", name, value));
+                    *value = JsonValue::Null;
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Change of the chain seen by [`blocks_follow`](BlockfrostAPI::blocks_follow).
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainEvent {
+    /// Next block on the chain.
+    RollForward(Box<BlockContent>),
+    /// The chain was rolled back to the block with this hash, the blocks yielded after it are
+    /// no longer on chain.
+    Rollback(String),
+}
+
+struct Follower {
+    // Hashes of the followed blocks, newest last
+    history: VecDeque<String>,
+    pending: VecDeque<ChainEvent>,
+    finished: bool,
+}
+
+// Blocks per pool, most blocks first then by pool ID
+fn tally_producers(leaders: &[SlotLeader]) -> Vec<(String, usize)> {
+    let mut tally = HashMap::<&str, usize>::new();
+    for pool_id in leaders.iter().filter_map(SlotLeader::pool_id) {
+        *tally.entry(pool_id).or_default() += 1;
+    }
+
+    let mut producers: Vec<_> = tally
+        .into_iter()
+        .map(|(pool_id, blocks)| (pool_id.to_string(), blocks))
+        .collect();
+    producers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    producers
+}
+
+/// Typed accessors for [`BlockContent`].
+pub trait BlockContentExt {
+    /// `slot_leader` classified as a pool or a description, the raw string stays in the field.
+    fn typed_slot_leader(&self) -> SlotLeader;
+
+    /// Confirmations against a tip fetched once, `tip_height - height`, instead of the
+    /// `confirmations` the server computed when this block was fetched.
+    ///
+    /// Blocks without a height (Byron epoch boundary blocks) keep the server value.
+    fn confirmations_at(&self, tip_height: i128) -> i128;
+
+    /// `op_cert_counter` as a number, the issue number of the operational certificate that
+    /// signed the block. It only goes up, a pool producing blocks with an older counter has a
+    /// stale or duplicate block producer.
+    ///
+    /// `None` for blocks without an operational certificate (Byron) or an unparsable counter.
+    fn typed_op_cert_counter(&self) -> Option<Integer>;
+}
+
+impl BlockContentExt for BlockContent {
+    fn typed_slot_leader(&self) -> SlotLeader {
+        SlotLeader::from(self.slot_leader.clone())
+    }
+
+    fn confirmations_at(&self, tip_height: i128) -> i128 {
+        match self.height {
+            Some(height) => tip_height - height as i128,
+            None => self.confirmations as i128,
+        }
+    }
+
+    fn typed_op_cert_counter(&self) -> Option<Integer> {
+        self.op_cert_counter.as_deref()?.parse().ok()
+    }
+}
+
+/// Creator of a block.
+///
+/// Serializes back to the raw string sent by the API.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum SlotLeader {
+    /// Bech32 id of the stake pool that minted the block.
+    Pool(String),
+    /// Any other leader, like `"Genesis slot leader"`, `"Epoch boundary slot leader"` or the
+    /// Byron genesis delegates.
+    Description(String),
+}
+
+impl SlotLeader {
+    /// The raw string sent by the API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            SlotLeader::Pool(leader) | SlotLeader::Description(leader) => leader,
+        }
+    }
+
+    /// Pool id, when the block was minted by a stake pool.
+    pub fn pool_id(&self) -> Option<&str> {
+        match self {
+            SlotLeader::Pool(pool_id) => Some(pool_id),
+            SlotLeader::Description(_) => None,
+        }
+    }
+}
+
+impl From<String> for SlotLeader {
+    fn from(leader: String) -> Self {
+        if leader.starts_with("pool1") {
+            SlotLeader::Pool(leader)
+        } else {
+            SlotLeader::Description(leader)
+        }
+    }
+}
+
+impl From<SlotLeader> for String {
+    fn from(leader: SlotLeader) -> Self {
+        match leader {
+            SlotLeader::Pool(leader) | SlotLeader::Description(leader) => leader,
+        }
+    }
+}
+
+impl fmt::Display for SlotLeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
     use blockfrost_openapi::models::block_content::BlockContent;
     use serde_json::json;
 
+    // Byron epoch boundary block, these have no height, slot or epoch slot and no slot leader
+    fn epoch_boundary_block() -> serde_json::Value {
+        json!({
+            "time": 1506203091,
+            "height": null,
+            "hash": "89d9b5a5b8ddc8d7e5a6795e9774d97faf1efea59b2caf7eaf9f8c5b32059df4",
+            "slot": null,
+            "epoch": 0,
+            "epoch_slot": null,
+            "slot_leader": "Epoch boundary slot leader",
+            "size": 0,
+            "tx_count": 0,
+            "output": null,
+            "fees": null,
+            "block_vrf": null,
+            "op_cert": null,
+            "op_cert_counter": null,
+            "previous_block": "5f20df933584822601f9e3f8c024eb5eb252fe8cefb24d1317dc3d432e940ebb",
+            "next_block": "f0f7892b5c333cffc4b3c4344de48af4cc63f55e44936196f365a9ef2244134f",
+            "confirmations": 10178587
+        })
+    }
+
+    #[tokio::test]
+    async fn test_lenient_block_fields() {
+        let block = json!({
+            "time": 1641338934,
+            "height": 15243593,
+            "hash": "4ea1ba291e8eef538635a53e59fddba7810d1679631cc3aed7c8e6c4091a516a",
+            "slot": 412162133,
+            "epoch": 425,
+            "epoch_slot": 12,
+            "slot_leader": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2qnikdy",
+            "size": 3,
+            "tx_count": 1,
+            "output": 128314491794u64,
+            "fees": { "lovelace": "592661" },
+            "op_cert": null,
+            "op_cert_counter": null,
+            "previous_block": null,
+            "next_block": null,
+            "confirmations": 4698
+        });
+        assert_eq!(
+            coerce_block_fields(&mut block.clone()),
+            [r#"unexpected fees {"lovelace":"592661"}"#]
+        );
+        let server = MockServer::start(vec![
+            MockResponse::new(200, block.to_string()),
+            MockResponse::new(200, json!([block]).to_string()),
+        ]);
+        let api = server.api();
+
+        let block = api.blocks_by_id("15243593").await.unwrap();
+        assert_eq!(block.output.as_deref(), Some("128314491794"));
+        assert_eq!(block.fees, None);
+        assert_eq!(block.block_vrf, None);
+
+        let blocks = api
+            .blocks_next("15243592", Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(blocks, [block]);
+    }
+
+    #[tokio::test]
+    async fn test_lenient_block_fields_with_warnings_off() {
+        let block = json!({
+            "time": 1641338934,
+            "height": 15243593,
+            "hash": "4ea1ba291e8eef538635a53e59fddba7810d1679631cc3aed7c8e6c4091a516a",
+            "slot": 412162133,
+            "epoch": 425,
+            "epoch_slot": 12,
+            "slot_leader": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2qnikdy",
+            "size": 3,
+            "tx_count": 1,
+            "output": null,
+            "fees": ["592661"],
+            "block_vrf": false,
+            "op_cert": null,
+            "op_cert_counter": null,
+            "previous_block": null,
+            "next_block": null,
+            "confirmations": 4698
+        });
+        assert_eq!(
+            coerce_block_fields(&mut block.clone()),
+            [
+                r#"unexpected fees ["592661"]"#,
+                "unexpected block_vrf false"
+            ]
+        );
+        let server = MockServer::start(vec![MockResponse::new(200, block.to_string())]);
+        let mut settings = BlockFrostSettings::new();
+        settings.set_warn_lenient_fields(false);
+        let api = server.api_with_settings(settings);
+
+        let block = api.blocks_by_id("15243593").await.unwrap();
+        assert_eq!(block.fees, None);
+        assert_eq!(block.block_vrf, None);
+    }
+
+    #[tokio::test]
+    async fn test_recent_block_producers() {
+        let block = |height: i32, slot_leader: &str| BlockContent {
+            hash: height.to_string(),
+            height: Some(height),
+            slot_leader: slot_leader.to_string(),
+            ..Default::default()
+        };
+        let server = MockServer::start_with(move |_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let body = if path.starts_with("/blocks/latest") {
+                json!(block(5, "pool1b"))
+            } else {
+                json!([
+                    block(1, "pool1c"),
+                    block(2, "ByronGenesis-52df0f2c3d4b3e27"),
+                    block(3, "pool1a"),
+                    block(4, "pool1b"),
+                ])
+            };
+            MockResponse::new(200, body.to_string())
+        });
+        let api = server.api();
+
+        assert_eq!(
+            api.recent_block_producers(4).await.unwrap(),
+            [("pool1b".to_string(), 2), ("pool1a".to_string(), 1)]
+        );
+        assert_eq!(
+            api.recent_block_producers(10).await.unwrap(),
+            [
+                ("pool1b".to_string(), 2),
+                ("pool1a".to_string(), 1),
+                ("pool1c".to_string(), 1)
+            ]
+        );
+        assert!(api.recent_block_producers(0).await.unwrap().is_empty());
+        assert!(server
+            .requests()
+            .iter()
+            .any(|request| request.contains("/blocks/5/previous?")));
+    }
+
+    #[tokio::test]
+    async fn test_blocks_follow() {
+        let block = |hash: &str, previous: &str| BlockContent {
+            hash: hash.to_string(),
+            previous_block: Some(previous.to_string()),
+            ..Default::default()
+        };
+        let not_found = json!({ "status_code": 404, "error": "Not Found", "message": "" });
+        let server = MockServer::start_with(move |_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let body = match path.split('?').next().unwrap_or_default() {
+                "/blocks/a/next" => json!([block("b", "a"), block("c", "b")]),
+                "/blocks/b" => json!(block("b", "a")),
+                "/blocks/b/next" => json!([block("d", "b")]),
+                "/blocks/d/next" => json!([]),
+                // `c` was rolled back
+                _ => return MockResponse::new(404, not_found.to_string()),
+            };
+            MockResponse::new(200, body.to_string())
+        });
+        let api = server.api();
+
+        let events: Vec<_> = api
+            .blocks_follow("a", Duration::from_millis(10))
+            .take(4)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| match event {
+                ChainEvent::RollForward(block) => format!("+{}", block.hash),
+                ChainEvent::Rollback(hash) => format!("-{}", hash),
+            })
+            .collect();
+        assert_eq!(summary, ["+b", "+c", "-b", "+d"]);
+    }
+
+    #[tokio::test]
+    async fn test_blocks_follow_with_txs() {
+        let block = |hash: &str, tx_count: i32| BlockContent {
+            hash: hash.to_string(),
+            tx_count,
+            ..Default::default()
+        };
+        let server = MockServer::start_with(move |_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let body = match path.split('?').next().unwrap_or_default() {
+                "/blocks/a/next" => json!([block("b", 2), block("c", 0), block("d", 1)]),
+                "/blocks/b/txs" => json!(["aa11", "bb22"]),
+                "/blocks/d/txs" => json!(["cc33"]),
+                _ => json!([]),
+            };
+            MockResponse::new(200, body.to_string())
+        });
+        let api = server.api();
+
+        let events: Vec<_> = api
+            .blocks_follow_with_txs("a", Duration::from_millis(10))
+            .take(3)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        let summary: Vec<_> = events
+            .iter()
+            .map(|(event, hashes)| match event {
+                ChainEvent::RollForward(block) => (block.hash.as_str(), hashes.len()),
+                ChainEvent::Rollback(hash) => (hash.as_str(), 0),
+            })
+            .collect();
+        assert_eq!(summary, [("b", 2), ("c", 0), ("d", 1)]);
+        assert_eq!(events[2].1, ["cc33"]);
+        assert!(!server
+            .requests()
+            .iter()
+            .any(|request| request.contains("/blocks/c/txs")));
+    }
+
+    #[tokio::test]
+    async fn test_blocks_follow_with_txs_ends_on_error() {
+        let not_found = json!({ "status_code": 404, "error": "Not Found", "message": "" });
+        let server = MockServer::start_with(move |_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let body = match path.split('?').next().unwrap_or_default() {
+                "/blocks/a/next" => json!([
+                    BlockContent {
+                        hash: "b".to_string(),
+                        tx_count: 1,
+                        ..Default::default()
+                    },
+                    BlockContent {
+                        hash: "c".to_string(),
+                        ..Default::default()
+                    }
+                ]),
+                _ => return MockResponse::new(404, not_found.to_string()),
+            };
+            MockResponse::new(200, body.to_string())
+        });
+        let api = server.api();
+
+        let events: Vec<_> = api
+            .blocks_follow_with_txs("a", Duration::ZERO)
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 1);
+        assert!(events[0].as_ref().unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_blocks_follow_unknown_start() {
+        let not_found = json!({ "status_code": 404, "error": "Not Found", "message": "" });
+        let server = MockServer::start(vec![MockResponse::new(404, not_found.to_string())]);
+        let api = server.api();
+
+        let events: Vec<_> = api.blocks_follow("a", Duration::ZERO).collect().await;
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 404
+        ));
+    }
+
+    #[test]
+    fn test_blocks_epoch_boundary() {
+        let block = serde_json::from_value::<BlockContent>(epoch_boundary_block()).unwrap();
+
+        assert_eq!(block.height, None);
+        assert_eq!(block.slot, None);
+        assert_eq!(block.epoch, Some(0));
+        assert_eq!(block.epoch_slot, None);
+        assert_eq!(block.output, None);
+    }
+
+    #[test]
+    fn test_blocks_genesis() {
+        // The genesis block itself has no previous block and no epoch
+        let mut genesis = epoch_boundary_block();
+        genesis["hash"] = json!("5f20df933584822601f9e3f8c024eb5eb252fe8cefb24d1317dc3d432e940ebb");
+        genesis["epoch"] = json!(null);
+        genesis["slot_leader"] = json!("Genesis slot leader");
+        genesis["previous_block"] = json!(null);
+
+        let block = serde_json::from_value::<BlockContent>(genesis).unwrap();
+
+        assert_eq!(block.epoch, None);
+        assert_eq!(block.previous_block, None);
+        assert_eq!(
+            block.typed_slot_leader(),
+            SlotLeader::Description("Genesis slot leader".to_string())
+        );
+        assert_eq!(block.typed_slot_leader().pool_id(), None);
+    }
+
+    #[test]
+    fn test_confirmations_at() {
+        let mut block = serde_json::from_value::<BlockContent>(epoch_boundary_block()).unwrap();
+
+        assert_eq!(block.confirmations_at(20000000), 10178587);
+
+        block.height = Some(15243593);
+        assert_eq!(block.confirmations_at(15248291), 4698);
+        assert_eq!(block.confirmations_at(15243593), 0);
+    }
+
+    #[test]
+    fn test_slot_leader() {
+        let pool_id = "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy";
+        let leader = serde_json::from_value::<SlotLeader>(json!(pool_id)).unwrap();
+
+        assert_eq!(leader, SlotLeader::Pool(pool_id.to_string()));
+        assert_eq!(leader.pool_id(), Some(pool_id));
+        assert_eq!(serde_json::to_value(&leader).unwrap(), json!(pool_id));
+
+        let leader =
+            serde_json::from_value::<SlotLeader>(json!("ByronGenesis-52df0f2c3d4b3e27")).unwrap();
+
+        assert_eq!(leader.pool_id(), None);
+        assert_eq!(leader.as_str(), "ByronGenesis-52df0f2c3d4b3e27");
+    }
+
+    #[tokio::test]
+    async fn test_blocks_by_id_epoch_boundary() {
+        let server = MockServer::start(vec![MockResponse::new(
+            200,
+            epoch_boundary_block().to_string(),
+        )]);
+
+        let block = server.api().blocks_by_id("1").await.unwrap();
+
+        assert_eq!(block.height, None);
+    }
+
     #[tokio::test]
     async fn test_blocks_latest() {
         let json_value = json!({
@@ -101,6 +940,38 @@ mod tests {
         serde_json::from_value::<BlockContent>(json_value).unwrap();
     }
 
+    #[test]
+    fn test_block_op_cert() {
+        let json_value = json!({
+            "time": 1641338934,
+            "height": 15243593,
+            "hash": "4ea1ba291e8eef538635a53e59fddba7810d1679631cc3aed7c8e6c4091a516a",
+            "slot": 412162133,
+            "epoch": 425,
+            "epoch_slot": 12,
+            "slot_leader": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2qnikdy",
+            "size": 3,
+            "tx_count": 1,
+            "output": "128314491794",
+            "fees": "592661",
+            "block_vrf": "vrf_vk1wf2k6lhujezqcfe00l6zetxpnmh9n6mwhpmhm0dvfh3fxgmdnrfqkms8ty",
+            "op_cert": "da905277534faf75dae41732650568af545134ee08a3c0392dbefc8096ae177c",
+            "op_cert_counter": "18",
+            "previous_block": "43ebccb3ac72c7cebd0d9b755a4b08412c9f5dcb81b8a0ad1e3c197d29d47b05",
+            "next_block": "8367f026cf4b03e116ff8ee5daf149b55ba5a6ec6dec04803b8dc317721d15fa",
+            "confirmations": 4698
+        });
+
+        let block = serde_json::from_value::<BlockContent>(json_value).unwrap();
+
+        assert_eq!(
+            block.op_cert.as_deref(),
+            Some("da905277534faf75dae41732650568af545134ee08a3c0392dbefc8096ae177c")
+        );
+        assert_eq!(block.typed_op_cert_counter(), Some(18));
+        assert_eq!(BlockContent::default().typed_op_cert_counter(), None);
+    }
+
     #[tokio::test]
     async fn test_blocks_latest_txs() {
         let json_value = json!([
@@ -149,4 +1020,112 @@ mod tests {
 
         serde_json::from_value::<Vec<String>>(json_value).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_blocks_latest_transactions_full() {
+        let block = epoch_boundary_block();
+        let block_hash = block["hash"].as_str().unwrap().to_string();
+        let server = MockServer::start_with(move |_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let (status, body) = if path == "/blocks/latest" {
+                (200, block.to_string())
+            } else if path.starts_with(&format!("/blocks/{}/txs", block_hash)) {
+                (200, json!(["aa11", "bb22"]).to_string())
+            } else if path == "/txs/aa11" {
+                let transaction = TxContent {
+                    hash: "aa11".to_string(),
+                    fees: "182485".to_string(),
+                    ..Default::default()
+                };
+                (200, serde_json::to_string(&transaction).unwrap())
+            } else {
+                (
+                    404,
+                    json!({ "status_code": 404, "error": "Not Found", "message": "" }).to_string(),
+                )
+            };
+            MockResponse::new(status, body)
+        });
+
+        let (block, transactions) = server
+            .api()
+            .blocks_latest_transactions_full()
+            .await
+            .unwrap();
+
+        assert_eq!(block.slot_leader, "Epoch boundary slot leader");
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].0, "aa11");
+        assert_eq!(transactions[0].1.as_ref().unwrap().fees, "182485");
+        assert_eq!(transactions[1].0, "bb22");
+        assert!(transactions[1].1.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_blocks_in_time_range() {
+        // Blocks 1 to 300, one every 20 seconds
+        let block = |height: i32| BlockContent {
+            time: height * 20,
+            height: Some(height),
+            hash: format!("h{}", height),
+            previous_block: Some(format!("h{}", height - 1)),
+            ..Default::default()
+        };
+        let server = MockServer::start_with(move |_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let body = if path == "/blocks/latest" {
+                serde_json::to_string(&block(300))
+            } else if let Some(next) = path.strip_prefix("/blocks/h") {
+                let (height, query) = next.split_once("/next?page=").unwrap();
+                let page: i32 = query.split('&').next().unwrap().parse().unwrap();
+                let first = height.parse::<i32>().unwrap() + 1 + (page - 1) * 100;
+                let blocks: Vec<_> = (first..first + 100)
+                    .filter(|h| *h <= 300)
+                    .map(block)
+                    .collect();
+                serde_json::to_string(&blocks)
+            } else {
+                serde_json::to_string(&block(path[8..].parse().unwrap()))
+            };
+            MockResponse::new(200, body.unwrap())
+        });
+        let api = server.api();
+
+        let blocks = api
+            .blocks_in_time_range(1010, 3500)
+            .await
+            .unwrap()
+            .collect_all()
+            .await
+            .unwrap();
+
+        let heights: Vec<_> = blocks.iter().map(|block| block.height.unwrap()).collect();
+        assert_eq!(heights, (51..=175).collect::<Vec<_>>());
+
+        let after_tip = api.blocks_in_time_range(7000, 8000).await.unwrap();
+        assert!(after_tip.collect_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tip_changed_since() {
+        let mut block = epoch_boundary_block();
+        block["height"] = json!(10178588);
+        block["slot"] = json!(138158395);
+        let server = MockServer::start(vec![MockResponse::new(200, block.to_string())]);
+        let api = server.api();
+
+        let tip = api.tip().await.unwrap();
+
+        assert_eq!(tip.height, Some(10178588));
+        assert_eq!(tip.slot, Some(138158395));
+        assert_eq!(tip.block_time, 1506203091);
+        assert_eq!(api.tip_changed_since(&tip).await.unwrap(), None);
+
+        let previous = Tip {
+            hash: "5f20df933584822601f9e3f8c024eb5eb252fe8cefb24d1317dc3d432e940ebb".to_string(),
+            height: Some(10178587),
+            ..tip.clone()
+        };
+        assert_eq!(api.tip_changed_since(&previous).await.unwrap(), Some(tip));
+    }
 }
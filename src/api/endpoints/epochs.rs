@@ -1,9 +1,14 @@
-use crate::*;
+use crate::{types::parse_lovelace, *};
 use blockfrost_openapi::models::{
     epoch_content::EpochContent, epoch_param_content::EpochParamContent,
     epoch_stake_content_inner::EpochStakeContentInner,
     epoch_stake_pool_content_inner::EpochStakePoolContentInner,
 };
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 impl BlockfrostAPI {
     pub async fn epochs_latest(&self) -> BlockfrostResult<EpochContent> {
@@ -25,6 +30,21 @@ impl BlockfrostAPI {
             .await
     }
 
+    /// The rates of [`epochs_latest_parameters`](Self::epochs_latest_parameters) as exact
+    /// decimals. Requires the `decimal` feature.
+    #[cfg(feature = "decimal")]
+    pub async fn epochs_latest_parameters_rates(&self) -> BlockfrostResult<EpochParamRates> {
+        self.call_endpoint("/epochs/latest/parameters").await
+    }
+
+    /// The rates of [`epochs_parameters`](Self::epochs_parameters) as exact decimals. Requires
+    /// the `decimal` feature.
+    #[cfg(feature = "decimal")]
+    pub async fn epochs_parameters_rates(&self, number: i32) -> BlockfrostResult<EpochParamRates> {
+        self.call_endpoint(format!("/epochs/{}/parameters", number).as_str())
+            .await
+    }
+
     pub async fn epochs_next(
         &self, number: i32, pagination: Pagination,
     ) -> BlockfrostResult<Vec<EpochContent>> {
@@ -46,6 +66,16 @@ impl BlockfrostAPI {
             .await
     }
 
+    /// Stream the whole stake distribution of an epoch, across all pools.
+    ///
+    /// On mainnet this is well over a million entries (one request per 100), consider
+    /// [`prefetch`](Lister::prefetch) for speed together with a
+    /// [`rate limit`](crate::BlockFrostSettings::rate_limit) such as [`RateLimit::blockfrost`] to
+    /// stay within the API limits.
+    pub fn epochs_stakes_all(&self, number: i32) -> Lister<'_, EpochStake> {
+        Lister::new(self, format!("/epochs/{}/stakes", number))
+    }
+
     pub async fn epochs_stakes_by_pool(
         &self, number: i32, pool_id: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<EpochStakePoolContentInner>> {
@@ -69,9 +99,262 @@ impl BlockfrostAPI {
     }
 }
 
+/// Typed amounts and times of [`EpochContent`], as returned by
+/// [`epochs_latest`](BlockfrostAPI::epochs_latest) and
+/// [`epochs_by_number`](BlockfrostAPI::epochs_by_number).
+pub trait EpochContentExt {
+    /// Sum of all the transaction outputs of the epoch.
+    fn output_lovelace(&self) -> BlockfrostResult<Lovelace>;
+
+    /// Sum of all the fees of the epoch.
+    fn fees_lovelace(&self) -> BlockfrostResult<Lovelace>;
+
+    /// Total active stake, `None` for epochs before the active stake snapshot exists.
+    fn active_stake_lovelace(&self) -> BlockfrostResult<Option<Lovelace>>;
+
+    /// Start of the epoch.
+    fn start_at(&self) -> SystemTime;
+
+    /// End of the epoch.
+    fn end_at(&self) -> SystemTime;
+
+    /// Time of the first block of the epoch.
+    fn first_block_at(&self) -> SystemTime;
+
+    /// Time of the last block of the epoch.
+    fn last_block_at(&self) -> SystemTime;
+}
+
+impl EpochContentExt for EpochContent {
+    fn output_lovelace(&self) -> BlockfrostResult<Lovelace> {
+        parse_lovelace(&self.output)
+    }
+
+    fn fees_lovelace(&self) -> BlockfrostResult<Lovelace> {
+        parse_lovelace(&self.fees)
+    }
+
+    fn active_stake_lovelace(&self) -> BlockfrostResult<Option<Lovelace>> {
+        self.active_stake.as_deref().map(parse_lovelace).transpose()
+    }
+
+    fn start_at(&self) -> SystemTime {
+        unix_time(self.start_time)
+    }
+
+    fn end_at(&self) -> SystemTime {
+        unix_time(self.end_time)
+    }
+
+    fn first_block_at(&self) -> SystemTime {
+        unix_time(self.first_block_time)
+    }
+
+    fn last_block_at(&self) -> SystemTime {
+        unix_time(self.last_block_time)
+    }
+}
+
+/// Protocol version and typed amounts and limits of [`EpochParamContent`], as returned by
+/// [`epochs_latest_parameters`](BlockfrostAPI::epochs_latest_parameters) and
+/// [`epochs_parameters`](BlockfrostAPI::epochs_parameters).
+///
+/// The limits that only exist since Alonzo are `None` for earlier epochs.
+pub trait EpochParamContentExt {
+    /// Protocol version the parameters belong to.
+    fn protocol_version(&self) -> ProtocolVersion;
+
+    /// Deposit for registering a stake key.
+    fn key_deposit_lovelace(&self) -> BlockfrostResult<Lovelace>;
+
+    /// Deposit for registering a stake pool.
+    fn pool_deposit_lovelace(&self) -> BlockfrostResult<Lovelace>;
+
+    /// Minimum value of an output before Alonzo, replaced by
+    /// [`coins_per_utxo_size_lovelace`](Self::coins_per_utxo_size_lovelace) since.
+    fn min_utxo_lovelace(&self) -> BlockfrostResult<Lovelace>;
+
+    /// Minimum fixed cost a pool can declare.
+    fn min_pool_cost_lovelace(&self) -> BlockfrostResult<Lovelace>;
+
+    /// Cost per byte of an output (per 8 byte word in Alonzo), `coins_per_utxo_word` holds the
+    /// same value.
+    fn coins_per_utxo_size_lovelace(&self) -> BlockfrostResult<Option<Lovelace>>;
+
+    /// Execution memory units a transaction can use.
+    fn max_tx_ex_mem_units(&self) -> BlockfrostResult<Option<u64>>;
+
+    /// Execution steps a transaction can use.
+    fn max_tx_ex_steps_units(&self) -> BlockfrostResult<Option<u64>>;
+
+    /// Execution memory units the transactions of a block can use.
+    fn max_block_ex_mem_units(&self) -> BlockfrostResult<Option<u64>>;
+
+    /// Execution steps the transactions of a block can use.
+    fn max_block_ex_steps_units(&self) -> BlockfrostResult<Option<u64>>;
+
+    /// Largest serialized value (amounts of an output) in bytes.
+    fn max_val_size_bytes(&self) -> BlockfrostResult<Option<u64>>;
+}
+
+impl EpochParamContentExt for EpochParamContent {
+    fn protocol_version(&self) -> ProtocolVersion {
+        ProtocolVersion::new(
+            self.protocol_major_ver.max(0) as u32,
+            self.protocol_minor_ver.max(0) as u32,
+        )
+    }
+
+    fn key_deposit_lovelace(&self) -> BlockfrostResult<Lovelace> {
+        parse_lovelace(&self.key_deposit)
+    }
+
+    fn pool_deposit_lovelace(&self) -> BlockfrostResult<Lovelace> {
+        parse_lovelace(&self.pool_deposit)
+    }
+
+    fn min_utxo_lovelace(&self) -> BlockfrostResult<Lovelace> {
+        parse_lovelace(&self.min_utxo)
+    }
+
+    fn min_pool_cost_lovelace(&self) -> BlockfrostResult<Lovelace> {
+        parse_lovelace(&self.min_pool_cost)
+    }
+
+    fn coins_per_utxo_size_lovelace(&self) -> BlockfrostResult<Option<Lovelace>> {
+        self.coins_per_utxo_size
+            .as_deref()
+            .or(self.coins_per_utxo_word.as_deref())
+            .map(parse_lovelace)
+            .transpose()
+    }
+
+    fn max_tx_ex_mem_units(&self) -> BlockfrostResult<Option<u64>> {
+        parse_limit("max_tx_ex_mem", &self.max_tx_ex_mem)
+    }
+
+    fn max_tx_ex_steps_units(&self) -> BlockfrostResult<Option<u64>> {
+        parse_limit("max_tx_ex_steps", &self.max_tx_ex_steps)
+    }
+
+    fn max_block_ex_mem_units(&self) -> BlockfrostResult<Option<u64>> {
+        parse_limit("max_block_ex_mem", &self.max_block_ex_mem)
+    }
+
+    fn max_block_ex_steps_units(&self) -> BlockfrostResult<Option<u64>> {
+        parse_limit("max_block_ex_steps", &self.max_block_ex_steps)
+    }
+
+    fn max_val_size_bytes(&self) -> BlockfrostResult<Option<u64>> {
+        parse_limit("max_val_size", &self.max_val_size)
+    }
+}
+
+// Limit sent by the API as an optional string, failing with a `Parsing` error
+fn parse_limit(name: &str, limit: &Option<String>) -> BlockfrostResult<Option<u64>> {
+    limit
+        .as_deref()
+        .map(|limit| {
+            limit.parse().map_err(|reason| BlockfrostError::Parsing {
+                message: format!("invalid {} '{}': {}", name, limit, reason),
+            })
+        })
+        .transpose()
+}
+
+/// Major and minor protocol version, ordered so hard fork thresholds can be compared against:
+/// `version >= ProtocolVersion::new(9, 0)` holds from Conway on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ProtocolVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl ProtocolVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// Ledger era of this version, [`Era::Unknown`] for majors newer than this crate knows.
+    pub fn era(&self) -> Era {
+        match self.major {
+            0..=1 => Era::Byron,
+            2 => Era::Shelley,
+            3 => Era::Allegra,
+            4 => Era::Mary,
+            5..=6 => Era::Alonzo,
+            7..=8 => Era::Babbage,
+            9..=10 => Era::Conway,
+            _ => Era::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Ledger era of a [`ProtocolVersion`], in chain order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Era {
+    Byron,
+    Shelley,
+    Allegra,
+    Mary,
+    Alonzo,
+    Babbage,
+    Conway,
+    /// Era after the ones known to this crate.
+    Unknown,
+}
+
+fn unix_time(seconds: i32) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds.max(0) as u64)
+}
+
+/// Rates of the protocol parameters of an epoch, from
+/// [`epochs_parameters_rates`](BlockfrostAPI::epochs_parameters_rates).
+///
+/// Only the rate fields of `/epochs/{number}/parameters` are read, the `strict` feature doesn't
+/// apply.
+#[cfg(feature = "decimal")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EpochParamRates {
+    /// Pool pledge influence.
+    pub a0: Decimal,
+    /// Monetary expansion.
+    pub rho: Decimal,
+    /// Treasury expansion.
+    pub tau: Decimal,
+    /// Proportion of blocks produced by federated nodes, `0` since the Babbage era.
+    pub decentralisation_param: Decimal,
+    /// Price of a unit of script memory, `None` before the Alonzo era.
+    pub price_mem: Option<Decimal>,
+    /// Price of a script execution step, `None` before the Alonzo era.
+    pub price_step: Option<Decimal>,
+}
+
+/// Active stake of an account in an epoch, created by
+/// [`epochs_stakes_all`](BlockfrostAPI::epochs_stakes_all).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct EpochStake {
+    /// Stake address.
+    pub stake_address: String,
+    /// Bech32 ID of the pool delegated to (`pool1...`).
+    pub pool_id: String,
+    /// Amount of active delegated stake.
+    pub amount: Lovelace,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::{MockResponse, MockServer};
     use blockfrost_openapi::models::{
         epoch_content::EpochContent, epoch_param_content::EpochParamContent,
     };
@@ -95,6 +378,72 @@ mod tests {
         serde_json::from_value::<EpochContent>(json_value).unwrap();
     }
 
+    #[test]
+    fn test_epoch_content_ext() {
+        let json_value = json!({
+            "epoch": 225,
+            "start_time": 1603403091,
+            "end_time": 1603835086,
+            "first_block_time": 1603403092,
+            "last_block_time": 1603835084,
+            "block_count": 21298,
+            "tx_count": 17856,
+            "output": "7849943934049314",
+            "fees": "4203312194",
+            "active_stake": null
+        });
+        let epoch = serde_json::from_value::<EpochContent>(json_value).unwrap();
+
+        assert_eq!(epoch.output_lovelace().unwrap(), Lovelace(7849943934049314));
+        assert_eq!(epoch.fees_lovelace().unwrap(), Lovelace(4203312194));
+        assert_eq!(epoch.active_stake_lovelace().unwrap(), None);
+        assert_eq!(
+            epoch.start_at(),
+            UNIX_EPOCH + Duration::from_secs(1603403091)
+        );
+        assert_eq!(
+            epoch.end_at().duration_since(epoch.start_at()).unwrap(),
+            Duration::from_secs(431995)
+        );
+        assert!(epoch.first_block_at() < epoch.last_block_at());
+
+        let epoch = EpochContent {
+            fees: "-1".to_string(),
+            ..epoch
+        };
+        assert!(matches!(
+            epoch.fees_lovelace(),
+            Err(BlockfrostError::Parsing { .. })
+        ));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[tokio::test]
+    async fn test_epochs_parameters_rates() {
+        let parameters = r#"{
+            "epoch": 300,
+            "a0": 0.3,
+            "rho": 0.003,
+            "tau": 0.2,
+            "decentralisation_param": 0,
+            "price_mem": 0.0577,
+            "price_step": 0.0000721,
+            "coins_per_utxo_word": "34482"
+        }"#;
+        let server = MockServer::start(vec![MockResponse::new(200, parameters)]);
+
+        let rates = server.api().epochs_parameters_rates(300).await.unwrap();
+
+        assert_eq!(rates.rho.to_fraction(), (3, 1000));
+        assert_eq!(rates.decentralisation_param, Decimal::default());
+        assert_eq!(rates.price_mem, Decimal::new(577, 4));
+        assert_eq!(rates.price_step.unwrap().to_fraction(), (721, 10000000));
+        assert_eq!(
+            server.requests()[0].split_whitespace().nth(1),
+            Some("/epochs/300/parameters")
+        );
+    }
+
     #[tokio::test]
     async fn test_epochs_latest_parameters() {
         let json_value = json!({
@@ -135,6 +484,71 @@ mod tests {
         serde_json::from_value::<EpochParamContent>(json_value).unwrap();
     }
 
+    #[test]
+    fn test_epoch_param_content_ext() {
+        // Mainnet parameters of epoch 500
+        let parameters = EpochParamContent {
+            key_deposit: "2000000".into(),
+            pool_deposit: "500000000".into(),
+            min_utxo: "4310".into(),
+            min_pool_cost: "170000000".into(),
+            max_tx_ex_mem: Some("14000000".into()),
+            max_tx_ex_steps: Some("10000000000".into()),
+            max_block_ex_mem: Some("62000000".into()),
+            max_block_ex_steps: Some("20000000000".into()),
+            max_val_size: Some("5000".into()),
+            collateral_percent: Some(150),
+            max_collateral_inputs: Some(3),
+            coins_per_utxo_size: Some("4310".into()),
+            coins_per_utxo_word: Some("4310".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parameters.key_deposit_lovelace().unwrap(),
+            Lovelace(2000000)
+        );
+        assert_eq!(
+            parameters.pool_deposit_lovelace().unwrap(),
+            Lovelace(500000000)
+        );
+        assert_eq!(parameters.min_utxo_lovelace().unwrap(), Lovelace(4310));
+        assert_eq!(
+            parameters.min_pool_cost_lovelace().unwrap(),
+            Lovelace(170000000)
+        );
+        assert_eq!(
+            parameters.coins_per_utxo_size_lovelace().unwrap(),
+            Some(Lovelace(4310))
+        );
+        assert_eq!(parameters.max_tx_ex_mem_units().unwrap(), Some(14000000));
+        assert_eq!(
+            parameters.max_tx_ex_steps_units().unwrap(),
+            Some(10000000000)
+        );
+        assert_eq!(parameters.max_block_ex_mem_units().unwrap(), Some(62000000));
+        assert_eq!(
+            parameters.max_block_ex_steps_units().unwrap(),
+            Some(20000000000)
+        );
+        assert_eq!(parameters.max_val_size_bytes().unwrap(), Some(5000));
+
+        // Shelley parameters have no Alonzo limits
+        let shelley = EpochParamContent {
+            min_utxo: "1000000".into(),
+            ..Default::default()
+        };
+        assert_eq!(shelley.coins_per_utxo_size_lovelace().unwrap(), None);
+        assert_eq!(shelley.max_tx_ex_mem_units().unwrap(), None);
+        assert!(shelley.key_deposit_lovelace().is_err());
+
+        let invalid = EpochParamContent {
+            max_tx_ex_steps: Some("1e10".into()),
+            ..Default::default()
+        };
+        assert!(invalid.max_tx_ex_steps_units().is_err());
+    }
+
     #[tokio::test]
     async fn test_epochs_next() {
         let json_value = json!([
@@ -190,4 +604,87 @@ mod tests {
 
         serde_json::from_value::<Vec<String>>(json_value).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_epochs_stakes_all() {
+        let stake = json!({
+            "stake_address": "stake1u9l5q5jwgelgagzyt6nuaasefgmn8pd25c8e9qpeprq0tdcp0e3uk",
+            "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy",
+            "amount": "4440295078"
+        });
+        let full_page = json!(vec![stake.clone(); 100]);
+        let server = MockServer::start_with(move |_, request| {
+            let page = if request.contains("page=1&") || request.contains("page=2&") {
+                full_page.clone()
+            } else {
+                json!([stake])
+            };
+            MockResponse::new(200, page.to_string())
+        });
+        let api = server.api();
+
+        let stakes = api
+            .epochs_stakes_all(225)
+            .prefetch(4)
+            .collect_all()
+            .await
+            .unwrap();
+
+        assert_eq!(stakes.len(), 201);
+        assert_eq!(stakes[0].amount, Lovelace(4440295078));
+        assert!(server.requests().len() >= 3);
+    }
+
+    #[test]
+    fn test_protocol_version() {
+        let parameters = EpochParamContent {
+            protocol_major_ver: 9,
+            protocol_minor_ver: 1,
+            ..Default::default()
+        };
+        let version = parameters.protocol_version();
+
+        assert_eq!(version, ProtocolVersion::new(9, 1));
+        assert_eq!(version.to_string(), "9.1");
+        assert!(version >= ProtocolVersion::new(9, 0));
+        assert!(version < ProtocolVersion::new(10, 0));
+        assert!(ProtocolVersion::new(8, 2) < ProtocolVersion::new(9, 0));
+        assert!(ProtocolVersion::new(6, 0) > ProtocolVersion::new(5, 9));
+
+        let eras: Vec<_> = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+            .map(|major| ProtocolVersion::new(major, 0).era())
+            .into();
+        assert_eq!(
+            eras,
+            [
+                Era::Byron,
+                Era::Byron,
+                Era::Shelley,
+                Era::Allegra,
+                Era::Mary,
+                Era::Alonzo,
+                Era::Alonzo,
+                Era::Babbage,
+                Era::Babbage,
+                Era::Conway,
+                Era::Conway,
+                Era::Unknown,
+            ]
+        );
+        assert!(Era::Babbage < Era::Conway);
+    }
+
+    #[test]
+    fn test_epoch_stake_unknown_fields() {
+        let json_value = json!({
+            "stake_address": "stake1u9l5q5jwgelgagzyt6nuaasefgmn8pd25c8e9qpeprq0tdcp0e3uk",
+            "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy",
+            "amount": "4440295078",
+            "new_field": true
+        });
+
+        let result = serde_json::from_value::<EpochStake>(json_value);
+
+        assert_eq!(result.is_err(), cfg!(feature = "strict"));
+    }
 }
@@ -3,6 +3,11 @@ use blockfrost_openapi::models::{
     __get_200_response::Get200Response, _health_clock_get_200_response::HealthClockGet200Response,
     _health_get_200_response::HealthGet200Response,
 };
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
 
 impl BlockfrostAPI {
     /// Root endpoint, points end users to documentation.
@@ -10,21 +15,211 @@ impl BlockfrostAPI {
         self.call_endpoint("/").await
     }
 
+    /// Version of the backend API, from [`BlockFrostSettings::set_api_version`] or else the
+    /// root endpoint, asked once and shared by every clone of the api.
+    pub async fn backend_version(&self) -> BlockfrostResult<String> {
+        if let Some(version) = &self.settings.api_version {
+            return Ok(version.clone());
+        }
+        if let Some(version) = self.backend_version.get() {
+            return Ok(version.clone());
+        }
+
+        let version = self.root().await?.version;
+        Ok(self.backend_version.get_or_init(|| version).clone())
+    }
+
     /// Backend health status as a boolean.
     pub async fn health(&self) -> BlockfrostResult<HealthGet200Response> {
         self.call_endpoint("/health").await
     }
 
+    /// [`health`](Self::health) with the sync progress self-hosted backends may add.
+    ///
+    /// The hosted API only sends `is_healthy`, `sync_progress` is then `None`.
+    pub async fn health_status(&self) -> BlockfrostResult<HealthStatus> {
+        self.call_endpoint("/health").await
+    }
+
     /// Current backend time.
     pub async fn health_clock(&self) -> BlockfrostResult<HealthClockGet200Response> {
         self.call_endpoint("/health/clock").await
     }
+
+    /// Read one known item from each endpoint group (blocks, epochs, pools, accounts and
+    /// assets) at the same time, to check that a deployment, self-hosted ones especially,
+    /// answers them all.
+    ///
+    /// Every group gets its own result and latency in the report, retries included.
+    pub async fn smoke_test(&self, ids: &SmokeTestIds) -> SmokeReport {
+        let checks = futures::join!(
+            smoke_check("blocks", self.blocks_by_id(&ids.block)),
+            smoke_check("epochs", self.epochs_by_number(ids.epoch)),
+            smoke_check("pools", self.pools_by_id(&ids.pool_id)),
+            smoke_check("accounts", self.accounts(&ids.stake_address)),
+            smoke_check("assets", self.assets_by_id(&ids.asset)),
+        );
+
+        SmokeReport {
+            checks: vec![checks.0, checks.1, checks.2, checks.3, checks.4],
+        }
+    }
+}
+
+async fn smoke_check<T>(
+    group: &'static str, request: impl Future<Output = BlockfrostResult<T>>,
+) -> SmokeCheck {
+    let start = Instant::now();
+    let result = request.await.map(|_| ());
+
+    SmokeCheck {
+        group,
+        latency: start.elapsed(),
+        result,
+    }
+}
+
+/// Backend health, from [`health_status`](BlockfrostAPI::health_status).
+///
+/// Other fields a backend adds to `/health` are ignored, the `strict` feature doesn't apply.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HealthStatus {
+    pub is_healthy: bool,
+    /// How far the backend has synced the chain, in percent. `None` when the backend doesn't
+    /// report it, like the hosted API, or sends something other than a number.
+    #[serde(default, deserialize_with = "lenient_progress")]
+    pub sync_progress: Option<f64>,
+}
+
+// A number, or a number in a string, anything else is no progress rather than an error
+fn lenient_progress<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    let progress = Option::<JsonValue>::deserialize(deserializer)?;
+
+    Ok(match progress {
+        Some(JsonValue::Number(number)) => number.as_f64(),
+        Some(JsonValue::String(text)) => text.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+/// Items read by [`smoke_test`](BlockfrostAPI::smoke_test), they must exist on the network
+/// the api talks to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SmokeTestIds {
+    /// Block hash or number.
+    pub block: String,
+    pub epoch: i32,
+    /// Bech32 pool ID.
+    pub pool_id: String,
+    pub stake_address: String,
+    /// Asset unit, the policy ID followed by the hex encoded asset name.
+    pub asset: String,
+}
+
+/// Outcome of [`smoke_test`](BlockfrostAPI::smoke_test).
+#[derive(Debug)]
+pub struct SmokeReport {
+    /// One check per endpoint group, in a fixed order.
+    pub checks: Vec<SmokeCheck>,
+}
+
+impl SmokeReport {
+    /// Whether every group answered.
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|check| check.result.is_ok())
+    }
+
+    /// Checks of the groups that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &SmokeCheck> {
+        self.checks.iter().filter(|check| check.result.is_err())
+    }
+}
+
+/// Read of one endpoint group in a [`SmokeReport`].
+#[derive(Debug)]
+pub struct SmokeCheck {
+    /// `blocks`, `epochs`, `pools`, `accounts` or `assets`.
+    pub group: &'static str,
+    pub latency: Duration,
+    pub result: BlockfrostResult<()>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::{MockResponse, MockServer};
     use serde_json::json;
+    use std::sync::atomic::Ordering;
+
+    #[tokio::test]
+    async fn test_smoke_test() {
+        let server = MockServer::start_with(|_, request| {
+            if request.starts_with("GET /pools/") {
+                let error = json!({ "status_code": 404, "error": "Not Found", "message": "" });
+                MockResponse::new(404, error.to_string())
+            } else {
+                // Not the shape of any of the endpoints, those groups fail to parse
+                let valid = request.starts_with("GET /epochs/");
+                let epoch = json!({
+                    "epoch": 225, "start_time": 1603403091, "end_time": 1603835086,
+                    "first_block_time": 1603403092, "last_block_time": 1603835084,
+                    "block_count": 21298, "tx_count": 17856, "output": "7849943934049314",
+                    "fees": "4203312194", "active_stake": "784953934049314"
+                });
+                MockResponse::new(
+                    200,
+                    if valid {
+                        epoch.to_string()
+                    } else {
+                        "{}".into()
+                    },
+                )
+            }
+        });
+        let ids = SmokeTestIds {
+            block: "1".to_string(),
+            epoch: 225,
+            pool_id: "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy".to_string(),
+            stake_address: "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7"
+                .to_string(),
+            asset: "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e"
+                .to_string(),
+        };
+
+        let report = server.api().smoke_test(&ids).await;
+
+        let groups: Vec<_> = report.checks.iter().map(|check| check.group).collect();
+        assert_eq!(groups, ["blocks", "epochs", "pools", "accounts", "assets"]);
+        assert!(!report.is_ok());
+        assert!(report.checks[1].result.is_ok());
+        assert!(matches!(
+            report.checks[2].result,
+            Err(BlockfrostError::Response { ref reason, .. }) if reason.status_code == 404
+        ));
+        assert_eq!(report.failures().count(), 4);
+        assert_eq!(server.requests().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_not_found_warning() {
+        let error = json!({ "status_code": 404, "error": "Not Found", "message": "" });
+        let server = MockServer::start(vec![MockResponse::new(404, error.to_string())]);
+
+        let api = server.api();
+        assert!(api.blocks_latest().await.unwrap_err().is_not_found());
+        assert!(!api.warned_not_found.load(Ordering::Relaxed));
+
+        let mut settings = BlockFrostSettings::new();
+        settings.set_api_version("0.1.40");
+        let api = server.api_with_settings(settings.clone());
+        assert!(api.network_eras().await.unwrap_err().is_not_found());
+        assert!(api.warned_not_found.load(Ordering::Relaxed));
+
+        settings.warn_unexpected_status = false;
+        let api = server.api_with_settings(settings);
+        assert!(api.network_eras().await.unwrap_err().is_not_found());
+        assert!(!api.warned_not_found.load(Ordering::Relaxed));
+    }
 
     #[test]
     fn test_root() {
@@ -45,6 +240,41 @@ mod tests {
         serde_json::from_value::<HealthGet200Response>(json_value).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_health_status() {
+        let server = MockServer::start(vec![
+            MockResponse::new(200, json!({ "is_healthy": true }).to_string()),
+            MockResponse::new(
+                200,
+                json!({ "is_healthy": false, "sync_progress": 83.27 }).to_string(),
+            ),
+        ]);
+        let api = server.api();
+
+        let hosted = api.health_status().await.unwrap();
+        assert!(hosted.is_healthy);
+        assert_eq!(hosted.sync_progress, None);
+
+        let self_hosted = api.health_status().await.unwrap();
+        assert!(!self_hosted.is_healthy);
+        assert_eq!(self_hosted.sync_progress, Some(83.27));
+        assert!(server.requests()[1].starts_with("GET /health "));
+
+        let progress = |value: JsonValue| {
+            serde_json::from_value::<HealthStatus>(json!({
+                "is_healthy": true,
+                "sync_progress": value,
+                "network": "preview"
+            }))
+            .unwrap()
+            .sync_progress
+        };
+        assert_eq!(progress(json!("99.5")), Some(99.5));
+        assert_eq!(progress(json!(100)), Some(100.0));
+        assert_eq!(progress(json!(null)), None);
+        assert_eq!(progress(json!("syncing")), None);
+    }
+
     #[test]
     fn test_health_clock() {
         let json_value = json!({
@@ -1,20 +1,48 @@
 use crate::*;
 use blockfrost_openapi::models::genesis_content::GenesisContent;
+#[cfg(feature = "decimal")]
+use serde::{Deserialize, Serialize};
 
 impl BlockfrostAPI {
+    /// Genesis constants of the network.
+    ///
+    /// These never change, the first successful response is cached and shared by every clone of
+    /// this api.
     pub async fn genesis(&self) -> BlockfrostResult<GenesisContent> {
+        if let Some(genesis) = self.genesis.get() {
+            return Ok(genesis.clone());
+        }
+
+        let genesis: GenesisContent = self.call_endpoint("/genesis").await?;
+        Ok(self.genesis.get_or_init(|| genesis).clone())
+    }
+
+    /// The rates of [`genesis`](Self::genesis) as exact decimals. Requires the `decimal`
+    /// feature.
+    #[cfg(feature = "decimal")]
+    pub async fn genesis_rates(&self) -> BlockfrostResult<GenesisRates> {
         self.call_endpoint("/genesis").await
     }
 }
 
+/// Rates of the genesis, from [`genesis_rates`](BlockfrostAPI::genesis_rates).
+///
+/// Only the rate fields of `/genesis` are read, the `strict` feature doesn't apply.
+#[cfg(feature = "decimal")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GenesisRates {
+    /// Proportion of slots in which blocks should be issued.
+    pub active_slots_coefficient: Decimal,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::{MockResponse, MockServer};
     use serde_json::json;
 
-    #[test]
-    fn test_genesis() {
-        let json_value = json!({
+    fn mainnet_genesis() -> serde_json::Value {
+        json!({
           "active_slots_coefficient": 0.05,
           "update_quorum": 5,
           "max_lovelace_supply": "45000000000000000",
@@ -25,8 +53,26 @@ mod tests {
           "slot_length": 1,
           "max_kes_evolutions": 62,
           "security_param": 2160
-        });
+        })
+    }
+
+    #[test]
+    fn test_genesis() {
+        let genesis = serde_json::from_value::<GenesisContent>(mainnet_genesis()).unwrap();
+
+        assert_eq!(genesis.network_magic, 764824073);
+        assert_eq!(genesis.active_slots_coefficient, 0.05);
+    }
+
+    #[tokio::test]
+    async fn test_genesis_is_cached() {
+        let server = MockServer::start(vec![MockResponse::new(200, mainnet_genesis().to_string())]);
+        let api = server.api();
+
+        let genesis = api.genesis().await.unwrap();
+        let cached = api.clone().genesis().await.unwrap();
 
-        serde_json::from_value::<GenesisContent>(json_value).unwrap();
+        assert_eq!(genesis, cached);
+        assert_eq!(server.requests().len(), 1);
     }
 }
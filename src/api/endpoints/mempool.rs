@@ -11,9 +11,18 @@ impl BlockfrostAPI {
     }
 
     pub async fn mempool_hash(&self, hash: &str) -> BlockfrostResult<MempoolTxContent> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/mempool/{}", hash).as_str())
             .await
     }
+
+    /// Outputs of [`mempool_hash`](Self::mempool_hash) as [`Output`]s.
+    pub async fn mempool_hash_outputs(&self, hash: &str) -> BlockfrostResult<Vec<Output>> {
+        let transaction = self.mempool_hash(hash).await?;
+
+        Ok(transaction.outputs.into_iter().map(Output::from).collect())
+    }
+
     pub async fn mempool_addresses_address(
         &self, address: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<MempoolContentInner>> {
@@ -1,16 +1,16 @@
-mod accounts;
-mod addresses;
-mod assets;
-mod blocks;
-mod epochs;
-mod health;
-mod ledger;
+pub(crate) mod accounts;
+pub(crate) mod addresses;
+pub(crate) mod assets;
+pub(crate) mod blocks;
+pub(crate) mod epochs;
+pub(crate) mod health;
+pub(crate) mod ledger;
 mod mempool;
 mod metadata;
 mod metrics;
 mod network;
 mod nutlink;
-mod pools;
-mod scripts;
-mod transactions;
-mod utils;
+pub(crate) mod pools;
+pub(crate) mod scripts;
+pub(crate) mod transactions;
+pub(crate) mod utils;
@@ -2,9 +2,9 @@ use crate::*;
 use blockfrost_openapi::models::{
     pool::Pool, pool_delegators_inner::PoolDelegatorsInner, pool_history_inner::PoolHistoryInner,
     pool_list_retire_inner::PoolListRetireInner, pool_metadata::PoolMetadata,
-    pool_updates_inner::PoolUpdatesInner,
     tx_content_pool_certs_inner_relays_inner::TxContentPoolCertsInnerRelaysInner,
 };
+use serde::{Deserialize, Serialize};
 
 impl BlockfrostAPI {
     pub async fn pools_by_id(&self, pool_id: &str) -> BlockfrostResult<Pool> {
@@ -12,6 +12,19 @@ impl BlockfrostAPI {
             .await
     }
 
+    /// The rates of [`pools_by_id`](Self::pools_by_id) as exact decimals. Requires the `decimal`
+    /// feature.
+    #[cfg(feature = "decimal")]
+    pub async fn pools_rates(&self, pool_id: &str) -> BlockfrostResult<PoolRates> {
+        self.call_endpoint(format!("/pools/{}", pool_id).as_str())
+            .await
+    }
+
+    /// On-chain metadata reference of a pool, with the off-chain `ticker`, `name`,
+    /// `description` and `homepage` fetched from its `url`.
+    ///
+    /// The off-chain fields are `None` when the metadata file could not be fetched or is
+    /// invalid, for example when its url answers with a 404.
     pub async fn pools_metadata(&self, pool_id: &str) -> BlockfrostResult<PoolMetadata> {
         self.call_endpoint(format!("/pools/{}/metadata", pool_id).as_str())
             .await
@@ -58,6 +71,29 @@ impl BlockfrostAPI {
         .await
     }
 
+    /// Number of accounts delegating to a pool.
+    ///
+    /// Read from the `live_delegators` of [`pools_by_id`](Self::pools_by_id), one request
+    /// however many delegators there are, instead of listing every page of
+    /// [`pools_delegators`](Self::pools_delegators).
+    pub async fn pools_delegators_count(&self, pool_id: &str) -> BlockfrostResult<u64> {
+        let pool = self.pools_by_id(pool_id).await?;
+
+        Ok(pool.live_delegators.max(0.0) as u64)
+    }
+
+    /// Number of blocks minted by a pool over its lifetime.
+    ///
+    /// Read from the `blocks_minted` of [`pools_by_id`](Self::pools_by_id), one request instead
+    /// of listing every page of [`pools_blocks`](Self::pools_blocks).
+    pub async fn pools_blocks_count(&self, pool_id: &str) -> BlockfrostResult<u64> {
+        let pool = self.pools_by_id(pool_id).await?;
+
+        Ok(pool.blocks_minted.max(0) as u64)
+    }
+
+    /// Hashes of the blocks minted by a pool, oldest first with [`Order::Asc`] or most recent
+    /// first with [`Order::Desc`].
     pub async fn pools_blocks(
         &self, pool_id: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<String>> {
@@ -65,16 +101,74 @@ impl BlockfrostAPI {
             .await
     }
 
+    /// Stream every page of [`pools_blocks`](Self::pools_blocks), oldest first.
+    pub fn pools_blocks_all(&self, pool_id: &str) -> Lister<'_, String> {
+        Lister::new(self, format!("/pools/{}/blocks", pool_id))
+    }
+
+    /// Number of blocks a pool minted in an epoch.
+    ///
+    /// `/pools/{pool_id}/blocks` can't be filtered by epoch and no summary has this count, so
+    /// this [counts](Lister::count_all) every page of `/epochs/{number}/blocks/{pool_id}`, one
+    /// request per 100 blocks.
+    pub async fn pools_block_count_in_epoch(
+        &self, pool_id: &str, epoch: i32,
+    ) -> BlockfrostResult<usize> {
+        Lister::<String>::new(self, format!("/epochs/{}/blocks/{}", epoch, pool_id))
+            .count_all()
+            .await
+    }
+
+    /// Registration and retirement certificates of a pool, its lifecycle rather than the
+    /// per-epoch statistics of [`pools_history`](Self::pools_history).
     pub async fn pools_updates(
         &self, pool_id: &str, pagination: Pagination,
-    ) -> BlockfrostResult<Vec<PoolUpdatesInner>> {
+    ) -> BlockfrostResult<Vec<PoolUpdate>> {
         self.call_paged_endpoint(format!("/pools/{}/updates", pool_id).as_str(), pagination)
             .await
     }
 }
+
+/// Certificate of a pool, returned by [`pools_updates`](BlockfrostAPI::pools_updates).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct PoolUpdate {
+    /// Hash of the transaction containing the certificate.
+    pub tx_hash: String,
+    /// Index of the certificate within the transaction.
+    pub cert_index: i32,
+    pub action: PoolUpdateAction,
+}
+
+/// Rates of a pool, from [`pools_rates`](BlockfrostAPI::pools_rates).
+///
+/// Only the rate fields of `/pools/{pool_id}` are read, the `strict` feature doesn't apply.
+#[cfg(feature = "decimal")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PoolRates {
+    /// Live stake relative to the saturation point, `1` is saturated.
+    pub live_saturation: Decimal,
+    /// Margin taken by the pool operator, `0.05` is 5%.
+    pub margin_cost: Decimal,
+}
+
+/// Kind of a [`PoolUpdate`] certificate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolUpdateAction {
+    /// Registration or update of the pool parameters.
+    Registered,
+    /// Retirement of the pool.
+    Deregistered,
+    /// An action added to the API after this version of the crate.
+    #[serde(other)]
+    Unknown,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::{MockResponse, MockServer};
     use serde_json::json;
 
     #[tokio::test]
@@ -161,6 +255,25 @@ mod tests {
         serde_json::from_value::<Pool>(json_value).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_pools_counts() {
+        let pool = Pool {
+            blocks_minted: 69,
+            live_delegators: 127.0,
+            ..Default::default()
+        };
+        let server = MockServer::start(vec![MockResponse::new(200, json!(pool).to_string())]);
+        let api = server.api();
+        let pool_id = "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy";
+
+        assert_eq!(api.pools_delegators_count(pool_id).await.unwrap(), 127);
+        assert_eq!(api.pools_blocks_count(pool_id).await.unwrap(), 69);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with(&format!("GET /pools/{} ", pool_id)));
+    }
+
     #[tokio::test]
     async fn test_pools_history() {
         let json_value = json!([
@@ -193,6 +306,31 @@ mod tests {
         serde_json::from_value::<PoolMetadata>(json_value).unwrap();
     }
 
+    #[test]
+    fn test_pools_metadata_unreachable() {
+        let json_value = json!({
+          "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy",
+          "hex": "0f292fcaa02b8b2f9b3c8f9fd8e0bb21abedb692a6d5058df3ef2735",
+          "url": "https://stakenuts.com/mainnet.json",
+          "hash": "47c0c68cb57f4a5b4a87bad896fc274678e7aea98e200fa14a1cb40c0cab1d8c",
+          "ticker": null,
+          "name": null,
+          "description": null,
+          "homepage": null
+        });
+
+        let metadata = serde_json::from_value::<PoolMetadata>(json_value).unwrap();
+
+        assert_eq!(
+            metadata.url.as_deref(),
+            Some("https://stakenuts.com/mainnet.json")
+        );
+        assert_eq!(metadata.ticker, None);
+        assert_eq!(metadata.name, None);
+        assert_eq!(metadata.description, None);
+        assert_eq!(metadata.homepage, None);
+    }
+
     #[tokio::test]
     async fn test_pools_relays() {
         let json_value = json!([
@@ -240,6 +378,31 @@ mod tests {
         serde_json::from_value::<Vec<String>>(json_value).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_pools_block_count_in_epoch() {
+        let hash = "d8982ca42cfe76b747cc681d35d671050a9e41e9cfe26573eb214e94fe6ff21d";
+        let server = MockServer::start(vec![
+            MockResponse::new(200, json!(vec![hash; 100]).to_string()),
+            MockResponse::new(200, json!([hash, hash]).to_string()),
+        ]);
+
+        let count = server
+            .api()
+            .pools_block_count_in_epoch(
+                "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy",
+                225,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(count, 102);
+        let requests = server.requests();
+        assert!(requests[0].starts_with(
+            "GET /epochs/225/blocks/pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy?page=1"
+        ));
+        assert!(requests[1].contains("?page=2&count=100&order=asc"));
+    }
+
     #[tokio::test]
     async fn test_pools_updates() {
         let json_value = json!([
@@ -260,6 +423,25 @@ mod tests {
             }
         ]);
 
-        serde_json::from_value::<Vec<PoolUpdatesInner>>(json_value).unwrap();
+        let updates = serde_json::from_value::<Vec<PoolUpdate>>(json_value).unwrap();
+
+        let actions: Vec<_> = updates.iter().map(|update| update.action).collect();
+        assert_eq!(
+            actions,
+            [
+                PoolUpdateAction::Registered,
+                PoolUpdateAction::Deregistered,
+                PoolUpdateAction::Registered
+            ]
+        );
+        assert_eq!(updates[2].cert_index, 1);
+
+        let unknown = json!({ "tx_hash": "", "cert_index": 0, "action": "updated" });
+        assert_eq!(
+            serde_json::from_value::<PoolUpdate>(unknown)
+                .unwrap()
+                .action,
+            PoolUpdateAction::Unknown
+        );
     }
 }
@@ -1,24 +1,40 @@
 use crate::*;
 use blockfrost_openapi::models::{
-    script::Script, script_redeemers_inner::ScriptRedeemersInner, scripts_inner::ScriptsInner,
+    address_utxo_content_inner::AddressUtxoContentInner,
+    script_redeemers_inner::ScriptRedeemersInner, scripts_inner::ScriptsInner,
+    tx_content_utxo_inputs_inner::TxContentUtxoInputsInner,
+    tx_content_utxo_outputs_inner::TxContentUtxoOutputsInner,
 };
+use serde::{Deserialize, Serialize};
+use std::future::Future;
 
 impl BlockfrostAPI {
     pub async fn scripts(&self, pagination: Pagination) -> BlockfrostResult<Vec<ScriptsInner>> {
         self.call_paged_endpoint("/scripts", pagination).await
     }
 
-    pub async fn scripts_by_id(&self, script_hash: &str) -> BlockfrostResult<Script> {
+    pub async fn scripts_by_id(&self, script_hash: &str) -> BlockfrostResult<ScriptInfo> {
+        self.check_hex("script_hash", script_hash, 28)?;
         self.call_endpoint(format!("/scripts/{}", script_hash).as_str())
             .await
     }
 
-    pub async fn scripts_hash_json(&self, script_hash: &str) -> BlockfrostResult<ScriptsInner> {
-        self.call_endpoint(format!("/scripts/{}/json", script_hash).as_str())
-            .await
+    /// JSON representation of a native (`timelock`) script.
+    ///
+    /// Returns `None` for Plutus scripts, which have no JSON form.
+    pub async fn scripts_hash_json(
+        &self, script_hash: &str,
+    ) -> BlockfrostResult<Option<NativeScript>> {
+        self.check_hex("script_hash", script_hash, 28)?;
+        let script: ScriptJson = self
+            .call_endpoint(format!("/scripts/{}/json", script_hash).as_str())
+            .await?;
+
+        Ok(script.json)
     }
 
     pub async fn scripts_hash_cbor(&self, script_hash: &str) -> BlockfrostResult<ScriptsInner> {
+        self.check_hex("script_hash", script_hash, 28)?;
         self.call_endpoint(format!("/scripts/{}/cbor", script_hash).as_str())
             .await
     }
@@ -26,6 +42,7 @@ impl BlockfrostAPI {
     pub async fn scripts_redeemers(
         &self, script_hash: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<ScriptRedeemersInner>> {
+        self.check_hex("script_hash", script_hash, 28)?;
         self.call_paged_endpoint(
             format!("/scripts/{}/redeemers", script_hash).as_str(),
             pagination,
@@ -36,6 +53,7 @@ impl BlockfrostAPI {
     pub async fn scripts_datum_hash(
         &self, datum_hash: &str,
     ) -> BlockfrostResult<serde_json::Value> {
+        self.check_hex("datum_hash", datum_hash, 32)?;
         self.call_endpoint(format!("/scripts/datum/{}", datum_hash).as_str())
             .await
     }
@@ -43,14 +61,132 @@ impl BlockfrostAPI {
     pub async fn scripts_datum_hash_cbor(
         &self, datum_hash: &str,
     ) -> BlockfrostResult<serde_json::Value> {
+        self.check_hex("datum_hash", datum_hash, 32)?;
         self.call_endpoint(format!("/scripts/{}/cbor", datum_hash).as_str())
             .await
     }
 }
 
+/// Script summary, as returned by [`scripts_by_id`](BlockfrostAPI::scripts_by_id).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ScriptInfo {
+    pub script_hash: String,
+    #[serde(rename = "type")]
+    pub script_type: ScriptType,
+    /// Size of the CBOR serialized script in bytes, `None` for native scripts. Spelled
+    /// `serialised_size` like the API field.
+    pub serialised_size: Option<Integer>,
+}
+
+/// Language of a script.
+///
+/// Serializes to the current API names (`timelock`, `plutusV1`, ...). Backends from before
+/// Plutus V2 sent `plutus`, which is read as [`PlutusV1`](ScriptType::PlutusV1).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScriptType {
+    /// Native script, see [`NativeScript`].
+    Timelock,
+    #[serde(alias = "plutus")]
+    PlutusV1,
+    PlutusV2,
+    PlutusV3,
+    /// Language added to the API after this version of the crate.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+struct ScriptJson {
+    json: Option<NativeScript>,
+}
+
+/// Native script (timelock), as returned by
+/// [`scripts_hash_json`](BlockfrostAPI::scripts_hash_json).
+///
+/// Scripts nest, e.g. a 2-of-3 multisig is an [`AtLeast`](NativeScript::AtLeast) of three
+/// [`Sig`](NativeScript::Sig) scripts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum NativeScript {
+    /// Requires a signature from the key with this hash.
+    Sig {
+        #[serde(rename = "keyHash")]
+        key_hash: String,
+    },
+    /// Requires all of the scripts.
+    All { scripts: Vec<NativeScript> },
+    /// Requires any one of the scripts.
+    Any { scripts: Vec<NativeScript> },
+    /// Requires at least `required` of the scripts.
+    AtLeast {
+        required: u32,
+        scripts: Vec<NativeScript>,
+    },
+    /// Valid only before this slot.
+    Before { slot: u64 },
+    /// Valid only after this slot.
+    After { slot: u64 },
+}
+
+/// Outputs that may carry a reference script in `reference_script_hash`.
+pub trait ReferenceScriptExt {
+    /// Fetch the reference script with [`scripts_by_id`](BlockfrostAPI::scripts_by_id), `None`
+    /// when the output has no reference script.
+    ///
+    /// Nothing is fetched along with the output, each call is one extra request.
+    fn resolve_reference_script<'a>(
+        &'a self, api: &'a BlockfrostAPI,
+    ) -> impl Future<Output = BlockfrostResult<Option<ScriptInfo>>> + Send + 'a;
+}
+
+async fn resolve_reference_script(
+    api: &BlockfrostAPI, script_hash: Option<&str>,
+) -> BlockfrostResult<Option<ScriptInfo>> {
+    match script_hash {
+        Some(script_hash) => api.scripts_by_id(script_hash).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+impl ReferenceScriptExt for AddressUtxoContentInner {
+    fn resolve_reference_script<'a>(
+        &'a self, api: &'a BlockfrostAPI,
+    ) -> impl Future<Output = BlockfrostResult<Option<ScriptInfo>>> + Send + 'a {
+        resolve_reference_script(api, self.reference_script_hash.as_deref())
+    }
+}
+
+impl ReferenceScriptExt for TxContentUtxoInputsInner {
+    fn resolve_reference_script<'a>(
+        &'a self, api: &'a BlockfrostAPI,
+    ) -> impl Future<Output = BlockfrostResult<Option<ScriptInfo>>> + Send + 'a {
+        resolve_reference_script(api, self.reference_script_hash.as_deref())
+    }
+}
+
+impl ReferenceScriptExt for Output {
+    fn resolve_reference_script<'a>(
+        &'a self, api: &'a BlockfrostAPI,
+    ) -> impl Future<Output = BlockfrostResult<Option<ScriptInfo>>> + Send + 'a {
+        resolve_reference_script(api, self.reference_script_hash.as_deref())
+    }
+}
+
+impl ReferenceScriptExt for TxContentUtxoOutputsInner {
+    fn resolve_reference_script<'a>(
+        &'a self, api: &'a BlockfrostAPI,
+    ) -> impl Future<Output = BlockfrostResult<Option<ScriptInfo>>> + Send + 'a {
+        resolve_reference_script(api, self.reference_script_hash.as_deref())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::{MockResponse, MockServer};
     use serde_json::json;
 
     #[tokio::test]
@@ -76,7 +212,39 @@ mod tests {
             "type": "plutusV1",
             "serialised_size": 3119
         });
-        serde_json::from_value::<Script>(json_value).unwrap();
+        let script = serde_json::from_value::<ScriptInfo>(json_value).unwrap();
+
+        assert_eq!(script.script_type, ScriptType::PlutusV1);
+        assert_eq!(script.serialised_size, Some(3119));
+    }
+
+    #[test]
+    fn test_script_types() {
+        let script_type = |name: &str| {
+            let json_value = json!({
+                "script_hash": "13a3efd825703a352a8f71f4e2758d08c28c564e8dfcce9f77776ad1",
+                "type": name,
+                "serialised_size": null
+            });
+            serde_json::from_value::<ScriptInfo>(json_value)
+                .unwrap()
+                .script_type
+        };
+
+        assert_eq!(script_type("timelock"), ScriptType::Timelock);
+        assert_eq!(script_type("plutusV1"), ScriptType::PlutusV1);
+        assert_eq!(script_type("plutus"), ScriptType::PlutusV1);
+        assert_eq!(script_type("plutusV2"), ScriptType::PlutusV2);
+        assert_eq!(script_type("plutusV3"), ScriptType::PlutusV3);
+        assert_eq!(script_type("plutusV4"), ScriptType::Unknown);
+        assert_eq!(
+            serde_json::to_value(ScriptType::PlutusV3).unwrap(),
+            json!("plutusV3")
+        );
+        assert_eq!(
+            serde_json::to_value(script_type("plutus")).unwrap(),
+            json!("plutusV1")
+        );
     }
 
     #[tokio::test]
@@ -95,4 +263,103 @@ mod tests {
         ]);
         serde_json::from_value::<Vec<ScriptRedeemersInner>>(json_value).unwrap();
     }
+
+    #[test]
+    fn test_scripts_hash_json_multisig() {
+        let json_value = json!({
+            "json": {
+                "type": "all",
+                "scripts": [
+                    {
+                        "type": "atLeast",
+                        "required": 2,
+                        "scripts": [
+                            { "type": "sig", "keyHash": "e09d36c79dec9bd1b3d9e152247701cd0bb860b5ebfd1de8abb6735a" },
+                            { "type": "sig", "keyHash": "a687dcc24e00dd3caafbeb5e68f97ca8ef269cb6fe971345eb951756" },
+                            { "type": "sig", "keyHash": "0bd1d702b2e6188fe0857a6dc7ffb0675229bab58c86638ffa87ed6d" }
+                        ]
+                    },
+                    {
+                        "type": "any",
+                        "scripts": [
+                            { "type": "after", "slot": 12000000 },
+                            { "type": "before", "slot": 65000000 }
+                        ]
+                    }
+                ]
+            }
+        });
+
+        let script = serde_json::from_value::<ScriptJson>(json_value)
+            .unwrap()
+            .json;
+        let sig = |key_hash: &str| NativeScript::Sig {
+            key_hash: key_hash.to_string(),
+        };
+        let expected = NativeScript::All {
+            scripts: vec![
+                NativeScript::AtLeast {
+                    required: 2,
+                    scripts: vec![
+                        sig("e09d36c79dec9bd1b3d9e152247701cd0bb860b5ebfd1de8abb6735a"),
+                        sig("a687dcc24e00dd3caafbeb5e68f97ca8ef269cb6fe971345eb951756"),
+                        sig("0bd1d702b2e6188fe0857a6dc7ffb0675229bab58c86638ffa87ed6d"),
+                    ],
+                },
+                NativeScript::Any {
+                    scripts: vec![
+                        NativeScript::After { slot: 12000000 },
+                        NativeScript::Before { slot: 65000000 },
+                    ],
+                },
+            ],
+        };
+        assert_eq!(script, Some(expected));
+    }
+
+    #[test]
+    fn test_scripts_hash_json_plutus() {
+        let json_value = json!({ "json": null });
+
+        assert_eq!(
+            serde_json::from_value::<ScriptJson>(json_value)
+                .unwrap()
+                .json,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_reference_script() {
+        let script = json!({
+            "script_hash": "13a3efd825703a352a8f71f4e2758d08c28c564e8dfcce9f77776ad1",
+            "type": "plutusV2",
+            "serialised_size": 3119
+        });
+        let server = MockServer::start(vec![MockResponse::new(200, script.to_string())]);
+        let api = server.api();
+        let mut utxo = serde_json::from_value::<AddressUtxoContentInner>(json!({
+            "address": "addr1qxqs59lphg8g6qndelq8xwqn60ag3aeyfcp33c2kdp46a09re5df3pzwwmyq946axfcejy5n4x0y99wqpgtp2gd0k09qsgy6pz",
+            "tx_hash": "39a7a284c2a0948189dc45dec670211cd4d72f7b66c5726c08d9b3df11e44d58",
+            "tx_index": 0,
+            "output_index": 0,
+            "amount": [{ "unit": "lovelace", "quantity": "42000000" }],
+            "block": "7eb8e27d18686c7db9a18f8bbcfe34e3fed6e047afaa2d969904d15e934847e6",
+            "data_hash": null,
+            "inline_datum": null,
+            "reference_script_hash": null
+        }))
+        .unwrap();
+
+        assert_eq!(utxo.resolve_reference_script(&api).await.unwrap(), None);
+        assert!(server.requests().is_empty());
+
+        utxo.reference_script_hash =
+            Some("13a3efd825703a352a8f71f4e2758d08c28c564e8dfcce9f77776ad1".to_string());
+        let resolved = utxo.resolve_reference_script(&api).await.unwrap().unwrap();
+
+        assert_eq!(resolved.serialised_size, Some(3119));
+        assert!(server.requests()[0]
+            .starts_with("GET /scripts/13a3efd825703a352a8f71f4e2758d08c28c564e8dfcce9f77776ad1 "));
+    }
 }
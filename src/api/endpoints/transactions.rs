@@ -1,22 +1,30 @@
 use crate::{request::send_request, url::Url, *};
 use blockfrost_openapi::models::{
-    tx_content::TxContent, tx_content_delegations_inner::TxContentDelegationsInner,
+    address_utxo_content_inner::AddressUtxoContentInner, tx_content::TxContent,
+    tx_content_delegations_inner::TxContentDelegationsInner,
     tx_content_metadata_cbor_inner::TxContentMetadataCborInner,
     tx_content_metadata_inner::TxContentMetadataInner, tx_content_mirs_inner::TxContentMirsInner,
+    tx_content_pool_certs_inner::TxContentPoolCertsInner,
     tx_content_pool_retires_inner::TxContentPoolRetiresInner,
     tx_content_redeemers_inner::TxContentRedeemersInner,
     tx_content_stake_addr_inner::TxContentStakeAddrInner, tx_content_utxo::TxContentUtxo,
+    tx_content_utxo_inputs_inner::TxContentUtxoInputsInner,
+    tx_content_utxo_outputs_inner::TxContentUtxoOutputsInner,
     tx_content_withdrawals_inner::TxContentWithdrawalsInner,
 };
 use reqwest::{header::HeaderValue, Body, Method};
-use serde_json::from_str as json_from;
+use serde_json::{from_str as json_from, Value as JsonValue};
+use std::collections::HashMap;
 
 impl BlockfrostAPI {
-    /// Obtain information about Move Instantaneous Rewards (MIRs) of a specific transaction.
+    /// Submit a CBOR serialized transaction, returns its hash.
     ///
-    /// OpenAPI endpoint reference: [`/accounts/{stake_address}/mirs`].
+    /// Blockfrost answers `200 OK` once the transaction is in the mempool, any other 2xx status
+    /// is treated as a success too. A full mempool is reported as a `425` error.
     ///
-    /// [`/accounts/{stake_address}/mirs`]: https://docs.blockfrost.io/#tag/Cardano-Transactions/paths/~1tx~1submit/post
+    /// OpenAPI endpoint reference: [`/tx/submit`].
+    ///
+    /// [`/tx/submit`]: https://docs.blockfrost.io/#tag/Cardano-Transactions/paths/~1tx~1submit/post
     pub async fn transactions_submit(&self, transaction_data: Vec<u8>) -> BlockfrostResult<String> {
         let body = Body::from(transaction_data);
         let endpoint_suffix = "/tx/submit";
@@ -28,38 +36,144 @@ impl BlockfrostAPI {
             .header("Content-Type", HeaderValue::from_static("application/cbor"))
             .body(body);
 
-        let (status, text) = send_request(request, self.settings.retry_settings)
-            .await
-            .map_err(|reason| BlockfrostError::Reqwest {
-                url: url.clone(),
-                reason,
-            })?;
+        let (status, headers, text) =
+            send_request(request, self.write_policy())
+                .await
+                .map_err(|reason| BlockfrostError::Reqwest {
+                    url: url.clone(),
+                    reason,
+                })?;
 
         if !status.is_success() {
-            return Err(process_error_response(&text, status, &url));
+            return Err(process_error_response(
+                &text,
+                status,
+                &headers,
+                &url,
+                self.settings.warn_unexpected_status,
+            ));
         }
         json_from(&text).map_err(|reason| json_error(url, text, reason))
     }
 
+    /// [`transactions_submit`](Self::transactions_submit) that won't fail on a transaction that
+    /// was already accepted, safe to call again for the same transaction.
+    ///
+    /// `hash` is the hash of the transaction, as given by the library that built it. When the
+    /// submission fails with a network error (a timeout, say) or with a `400` (the answer to a
+    /// retry once the inputs are spent), it is looked up in the [mempool](Self::mempool_hash) and
+    /// then [on chain](Self::transaction_by_hash). If either knows it, the hash is returned as a
+    /// success, otherwise the submission error is.
+    ///
+    /// A transaction accepted just before a timeout may not be visible yet, calling this again
+    /// later then finds it instead of broadcasting it twice.
+    pub async fn transactions_submit_idempotent(
+        &self, transaction_data: Vec<u8>, hash: &str,
+    ) -> BlockfrostResult<String> {
+        self.check_hex("hash", hash, 32)?;
+
+        let error = match self.transactions_submit(transaction_data).await {
+            Ok(submitted) => return Ok(submitted),
+            Err(error) => error,
+        };
+        let uncertain = match &error {
+            BlockfrostError::Reqwest { .. } => true,
+            BlockfrostError::Response { reason, .. } => reason.status_code == 400,
+            _ => false,
+        };
+
+        if uncertain && self.transaction_known(hash).await {
+            Ok(hash.to_string())
+        } else {
+            Err(error)
+        }
+    }
+
+    // Whether the transaction is in the mempool or on chain, lookup errors count as unknown
+    async fn transaction_known(&self, hash: &str) -> bool {
+        self.mempool_hash(hash).await.is_ok() || self.transaction_by_hash(hash).await.is_ok()
+    }
+
     pub async fn transaction_by_hash(&self, hash: &str) -> BlockfrostResult<TxContent> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}", hash).as_str()).await
     }
 
     pub async fn transactions_utxos(&self, hash: &str) -> BlockfrostResult<TxContentUtxo> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/utxos", hash).as_str())
             .await
     }
 
+    /// Outputs of [`transactions_utxos`](Self::transactions_utxos) as [`Output`]s, collateral
+    /// returns included. The inputs are left out, they are in `transactions_utxos`.
+    pub async fn transactions_utxos_outputs(&self, hash: &str) -> BlockfrostResult<Vec<Output>> {
+        let utxos = self.transactions_utxos(hash).await?;
+
+        Ok(utxos.outputs.into_iter().map(Output::from).collect())
+    }
+
+    /// Change the transaction made to the balance of `address`, per unit: what its outputs pay
+    /// to `address` minus what its inputs spent from it. Units left unchanged are omitted, so an
+    /// address the transaction doesn't touch gets an empty map.
+    ///
+    /// A transaction whose scripts failed spent its collateral and created its collateral return
+    /// instead, checking for that costs one more request for transactions with collateral.
+    pub async fn transactions_net_effect(
+        &self, hash: &str, address: &str,
+    ) -> BlockfrostResult<HashMap<String, i128>> {
+        let utxos = self.transactions_utxos(hash).await?;
+        let scripts_failed = match utxos.collateral_inputs().next() {
+            Some(_) => !self.transaction_by_hash(hash).await?.valid_contract,
+            None => false,
+        };
+        let (inputs, outputs): (Vec<_>, Vec<_>) = if scripts_failed {
+            (
+                utxos.collateral_inputs().collect(),
+                utxos.collateral_outputs().collect(),
+            )
+        } else {
+            (
+                utxos.spent_inputs().collect(),
+                utxos.regular_outputs().collect(),
+            )
+        };
+
+        let parsing_error = |error: BalanceError| BlockfrostError::Parsing {
+            message: error.to_string(),
+        };
+        let (mut spent, mut received) = (Balance::new(), Balance::new());
+        for input in inputs.iter().filter(|input| input.address == address) {
+            input
+                .amount
+                .add_to_balance(&mut spent)
+                .map_err(parsing_error)?;
+        }
+        for output in outputs.iter().filter(|output| output.address == address) {
+            output
+                .amount
+                .add_to_balance(&mut received)
+                .map_err(parsing_error)?;
+        }
+
+        balance_diff(&spent, &received).map_err(parsing_error)
+    }
+
+    /// Stake address registration and deregistration certificates of a transaction.
     pub async fn transactions_stakes(
         &self, hash: &str,
     ) -> BlockfrostResult<Vec<TxContentStakeAddrInner>> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/stakes", hash).as_str())
             .await
     }
 
+    /// Delegation certificates of a transaction, `active_epoch` is the first epoch in which the
+    /// delegation counts.
     pub async fn transactions_delegations(
         &self, hash: &str,
     ) -> BlockfrostResult<Vec<TxContentDelegationsInner>> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/delegations", hash).as_str())
             .await
     }
@@ -67,32 +181,41 @@ impl BlockfrostAPI {
     pub async fn transactions_withdrawals(
         &self, hash: &str,
     ) -> BlockfrostResult<Vec<TxContentWithdrawalsInner>> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/withdrawals", hash).as_str())
             .await
     }
 
     pub async fn transactions_mirs(&self, hash: &str) -> BlockfrostResult<Vec<TxContentMirsInner>> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/mirs", hash).as_str())
             .await
     }
 
+    /// Pool registration and update certificates of a transaction.
     pub async fn transactions_pool_updates(
         &self, hash: &str,
-    ) -> BlockfrostResult<Vec<TxContentMirsInner>> {
+    ) -> BlockfrostResult<Vec<TxContentPoolCertsInner>> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/pool_updates", hash).as_str())
             .await
     }
 
+    /// Pool retirement certificates of a transaction, `retiring_epoch` is the epoch in which the
+    /// pool retires.
     pub async fn transactions_pool_retires(
         &self, hash: &str,
     ) -> BlockfrostResult<Vec<TxContentPoolRetiresInner>> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/pool_retires", hash).as_str())
             .await
     }
 
+    /// Metadata of a transaction as JSON, see [`TxMetadataJson`] for the common labels.
     pub async fn transactions_metadata(
         &self, hash: &str,
     ) -> BlockfrostResult<Vec<TxContentMetadataInner>> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/metadata", hash).as_str())
             .await
     }
@@ -100,6 +223,7 @@ impl BlockfrostAPI {
     pub async fn transactions_metadata_cbor(
         &self, hash: &str,
     ) -> BlockfrostResult<Vec<TxContentMetadataCborInner>> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/metadata/cbor", hash).as_str())
             .await
     }
@@ -107,19 +231,361 @@ impl BlockfrostAPI {
     pub async fn transactions_redeemers(
         &self, hash: &str,
     ) -> BlockfrostResult<Vec<TxContentRedeemersInner>> {
+        self.check_hex("hash", hash, 32)?;
         self.call_endpoint(format!("/txs/{}/redeemers", hash).as_str())
             .await
     }
 }
+
+/// Script validation outcome of a [`TxContent`].
+pub trait TxContentExt {
+    /// Whether a Plutus script failed (`valid_contract` is false): the transaction was added
+    /// to the chain only to consume its collateral, its regular inputs and outputs were not.
+    fn collateral_was_consumed(&self) -> bool;
+}
+
+impl TxContentExt for TxContent {
+    fn collateral_was_consumed(&self) -> bool {
+        !self.valid_contract
+    }
+}
+
+/// Collateral of a [`TxContentUtxo`], flagged by `collateral` on inputs and outputs.
+pub trait TxContentUtxoExt {
+    /// Inputs spent when the scripts succeed, neither collateral nor reference inputs.
+    fn spent_inputs(&self) -> impl Iterator<Item = &TxContentUtxoInputsInner>;
+
+    /// Inputs put up as collateral, spent only when a script fails.
+    fn collateral_inputs(&self) -> impl Iterator<Item = &TxContentUtxoInputsInner>;
+
+    /// Reference inputs, read by the scripts (for their datum or reference script) without
+    /// being spent.
+    fn reference_inputs(&self) -> impl Iterator<Item = &TxContentUtxoInputsInner>;
+
+    /// Outputs created when the scripts succeed.
+    fn regular_outputs(&self) -> impl Iterator<Item = &TxContentUtxoOutputsInner>;
+
+    /// Collateral return outputs, created instead of the regular outputs when a script fails.
+    fn collateral_outputs(&self) -> impl Iterator<Item = &TxContentUtxoOutputsInner>;
+}
+
+impl TxContentUtxoExt for TxContentUtxo {
+    fn spent_inputs(&self) -> impl Iterator<Item = &TxContentUtxoInputsInner> {
+        self.inputs
+            .iter()
+            .filter(|input| !input.collateral && !is_reference(input))
+    }
+
+    fn collateral_inputs(&self) -> impl Iterator<Item = &TxContentUtxoInputsInner> {
+        self.inputs.iter().filter(|input| input.collateral)
+    }
+
+    fn reference_inputs(&self) -> impl Iterator<Item = &TxContentUtxoInputsInner> {
+        self.inputs.iter().filter(|input| is_reference(input))
+    }
+
+    fn regular_outputs(&self) -> impl Iterator<Item = &TxContentUtxoOutputsInner> {
+        self.outputs.iter().filter(|output| !output.collateral)
+    }
+
+    fn collateral_outputs(&self) -> impl Iterator<Item = &TxContentUtxoOutputsInner> {
+        self.outputs.iter().filter(|output| output.collateral)
+    }
+}
+
+// `reference` is missing before Babbage
+fn is_reference(input: &TxContentUtxoInputsInner) -> bool {
+    input.reference == Some(true)
+}
+
+/// Typed views of the well-known metadata labels, the raw JSON stays in `json_metadata`.
+pub trait TxMetadataJson {
+    /// Lines of a transaction message (label 674, CIP-20).
+    fn as_message(&self) -> Option<Vec<String>>;
+    /// NFT metadata of a mint (label 721, CIP-25).
+    fn as_cip25(&self) -> Option<Cip25Metadata>;
+}
+
+impl TxMetadataJson for TxContentMetadataInner {
+    fn as_message(&self) -> Option<Vec<String>> {
+        if self.label != "674" {
+            return None;
+        }
+
+        match self.json_metadata.as_ref()?.get("msg")? {
+            JsonValue::Array(lines) => lines
+                .iter()
+                .map(|line| line.as_str().map(String::from))
+                .collect(),
+            JsonValue::String(line) => Some(vec![line.clone()]),
+            _ => None,
+        }
+    }
+
+    fn as_cip25(&self) -> Option<Cip25Metadata> {
+        if self.label != "721" {
+            return None;
+        }
+
+        let policies = self.json_metadata.as_ref()?.as_object()?;
+        let version = policies.get("version").map(|version| match version {
+            JsonValue::String(version) => version.clone(),
+            version => version.to_string(),
+        });
+        let assets = policies
+            .iter()
+            .filter(|(policy_id, _)| *policy_id != "version")
+            .filter_map(|(policy_id, assets)| Some((policy_id, assets.as_object()?)))
+            .flat_map(|(policy_id, assets)| {
+                assets.iter().map(move |(asset_name, fields)| Cip25Asset {
+                    policy_id: policy_id.clone(),
+                    asset_name: asset_name.clone(),
+                    name: fields.get("name").and_then(metadata_text),
+                    image: fields.get("image").and_then(metadata_text),
+                    media_type: fields.get("mediaType").and_then(metadata_text),
+                    description: fields.get("description").and_then(metadata_text),
+                    fields: fields.clone(),
+                })
+            })
+            .collect();
+
+        Some(Cip25Metadata { version, assets })
+    }
+}
+
+// Metadata strings are limited to 64 bytes, longer ones are split into arrays of strings
+fn metadata_text(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(text) => Some(text.clone()),
+        JsonValue::Array(parts) => parts.iter().map(JsonValue::as_str).collect(),
+        _ => None,
+    }
+}
+
+/// CIP-25 metadata of a mint, created by [`TxMetadataJson::as_cip25`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cip25Metadata {
+    /// CIP-25 version, `None` for the original version 1.0.
+    pub version: Option<String>,
+    pub assets: Vec<Cip25Asset>,
+}
+
+/// Metadata of one asset in [`Cip25Metadata`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cip25Asset {
+    pub policy_id: String,
+    /// Asset name as written in the metadata, UTF-8 in version 1.0 and hex in version 2.0.
+    pub asset_name: String,
+    pub name: Option<String>,
+    /// Image URI, split parts are joined back together.
+    pub image: Option<String>,
+    pub media_type: Option<String>,
+    pub description: Option<String>,
+    /// Every field of the asset, including the ones above.
+    pub fields: JsonValue,
+}
+
+/// Datum attached to an output, created by [`DatumExt::datum`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Datum {
+    /// Only the hash is on the output, the datum itself is supplied when spending it.
+    Hash(String),
+    /// CBOR encoded datum stored in the output.
+    Inline(String),
+    None,
+}
+
+impl Datum {
+    /// Hash of a datum stored off the output.
+    pub fn hash(&self) -> Option<&str> {
+        match self {
+            Datum::Hash(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    /// CBOR of an inline datum.
+    pub fn inline(&self) -> Option<&str> {
+        match self {
+            Datum::Inline(cbor) => Some(cbor),
+            _ => None,
+        }
+    }
+}
+
+/// Outputs carrying a datum in `data_hash` or `inline_datum`.
+pub trait DatumExt {
+    /// The datum of the output as a single value.
+    ///
+    /// Blockfrost fills `data_hash` for inline datums too, so `inline_datum` wins when both are
+    /// set.
+    fn datum(&self) -> Datum;
+}
+
+fn datum(data_hash: &Option<String>, inline_datum: &Option<String>) -> Datum {
+    match (inline_datum, data_hash) {
+        (Some(cbor), _) => Datum::Inline(cbor.clone()),
+        (None, Some(hash)) => Datum::Hash(hash.clone()),
+        (None, None) => Datum::None,
+    }
+}
+
+impl DatumExt for AddressUtxoContentInner {
+    fn datum(&self) -> Datum {
+        datum(&self.data_hash, &self.inline_datum)
+    }
+}
+
+impl DatumExt for TxContentUtxoInputsInner {
+    fn datum(&self) -> Datum {
+        datum(&self.data_hash, &self.inline_datum)
+    }
+}
+
+impl DatumExt for TxContentUtxoOutputsInner {
+    fn datum(&self) -> Datum {
+        datum(&self.data_hash, &self.inline_datum)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::{MockResponse, MockServer};
     use blockfrost_openapi::models::{
+        mempool_tx_content::MempoolTxContent,
         tx_content_metadata_inner_json_metadata::TxContentMetadataInnerJsonMetadata,
-        tx_content_utxo::TxContentUtxo,
+        tx_content_output_amount_inner::TxContentOutputAmountInner, tx_content_utxo::TxContentUtxo,
     };
     use serde_json::json;
 
+    #[tokio::test]
+    async fn test_transactions_submit_accepts_any_success_status() {
+        let hash = "1e043f100dce12d107f679685acd2fc0610e10f72a92d412794c9773d11d8477";
+        for status in [200, 202] {
+            let server =
+                MockServer::start(vec![MockResponse::new(status, json!(hash).to_string())]);
+
+            let submitted = server.api().transactions_submit(vec![0x84]).await.unwrap();
+
+            assert_eq!(submitted, hash);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transactions_submit_retried_only_with_retry_writes() {
+        let hash = "1e043f100dce12d107f679685acd2fc0610e10f72a92d412794c9773d11d8477";
+        for retry_writes in [false, true] {
+            let server = MockServer::start(vec![
+                MockResponse::new(503, ""),
+                MockResponse::new(200, json!(hash).to_string()),
+            ]);
+            let mut settings = BlockFrostSettings::new();
+            settings.retry_settings =
+                RetrySettings::new(3, std::time::Duration::ZERO).retry_writes(retry_writes);
+            let api = server.api_with_settings(settings);
+
+            let result = api.transactions_submit(vec![0x84]).await;
+
+            assert_eq!(result.is_ok(), retry_writes);
+            assert_eq!(server.requests().len(), if retry_writes { 2 } else { 1 });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transactions_submit_mempool_full() {
+        let error = json!({
+            "status_code": 425,
+            "error": "Mempool Full",
+            "message": "Mempool is full, please try resubmitting again later."
+        });
+        let server = MockServer::start(vec![MockResponse::new(425, error.to_string())]);
+
+        let result = server.api().transactions_submit(vec![0x84]).await;
+
+        assert!(matches!(
+            result,
+            Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 425
+        ));
+    }
+
+    // [{0: [], 1: [], 2: 0}, {}, true, null], the hash is BLAKE2b-256 of its body
+    const TRANSACTION: [u8; 11] = [
+        0x84, 0xa3, 0x00, 0x80, 0x01, 0x80, 0x02, 0x00, 0xa0, 0xf5, 0xf6,
+    ];
+    const TRANSACTION_HASH: &str =
+        "36fdff68dfe3660f1ceea60f018a0fd7a83da13def229108794c397a879b0436";
+
+    fn bad_inputs() -> MockResponse {
+        let error = json!({
+            "status_code": 400,
+            "error": "Bad Request",
+            "message": "BadInputsUTxO"
+        });
+        MockResponse::new(400, error.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_transactions_submit_idempotent_already_accepted() {
+        let server = MockServer::start_with(|_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            if path == format!("/mempool/{}", TRANSACTION_HASH) {
+                let transaction = MempoolTxContent::default();
+                MockResponse::new(200, serde_json::to_string(&transaction).unwrap())
+            } else {
+                bad_inputs()
+            }
+        });
+
+        let submitted = server
+            .api()
+            .transactions_submit_idempotent(TRANSACTION.to_vec(), TRANSACTION_HASH)
+            .await
+            .unwrap();
+
+        assert_eq!(submitted, TRANSACTION_HASH);
+        assert!(server.requests()[1].starts_with(&format!("GET /mempool/{}", TRANSACTION_HASH)));
+    }
+
+    #[tokio::test]
+    async fn test_transactions_submit_idempotent_unknown() {
+        let not_found = json!({ "status_code": 404, "error": "Not Found", "message": "" });
+        let server = MockServer::start(vec![
+            bad_inputs(),
+            MockResponse::new(404, not_found.to_string()),
+        ]);
+
+        let result = server
+            .api()
+            .transactions_submit_idempotent(TRANSACTION.to_vec(), TRANSACTION_HASH)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 400
+        ));
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_datum() {
+        let hash = "923918e403bf43c34b4ef6b48eb2ee04babed17320d8d1b9ff9ad086e86f44ec";
+        let mut output = TxContentUtxoOutputsInner::default();
+
+        assert_eq!(output.datum(), Datum::None);
+        assert_eq!(output.datum().hash(), None);
+
+        output.data_hash = Some(hash.to_string());
+        assert_eq!(output.datum(), Datum::Hash(hash.to_string()));
+        assert_eq!(output.datum().hash(), Some(hash));
+        assert_eq!(output.datum().inline(), None);
+
+        output.inline_datum = Some("19a6aa".to_string());
+        assert_eq!(output.datum(), Datum::Inline("19a6aa".to_string()));
+        assert_eq!(output.datum().inline(), Some("19a6aa"));
+        assert_eq!(output.datum().hash(), None);
+    }
+
     #[tokio::test]
     async fn test_transaction() {
         let json_value = json!({
@@ -246,6 +712,254 @@ mod tests {
         serde_json::from_value::<TxContentUtxo>(json_value).unwrap();
     }
 
+    #[test]
+    fn test_failed_script_transaction() {
+        let address = "addr1q9ld26v2lv8wvrxxmvg90pn8n8n5k6tdst06q2s856rwmvnueldzuuqmnsye359fqrk8hwvenjnqultn7djtrlft7jnq7dy7wv";
+        let transaction = TxContent {
+            valid_contract: false,
+            ..Default::default()
+        };
+        let utxos = serde_json::from_value::<TxContentUtxo>(json!({
+            "hash": "1e043f100dce12d107f679685acd2fc0610e10f72a92d412794c9773d11d8477",
+            "inputs": [
+                {
+                    "address": address,
+                    "amount": [{ "unit": "lovelace", "quantity": "42000000" }],
+                    "tx_hash": "1a0570af966fb355a7160e4f82d5a80b8681b7955f5d44bec0dce628516157f0",
+                    "output_index": 0,
+                    "data_hash": "9e478573ab81ea7a8e31891ce0648b81229f408d596a3483e6f4f9b92d3cf710",
+                    "inline_datum": null,
+                    "reference_script_hash": null,
+                    "collateral": false
+                },
+                {
+                    "address": address,
+                    "amount": [{ "unit": "lovelace", "quantity": "5000000" }],
+                    "tx_hash": "8788591983aa73981fc92d6cddbbe643959f5a784e84b8bee0db15823f575a5b",
+                    "output_index": 1,
+                    "data_hash": null,
+                    "inline_datum": null,
+                    "reference_script_hash": null,
+                    "collateral": true
+                }
+            ],
+            "outputs": [
+                {
+                    "address": address,
+                    "amount": [{ "unit": "lovelace", "quantity": "41800000" }],
+                    "output_index": 0,
+                    "data_hash": null,
+                    "inline_datum": null,
+                    "reference_script_hash": null,
+                    "collateral": false
+                },
+                {
+                    "address": address,
+                    "amount": [{ "unit": "lovelace", "quantity": "4700000" }],
+                    "output_index": 1,
+                    "data_hash": null,
+                    "inline_datum": null,
+                    "reference_script_hash": null,
+                    "collateral": true
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert!(transaction.collateral_was_consumed());
+        assert!(!TxContent {
+            valid_contract: true,
+            ..Default::default()
+        }
+        .collateral_was_consumed());
+
+        let collateral: Vec<_> = utxos.collateral_inputs().collect();
+        assert_eq!(collateral.len(), 1);
+        assert_eq!(collateral[0].amount[0].quantity, "5000000");
+        let returned: Vec<_> = utxos.collateral_outputs().collect();
+        assert_eq!(returned.len(), 1);
+        assert_eq!(returned[0].amount[0].quantity, "4700000");
+    }
+
+    #[test]
+    fn test_input_and_output_kinds() {
+        let input =
+            |tx_hash: &str, collateral: bool, reference: Option<bool>| TxContentUtxoInputsInner {
+                tx_hash: tx_hash.to_string(),
+                collateral,
+                reference,
+                ..Default::default()
+            };
+        let output = |output_index: i32, collateral: bool| TxContentUtxoOutputsInner {
+            output_index,
+            collateral,
+            ..Default::default()
+        };
+        let utxos = TxContentUtxo {
+            inputs: vec![
+                input("spent", false, Some(false)),
+                input("collateral", true, Some(false)),
+                input("reference", false, Some(true)),
+                // Before Babbage `reference` is not sent
+                input("legacy", false, None),
+            ],
+            outputs: vec![output(0, false), output(1, false), output(2, true)],
+            ..Default::default()
+        };
+
+        let hashes = |inputs: Vec<&TxContentUtxoInputsInner>| -> Vec<String> {
+            inputs.iter().map(|input| input.tx_hash.clone()).collect()
+        };
+        assert_eq!(hashes(utxos.spent_inputs().collect()), ["spent", "legacy"]);
+        assert_eq!(hashes(utxos.collateral_inputs().collect()), ["collateral"]);
+        assert_eq!(hashes(utxos.reference_inputs().collect()), ["reference"]);
+
+        let indexes: Vec<_> = utxos
+            .regular_outputs()
+            .map(|output| output.output_index)
+            .collect();
+        assert_eq!(indexes, [0, 1]);
+        assert_eq!(utxos.collateral_outputs().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_transactions_utxos_outputs() {
+        let hash = "1e043f100dce12d107f679685acd2fc0610e10f72a92d412794c9773d11d8477";
+        let utxos = TxContentUtxo {
+            hash: hash.to_string(),
+            inputs: vec![TxContentUtxoInputsInner::default()],
+            outputs: vec![
+                TxContentUtxoOutputsInner {
+                    address: "addr1".to_string(),
+                    data_hash: Some(
+                        "9e478573ab81ea7a8e31891ce0648b81229f408d596a3483e6f4f9b92d3cf710"
+                            .to_string(),
+                    ),
+                    ..Default::default()
+                },
+                TxContentUtxoOutputsInner {
+                    collateral: true,
+                    ..Default::default()
+                },
+            ],
+        };
+        let server = MockServer::start(vec![MockResponse::new(200, json!(utxos).to_string())]);
+
+        let outputs = server.api().transactions_utxos_outputs(hash).await.unwrap();
+
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0], Output::from(utxos.outputs[0].clone()));
+        assert!(outputs[1].collateral);
+        assert_eq!(
+            server.requests()[0].split_whitespace().nth(1),
+            Some(format!("/txs/{}/utxos", hash).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transactions_net_effect() {
+        const WALLET: &str = "addr1qxqs59lphg8g6qndelq8xwqn60ag3aeyfcp33c2kdp46a09re5df3pzwwmyq946axfcejy5n4x0y99wqpgtp2gd0k09qsgy6pz";
+        const SHOP: &str = "addr1q9ld26v2lv8wvrxxmvg90pn8n8n5k6tdst06q2s856rwmvnueldzuuqmnsye359fqrk8hwvenjnqultn7djtrlft7jnq7dy7wv";
+        const NFT: &str = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e";
+        const TOKEN: &str =
+            "476039a0949cf0b22f6a800f56780184c44533887ca6e821007840c36e7574636f696e";
+        let amount = |units: &[(&str, &str)]| -> Vec<TxContentOutputAmountInner> {
+            units
+                .iter()
+                .map(|(unit, quantity)| TxContentOutputAmountInner {
+                    unit: unit.to_string(),
+                    quantity: quantity.to_string(),
+                })
+                .collect()
+        };
+        let input =
+            |address: &str, units: &[(&str, &str)], collateral: bool| TxContentUtxoInputsInner {
+                address: address.to_string(),
+                amount: amount(units),
+                collateral,
+                ..Default::default()
+            };
+        let output =
+            |address: &str, units: &[(&str, &str)], collateral: bool| TxContentUtxoOutputsInner {
+                address: address.to_string(),
+                amount: amount(units),
+                collateral,
+                ..Default::default()
+            };
+        // The wallet buys a token with 5 ADA and an NFT, paying fees from its change
+        let purchase = TxContentUtxo {
+            inputs: vec![
+                input(WALLET, &[("lovelace", "10000000"), (NFT, "1")], false),
+                input(SHOP, &[("lovelace", "2000000"), (TOKEN, "500")], false),
+            ],
+            outputs: vec![
+                output(WALLET, &[("lovelace", "4800000"), (TOKEN, "500")], false),
+                output(SHOP, &[("lovelace", "7000000"), (NFT, "1")], false),
+            ],
+            ..Default::default()
+        };
+        // The same wallet sending a script transaction that failed, losing its collateral
+        let failed = TxContentUtxo {
+            inputs: vec![
+                input(WALLET, &[("lovelace", "10000000")], false),
+                input(WALLET, &[("lovelace", "5000000")], true),
+            ],
+            outputs: vec![
+                output(SHOP, &[("lovelace", "9800000")], false),
+                output(WALLET, &[("lovelace", "4500000")], true),
+            ],
+            ..Default::default()
+        };
+        let purchase_hash = "6e5f825c42c1c6d6b77f2a14092f3b78c8f1b66db6f4cf8a6c3b5e8d5e1e4d2a";
+        let failed_hash = "1e043f100dce12d107f679685acd2fc0610e10f72a92d412794c9773d11d8477";
+        let server = MockServer::start_with(move |_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let body = if path == format!("/txs/{}/utxos", purchase_hash) {
+                json!(purchase)
+            } else if path == format!("/txs/{}/utxos", failed_hash) {
+                json!(failed)
+            } else {
+                json!(TxContent {
+                    valid_contract: false,
+                    ..Default::default()
+                })
+            };
+            MockResponse::new(200, body.to_string())
+        });
+        let api = server.api();
+
+        let effect = api
+            .transactions_net_effect(purchase_hash, WALLET)
+            .await
+            .unwrap();
+        assert_eq!(
+            effect,
+            HashMap::from([
+                ("lovelace".to_string(), -5200000),
+                (NFT.to_string(), -1),
+                (TOKEN.to_string(), 500),
+            ])
+        );
+        let shop = api
+            .transactions_net_effect(purchase_hash, SHOP)
+            .await
+            .unwrap();
+        assert_eq!(shop["lovelace"], 5000000);
+        assert_eq!(shop[TOKEN], -500);
+        assert_eq!(server.requests().len(), 2);
+
+        let effect = api
+            .transactions_net_effect(failed_hash, WALLET)
+            .await
+            .unwrap();
+        assert_eq!(effect, HashMap::from([("lovelace".to_string(), -500000)]));
+        assert!(api
+            .transactions_net_effect(failed_hash, SHOP)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn test_transaction_stakes() {
         let json_value = json!([
@@ -253,10 +967,19 @@ mod tests {
                 "cert_index": 0,
                 "address": "stake1u9t3a0tcwune5xrnfjg4q7cpvjlgx9lcv0cuqf5mhfjwrvcwrulda",
                 "registration": true
+            },
+            {
+                "cert_index": 1,
+                "address": "stake1u9r76ypf5fskppa0cmttas05cgcswrttn6jrq4yd7jpdnvc7gt0yc",
+                "registration": false
             }
         ]);
 
-        serde_json::from_value::<Vec<TxContentStakeAddrInner>>(json_value).unwrap();
+        let certs = serde_json::from_value::<Vec<TxContentStakeAddrInner>>(json_value).unwrap();
+
+        assert!(certs[0].registration);
+        assert!(!certs[1].registration);
+        assert_eq!(certs[1].cert_index, 1);
     }
 
     #[tokio::test]
@@ -270,7 +993,14 @@ mod tests {
                 "active_epoch": 210
             }
         ]);
-        serde_json::from_value::<Vec<TxContentDelegationsInner>>(json_value).unwrap();
+        let delegations =
+            serde_json::from_value::<Vec<TxContentDelegationsInner>>(json_value).unwrap();
+
+        assert_eq!(delegations[0].active_epoch, 210);
+        assert_eq!(
+            delegations[0].pool_id,
+            "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy"
+        );
     }
 
     #[tokio::test]
@@ -334,7 +1064,11 @@ mod tests {
             }
         ]);
 
-        serde_json::from_value::<Vec<TxContentMetadataInnerJsonMetadata>>(json_value).unwrap();
+        let certs = serde_json::from_value::<Vec<TxContentPoolCertsInner>>(json_value).unwrap();
+
+        assert_eq!(certs[0].pledge, "5000000000");
+        assert_eq!(certs[0].active_epoch, 210);
+        assert_eq!(certs[0].relays[0].port, 3001);
     }
 
     #[tokio::test]
@@ -347,7 +1081,9 @@ mod tests {
             }
         ]);
 
-        serde_json::from_value::<Vec<TxContentMetadataInnerJsonMetadata>>(json_value).unwrap();
+        let retires = serde_json::from_value::<Vec<TxContentPoolRetiresInner>>(json_value).unwrap();
+
+        assert_eq!(retires[0].retiring_epoch, 216);
     }
 
     #[tokio::test]
@@ -388,4 +1124,62 @@ mod tests {
 
         serde_json::from_value::<Vec<TxContentMetadataCborInner>>(json_value).unwrap();
     }
+
+    #[test]
+    fn test_transaction_metadata_message() {
+        let metadata = serde_json::from_value::<TxContentMetadataInner>(json!({
+            "label": "674",
+            "json_metadata": {
+                "msg": ["Invoice-No: 1234567890", "Customer-No: 555-1234"]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            metadata.as_message().unwrap(),
+            ["Invoice-No: 1234567890", "Customer-No: 555-1234"]
+        );
+        assert_eq!(metadata.as_cip25(), None);
+    }
+
+    #[test]
+    fn test_transaction_metadata_cip25() {
+        let metadata = serde_json::from_value::<TxContentMetadataInner>(json!({
+            "label": "721",
+            "json_metadata": {
+                "d5e6bf0500378d4f0da4e8dde6becec7621cd8cbf5cbb9b87013d4cc": {
+                    "SpaceBud3411": {
+                        "name": "SpaceBud #3411",
+                        "type": "Alien",
+                        "image": [
+                            "ipfs://QmUiyXyj6xsw3SRV2SX2ffkGXcWFufyGXyN6STHmh8Nx44/",
+                            "3411.png"
+                        ],
+                        "traits": ["Chestplate", "Belt"]
+                    }
+                },
+                "version": "1.0"
+            }
+        }))
+        .unwrap();
+
+        let cip25 = metadata.as_cip25().unwrap();
+        let asset = &cip25.assets[0];
+
+        assert_eq!(cip25.version.as_deref(), Some("1.0"));
+        assert_eq!(cip25.assets.len(), 1);
+        assert_eq!(
+            asset.policy_id,
+            "d5e6bf0500378d4f0da4e8dde6becec7621cd8cbf5cbb9b87013d4cc"
+        );
+        assert_eq!(asset.asset_name, "SpaceBud3411");
+        assert_eq!(asset.name.as_deref(), Some("SpaceBud #3411"));
+        assert_eq!(
+            asset.image.as_deref(),
+            Some("ipfs://QmUiyXyj6xsw3SRV2SX2ffkGXcWFufyGXyN6STHmh8Nx44/3411.png")
+        );
+        assert_eq!(asset.media_type, None);
+        assert_eq!(asset.fields["type"], "Alien");
+        assert_eq!(metadata.as_message(), None);
+    }
 }
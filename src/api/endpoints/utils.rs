@@ -1,7 +1,9 @@
 use crate::{request::send_request, url::Url, *};
 use blockfrost_openapi::models::utils_addresses_xpub::UtilsAddressesXpub;
 use reqwest::{header::HeaderValue, Body, Method};
+use serde::{Deserialize, Serialize};
 use serde_json::{from_str as json_from, Value};
+use std::collections::BTreeMap;
 
 impl BlockfrostAPI {
     pub async fn derive_address(
@@ -14,6 +16,13 @@ impl BlockfrostAPI {
         .await
     }
 
+    /// Evaluate the execution units of a CBOR serialized transaction, any 2xx status is a success.
+    ///
+    /// The units of each redeemer can be read with [`EvaluationResult::from_response`].
+    ///
+    /// OpenAPI endpoint reference: [`/utils/txs/evaluate`].
+    ///
+    /// [`/utils/txs/evaluate`]: https://docs.blockfrost.io/#tag/Cardano-Utilities/paths/~1utils~1txs~1evaluate/post
     pub async fn utils_tx_evaluate(&self, transaction_data: Vec<u8>) -> BlockfrostResult<Value> {
         let body = Body::from(transaction_data);
         let url = Url::from_endpoint(self.base_url.as_str(), "/utils/txs/evaluate")?;
@@ -24,17 +33,191 @@ impl BlockfrostAPI {
             .header("Content-Type", HeaderValue::from_static("application/cbor"))
             .body(body);
 
-        let (status, text) = send_request(request, self.settings.retry_settings)
-            .await
-            .map_err(|reason| BlockfrostError::Reqwest {
-                url: url.clone(),
-                reason,
-            })?;
+        let (status, headers, text) =
+            send_request(request, self.write_policy())
+                .await
+                .map_err(|reason| BlockfrostError::Reqwest {
+                    url: url.clone(),
+                    reason,
+                })?;
 
         if !status.is_success() {
-            return Err(process_error_response(&text, status, &url));
+            return Err(process_error_response(
+                &text,
+                status,
+                &headers,
+                &url,
+                self.settings.warn_unexpected_status,
+            ));
         }
 
         json_from(&text).map_err(|reason| json_error(url, text, reason))
     }
 }
+
+/// Execution units used by scripts, memory and CPU steps.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct ExUnits {
+    pub memory: u64,
+    pub steps: u64,
+}
+
+/// Execution units of each redeemer of an evaluated transaction, read from the response of
+/// [`utils_tx_evaluate`](BlockfrostAPI::utils_tx_evaluate).
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct EvaluationResult {
+    /// Units by redeemer, keyed by purpose and index such as `spend:0` or `mint:1`.
+    pub redeemers: BTreeMap<String, ExUnits>,
+}
+
+impl EvaluationResult {
+    /// Units of each redeemer in an evaluation response, in the Ogmios v5 format the endpoint
+    /// returns (`result.EvaluationResult`) or the v6 one (a `result` array of budgets).
+    ///
+    /// `None` when the evaluation failed, the reason is then in `result.EvaluationFailure`
+    /// (v5) or `error` (v6).
+    pub fn from_response(response: &Value) -> Option<Self> {
+        let result = response.get("result")?;
+
+        let redeemers = match result {
+            Value::Object(result) => result
+                .get("EvaluationResult")?
+                .as_object()?
+                .iter()
+                .map(|(redeemer, units)| {
+                    let units = ExUnits {
+                        memory: units.get("memory")?.as_u64()?,
+                        steps: units.get("steps")?.as_u64()?,
+                    };
+                    Some((redeemer.clone(), units))
+                })
+                .collect::<Option<_>>()?,
+            Value::Array(budgets) => budgets
+                .iter()
+                .map(|budget| {
+                    let validator = budget.get("validator")?;
+                    let redeemer = format!(
+                        "{}:{}",
+                        validator.get("purpose")?.as_str()?,
+                        validator.get("index")?.as_u64()?
+                    );
+                    let units = ExUnits {
+                        memory: budget.get("budget")?.get("memory")?.as_u64()?,
+                        steps: budget.get("budget")?.get("cpu")?.as_u64()?,
+                    };
+                    Some((redeemer, units))
+                })
+                .collect::<Option<_>>()?,
+            _ => return None,
+        };
+
+        Some(Self { redeemers })
+    }
+
+    /// Units of all the redeemers together, what the transaction limits apply to.
+    pub fn total_budget(&self) -> ExUnits {
+        self.redeemers
+            .values()
+            .fold(ExUnits::default(), |total, units| ExUnits {
+                memory: total.memory.saturating_add(units.memory),
+                steps: total.steps.saturating_add(units.steps),
+            })
+    }
+
+    /// Whether the [total budget](Self::total_budget) stays within the limits of a transaction,
+    /// see [`EpochParamContentExt::max_tx_ex_mem_units`] and
+    /// [`max_tx_ex_steps_units`](EpochParamContentExt::max_tx_ex_steps_units).
+    pub fn fits_within(&self, max_mem: u64, max_steps: u64) -> bool {
+        let total = self.total_budget();
+
+        total.memory <= max_mem && total.steps <= max_steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_evaluation_result() {
+        let response = json!({
+            "type": "jsonwsp/response",
+            "version": "1.0",
+            "servicename": "ogmios",
+            "methodname": "EvaluateTx",
+            "result": {
+                "EvaluationResult": {
+                    "spend:0": { "memory": 1700, "steps": 476468 },
+                    "spend:1": { "memory": 2_000_000, "steps": 600_000_000 },
+                    "mint:0": { "memory": 300_000, "steps": 90_000_000 }
+                }
+            },
+            "reflection": { "id": "4ab7f3e1-6a5c-4d3e-9d7b-8f1c2b3a4d5e" }
+        });
+
+        let result = EvaluationResult::from_response(&response).unwrap();
+
+        assert_eq!(result.redeemers.len(), 3);
+        assert_eq!(
+            result.redeemers["spend:0"],
+            ExUnits {
+                memory: 1700,
+                steps: 476468
+            }
+        );
+        assert_eq!(
+            result.total_budget(),
+            ExUnits {
+                memory: 2_301_700,
+                steps: 690_476_468
+            }
+        );
+        // Mainnet limits
+        assert!(result.fits_within(14_000_000, 10_000_000_000));
+        assert!(!result.fits_within(2_000_000, 10_000_000_000));
+        assert!(!result.fits_within(14_000_000, 690_000_000));
+    }
+
+    #[test]
+    fn test_evaluation_result_v6() {
+        let response = json!({
+            "jsonrpc": "2.0",
+            "method": "evaluateTransaction",
+            "result": [
+                { "validator": { "purpose": "spend", "index": 0 }, "budget": { "memory": 1700, "cpu": 476468 } },
+                { "validator": { "purpose": "mint", "index": 0 }, "budget": { "memory": 300, "cpu": 500 } }
+            ]
+        });
+
+        let result = EvaluationResult::from_response(&response).unwrap();
+
+        assert_eq!(
+            result.total_budget(),
+            ExUnits {
+                memory: 2000,
+                steps: 476968
+            }
+        );
+    }
+
+    #[test]
+    fn test_evaluation_failure() {
+        let response = json!({
+            "type": "jsonwsp/response",
+            "result": {
+                "EvaluationFailure": {
+                    "ScriptFailures": { "spend:0": [{ "extraRedeemers": ["spend:0"] }] }
+                }
+            }
+        });
+
+        assert_eq!(EvaluationResult::from_response(&response), None);
+        assert_eq!(
+            EvaluationResult::default().total_budget(),
+            ExUnits::default()
+        );
+    }
+}
@@ -1,69 +1,751 @@
 pub(super) mod endpoints;
 use crate::{
+    json_error,
     pagination::Pagination,
-    request::{fetch_all_pages, send_get_request},
+    process_error_response,
+    rate_limiter::RateLimiter,
+    request::{is_retryable_error, send_get_request, send_request, RequestPolicy},
+    reqwest_error,
     url::Url,
-    utils::build_header_map,
-    utils::create_client_with_project_id,
-    BlockFrostSettings, BlockfrostError,
+    utils::{build_header_map, configure_client},
+    BlockFrostSettings, BlockfrostError, BlockfrostResult, Network,
+};
+use blockfrost_openapi::models::genesis_content::GenesisContent;
+use futures::future;
+use reqwest::{header::HeaderValue, ClientBuilder};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
 };
-use reqwest::ClientBuilder;
 
 #[derive(Debug, Clone)]
 pub struct BlockfrostAPI {
-    base_url: String,
-    settings: BlockFrostSettings,
+    pub(crate) base_url: String,
+    pub(crate) settings: BlockFrostSettings,
     client: reqwest::Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    // Genesis constants never change, fetched once and shared by every clone
+    pub(crate) genesis: Arc<OnceLock<GenesisContent>>,
+    // Version reported by the root endpoint, fetched once like the genesis
+    pub(crate) backend_version: Arc<OnceLock<String>>,
+    // Whether a `404` was already blamed on the backend version, said once per api
+    pub(crate) warned_not_found: Arc<AtomicBool>,
+    // Index of the base url that answered last, 0 is `base_url` and the rest are fallbacks
+    last_base: Arc<AtomicUsize>,
 }
 
 impl BlockfrostAPI {
     pub fn new(project_id: &str, settings: BlockFrostSettings) -> Self {
-        let client = create_client_with_project_id(project_id);
-        let base_url = Url::get_base_url_from_project_id(project_id);
+        // Safety: only headers, decompression and the timeout are configured, this can't fail
+        Self::new_with_client(project_id, settings, ClientBuilder::new()).unwrap()
+    }
 
-        Self {
-            settings,
-            client,
-            base_url,
-        }
+    /// Like [`new`](Self::new), but first checks the project id with
+    /// [`BlockFrostSettings::validate`], so a key for the wrong network fails right away.
+    pub fn try_new(
+        project_id: &str, settings: BlockFrostSettings,
+    ) -> Result<Self, BlockfrostError> {
+        settings.validate(project_id)?;
+        Ok(Self::new(project_id, settings))
     }
 
     pub fn new_with_client(
         project_id: &str, settings: BlockFrostSettings, client_builder: ClientBuilder,
     ) -> reqwest::Result<Self> {
-        let base_url = Url::get_base_url_from_project_id(project_id);
+        let base_url = settings
+            .base_url
+            .clone()
+            .unwrap_or_else(|| Url::get_base_url_from_project_id(project_id));
+        let rate_limiter = settings
+            .rate_limit
+            .map(|limit| Arc::new(RateLimiter::new(limit)));
+        // Mandatory headers replace extra headers with the same name
+        let mut headers = settings.extra_headers.clone();
+        headers.extend(build_header_map(project_id));
 
-        client_builder
-            .default_headers(build_header_map(project_id))
+        configure_client(client_builder, &settings)
+            .default_headers(headers)
             .build()
             .map(|client| Self {
                 settings,
                 client,
                 base_url,
+                rate_limiter,
+                genesis: Arc::default(),
+                backend_version: Arc::default(),
+                warned_not_found: Arc::default(),
+                last_base: Arc::default(),
             })
     }
 
-    async fn call_endpoint<T>(&self, url_endpoint: &str) -> Result<T, BlockfrostError>
+    pub(crate) async fn call_endpoint<T>(&self, url_endpoint: &str) -> Result<T, BlockfrostError>
     where
         T: for<'de> serde::Deserialize<'de> + serde::de::DeserializeOwned,
     {
-        let url = Url::from_endpoint(self.base_url.as_str(), url_endpoint)?;
-
-        send_get_request(&self.client, url, self.settings.retry_settings).await
+        self.get_with_failover(|base_url| Url::from_endpoint(base_url, url_endpoint))
+            .await
     }
 
-    async fn call_paged_endpoint<T>(
+    pub(crate) async fn call_paged_endpoint<T>(
         &self, url_endpoint: &str, pagination: Pagination,
     ) -> Result<Vec<T>, BlockfrostError>
     where
         T: for<'de> serde::Deserialize<'de> + serde::de::DeserializeOwned,
     {
-        let url = Url::from_paginated_endpoint(self.base_url.as_str(), url_endpoint, pagination)?;
-
         if pagination.fetch_all {
-            fetch_all_pages(&self.client, url, self.settings.retry_settings, pagination).await
+            self.fetch_all_pages(url_endpoint, pagination).await
+        } else {
+            self.get_with_failover(|base_url| {
+                Url::from_paginated_endpoint(base_url, url_endpoint, pagination)
+            })
+            .await
+        }
+    }
+
+    // Pages are fetched in concurrent batches until one is shorter than `count`, each page
+    // failing over on its own
+    async fn fetch_all_pages<T>(
+        &self, url_endpoint: &str, pagination: Pagination,
+    ) -> Result<Vec<T>, BlockfrostError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        const BATCH_SIZE: usize = 10;
+
+        let mut page_start: usize = 1;
+        let mut result = Vec::new();
+
+        loop {
+            let batch = (page_start..page_start + BATCH_SIZE).map(|page| {
+                let pagination = Pagination { page, ..pagination };
+
+                self.get_with_failover::<Vec<T>>(move |base_url| {
+                    Url::from_paginated_endpoint(base_url, url_endpoint, pagination)
+                })
+            });
+
+            for data in future::try_join_all(batch).await? {
+                let is_end = data.len() < pagination.count;
+                result.extend(data);
+
+                if is_end {
+                    return Ok(result);
+                }
+            }
+
+            page_start += BATCH_SIZE;
+        }
+    }
+
+    // Try the base url, then each fallback in order, moving on only after retryable errors
+    async fn get_with_failover<T>(
+        &self, url_for: impl Fn(&str) -> Result<String, Box<dyn Error>>,
+    ) -> Result<T, BlockfrostError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let fallbacks = &self.settings.fallbacks;
+
+        for index in 0..=fallbacks.len() {
+            let (base_url, project_id) = match index {
+                0 => (self.base_url.as_str(), None),
+                _ => (
+                    fallbacks[index - 1].0.as_str(),
+                    Some(&fallbacks[index - 1].1),
+                ),
+            };
+            let url = url_for(base_url)?;
+            let mut request = self.client.get(&url);
+
+            if let Some(project_id) = project_id {
+                let mut project_id =
+                    HeaderValue::from_str(project_id).map_err(|_| BlockfrostError::Parsing {
+                        message: format!("invalid project_id for fallback {}", base_url),
+                    })?;
+                project_id.set_sensitive(true);
+                request = request.header("project_id", project_id);
+            }
+
+            match send_get_request(request, url, self.request_policy()).await {
+                Err(error) if index < fallbacks.len() && is_retryable_error(&error) => continue,
+                result => {
+                    match &result {
+                        Ok(_) => self.last_base.store(index, Ordering::Relaxed),
+                        Err(error) if error.is_not_found() => self.warn_not_found(),
+                        Err(_) => {}
+                    }
+                    return result;
+                }
+            }
+        }
+
+        unreachable!("the base url is always tried")
+    }
+
+    // A `404` also comes from endpoints an older self-hosted backend doesn't serve, worth a
+    // note when the version is known. Most are missing items, so it is said only once.
+    fn warn_not_found(&self) {
+        let Some(version) = self
+            .settings
+            .api_version
+            .as_ref()
+            .or(self.backend_version.get())
+        else {
+            return;
+        };
+        if self.settings.warn_unexpected_status
+            && !self.warned_not_found.swap(true, Ordering::Relaxed)
+        {
+            eprintln!(
+                "Warning: got a 404 from backend version {}, it may be too old for the endpoint.",
+                version
+            );
+        }
+    }
+
+    /// Base url of the last successful request, the primary one or one of the
+    /// [`fallbacks`](BlockFrostSettings::fallbacks).
+    pub fn last_base_url(&self) -> &str {
+        match self.last_base.load(Ordering::Relaxed) {
+            0 => &self.base_url,
+            index => &self.settings.fallbacks[index - 1].0,
+        }
+    }
+
+    /// Network of the base url, `None` when it is not one of the Blockfrost urls.
+    pub fn cardano_network(&self) -> Option<Network> {
+        Network::from_base_url(&self.base_url)
+    }
+
+    /// Persist whatever the api buffers, call it before the process exits since `Drop` can't
+    /// wait on it.
+    ///
+    /// Nothing is buffered yet, this is a no-op for now. It is idempotent: calling it again, or
+    /// from several clones, is harmless.
+    pub async fn flush(&self) -> BlockfrostResult<()> {
+        Ok(())
+    }
+
+    /// GET any endpoint, with the same headers, retries, rate limiting and error handling as
+    /// the typed methods. Meant for endpoints this crate does not cover yet.
+    ///
+    /// `path` is relative to the base url, like `"/network/eras"`. With a `pagination` its
+    /// query parameters are added, [`Pagination::all`] fetches every page and `T` must then
+    /// deserialize from the concatenated JSON array.
+    ///
+    /// The typed methods should be preferred, nothing guarantees the shape of responses from
+    /// endpoints the crate does not model.
+    pub async fn get<T: DeserializeOwned>(
+        &self, path: &str, pagination: Option<Pagination>,
+    ) -> Result<T, BlockfrostError> {
+        match pagination {
+            Some(pagination) if pagination.fetch_all => {
+                let items: Vec<Value> = self.call_paged_endpoint(path, pagination).await?;
+
+                serde_json::from_value(Value::Array(items)).map_err(|reason| {
+                    BlockfrostError::Json {
+                        url: Url::from_endpoint(self.base_url.as_str(), path).unwrap_or_default(),
+                        text: String::new(),
+                        reason,
+                    }
+                })
+            }
+            Some(pagination) => {
+                self.get_with_failover(|base_url| {
+                    Url::from_paginated_endpoint(base_url, path, pagination)
+                })
+                .await
+            }
+            None => {
+                self.get_with_failover(|base_url| Url::from_endpoint(base_url, path))
+                    .await
+            }
+        }
+    }
+
+    /// One page of a paginated endpoint behind an [`Arc`], like [`get`](Self::get) with a
+    /// pagination.
+    ///
+    /// Prefer it when a page is handed to many consumers (tasks or channels of an indexer), each
+    /// gets a cheap clone of the `Arc` instead of a copy of every element. The typed methods
+    /// returning an owned `Vec` remain the simpler choice when a page has a single consumer.
+    pub async fn call_paged_endpoint_shared<T: DeserializeOwned>(
+        &self, path: &str, pagination: Pagination,
+    ) -> Result<Arc<Vec<T>>, BlockfrostError> {
+        self.call_paged_endpoint(path, pagination)
+            .await
+            .map(Arc::new)
+    }
+
+    /// POST `body` as JSON to any endpoint, see [`get`](Self::get).
+    pub async fn post<T, B>(&self, path: &str, body: &B) -> Result<T, BlockfrostError>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        let url = Url::from_endpoint(self.base_url.as_str(), path)?;
+        let request = self.client.post(&url).json(body);
+
+        let (status, headers, text) = send_request(request, self.write_policy())
+            .await
+            .map_err(|reason| reqwest_error(&url, reason))?;
+
+        if !status.is_success() {
+            return Err(process_error_response(
+                &text,
+                status,
+                &headers,
+                &url,
+                self.settings.warn_unexpected_status,
+            ));
+        }
+
+        serde_json::from_str(&text).map_err(|reason| json_error(url, text, reason))
+    }
+
+    // Reject `value` unless it is the hex encoding of `bytes` bytes, only with `validate_inputs`
+    pub(crate) fn check_hex(
+        &self, field: &str, value: &str, bytes: usize,
+    ) -> Result<(), BlockfrostError> {
+        if !self.settings.validate_inputs {
+            return Ok(());
+        }
+        let reason = if value.len() != bytes * 2 {
+            format!("expected {} hex characters, got {}", bytes * 2, value.len())
+        } else if !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            "not hex encoded".to_string()
         } else {
-            send_get_request(&self.client, url, self.settings.retry_settings).await
+            return Ok(());
+        };
+
+        Err(BlockfrostError::InvalidInput {
+            field: field.to_string(),
+            reason,
+        })
+    }
+
+    pub(crate) fn request_policy(&self) -> RequestPolicy<'_> {
+        RequestPolicy {
+            retry_settings: self.settings.retry_settings,
+            backoff: self.settings.backoff.as_deref(),
+            rate_limiter: self.rate_limiter.as_deref(),
+            warn_unexpected_status: self.settings.warn_unexpected_status,
         }
     }
+
+    pub(crate) fn write_policy(&self) -> RequestPolicy<'_> {
+        self.request_policy().for_writes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{MockResponse, MockServer},
+        Order, RateLimit, RedirectPolicy,
+    };
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    fn gzip(text: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_compressed_response_is_decoded() {
+        let body = r#"["8788591983aa73981fc92d6cddbbe643959f5a784e84b8bee0db15823f575a5b"]"#;
+        let server = MockServer::start(vec![
+            MockResponse::new(200, gzip(body)).header("Content-Encoding", "gzip")
+        ]);
+        let api = server.api();
+
+        let txs = api.blocks_latest_txs(Pagination::default()).await.unwrap();
+
+        assert_eq!(txs, serde_json::from_str::<Vec<String>>(body).unwrap());
+        let request = server.requests()[0].to_lowercase();
+        assert!(request.contains("accept-encoding: gzip, br"));
+    }
+
+    #[tokio::test]
+    async fn test_compression_disabled() {
+        let server = MockServer::start(vec![MockResponse::new(200, "[]")]);
+        let mut settings = BlockFrostSettings::new();
+        settings.set_compression(false);
+        let api = server.api_with_settings(settings);
+
+        api.blocks_latest_txs(Pagination::default()).await.unwrap();
+
+        assert!(!server.requests()[0]
+            .to_lowercase()
+            .contains("accept-encoding"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let server = MockServer::start_with(|_, _| {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            MockResponse::new(200, "[]")
+        });
+        let mut settings = BlockFrostSettings::new();
+        settings.set_timeout(std::time::Duration::from_millis(50));
+        let api = server.api_with_settings(settings);
+
+        let error = api.blocks_latest_txs(Pagination::default()).await;
+
+        assert!(matches!(error, Err(BlockfrostError::Reqwest { .. })));
+    }
+
+    #[test]
+    fn test_presets() {
+        let production = BlockFrostSettings::production();
+        let indexer = BlockFrostSettings::indexer();
+
+        assert_eq!(production.rate_limit, Some(RateLimit::blockfrost()));
+        assert!(production.retry_settings.respect_rate_limiter);
+        assert!(production.backoff.is_some());
+        assert_eq!(production.prefetch, 1);
+        assert_eq!(indexer.prefetch, 10);
+        assert!(indexer.timeout > production.timeout);
+        assert_eq!(BlockFrostSettings::new().timeout, None);
+    }
+
+    #[tokio::test]
+    async fn test_base_url_setting() {
+        let server = MockServer::start(vec![MockResponse::new(200, "[]")]);
+        let mut settings = BlockFrostSettings::new();
+        settings.set_base_url(format!("{}/api/v0/", server.url));
+        let api = BlockfrostAPI::new("mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be", settings);
+
+        api.blocks_latest_txs(Pagination::default()).await.unwrap();
+
+        assert!(server.requests()[0].starts_with("GET /api/v0/blocks/latest/txs?"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_override() {
+        let server = MockServer::start(vec![MockResponse::new(200, "[]")]);
+        let address: std::net::SocketAddr =
+            server.url.trim_start_matches("http://").parse().unwrap();
+        let mut settings = BlockFrostSettings::new();
+        settings
+            .set_base_url(format!(
+                "http://blockfrost.invalid:{}/api/v0",
+                address.port()
+            ))
+            .resolve("blockfrost.invalid", address);
+        let api = BlockfrostAPI::new("mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be", settings);
+
+        api.blocks_latest_txs(Pagination::default()).await.unwrap();
+
+        let request = &server.requests()[0];
+        assert!(request.starts_with("GET /api/v0/blocks/latest/txs?"));
+        assert!(request
+            .to_lowercase()
+            .contains(&format!("host: blockfrost.invalid:{}", address.port())));
+    }
+
+    #[tokio::test]
+    async fn test_redirects_not_followed() {
+        let server = MockServer::start(vec![
+            MockResponse::new(302, "").header("Location", "/blocks/latest/txs"),
+            MockResponse::new(200, "[]"),
+        ]);
+        let api = server.api();
+
+        let error = api.blocks_latest_txs(Pagination::default()).await;
+
+        assert!(matches!(
+            error,
+            Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 302
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_redirects_followed() {
+        let server = MockServer::start(vec![
+            MockResponse::new(302, "").header("Location", "/blocks/latest/txs"),
+            MockResponse::new(200, "[]"),
+        ]);
+        let mut settings = BlockFrostSettings::new();
+        settings.set_redirect_policy(RedirectPolicy::Limited(1));
+        let api = server.api_with_settings(settings);
+
+        api.blocks_latest_txs(Pagination::default()).await.unwrap();
+
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_urls() {
+        let primary = MockServer::start(vec![MockResponse::new(503, "")]);
+        let fallback = MockServer::start(vec![MockResponse::new(200, "[]")]);
+        let mut settings = BlockFrostSettings::new();
+        settings.set_fallback_urls(vec![(fallback.url.clone(), "backupproject".to_string())]);
+        let api = primary.api_with_settings(settings);
+
+        api.blocks_latest_txs(Pagination::default()).await.unwrap();
+
+        assert_eq!(primary.requests().len(), 1);
+        assert!(fallback.requests()[0].contains("project_id: backupproject"));
+        assert_eq!(api.last_base_url(), fallback.url);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_urls_only_on_retryable_errors() {
+        let primary = MockServer::start(vec![MockResponse::new(404, "")]);
+        let fallback = MockServer::start(vec![MockResponse::new(200, "[]")]);
+        let mut settings = BlockFrostSettings::new();
+        settings.set_fallback_urls(vec![(fallback.url.clone(), "backupproject".to_string())]);
+        let api = primary.api_with_settings(settings);
+
+        assert!(api.blocks_latest_txs(Pagination::default()).await.is_err());
+        assert!(fallback.requests().is_empty());
+        assert_eq!(api.last_base_url(), primary.url);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_urls_fetch_all() {
+        let primary = MockServer::start(vec![MockResponse::new(503, "")]);
+        let fallback = MockServer::start(vec![MockResponse::new(200, r#"["a", "b"]"#)]);
+        let mut settings = BlockFrostSettings::new();
+        settings.set_fallback_urls(vec![(fallback.url.clone(), "backupproject".to_string())]);
+        let api = primary.api_with_settings(settings);
+
+        let txs = api.blocks_latest_txs(Pagination::all()).await.unwrap();
+
+        assert_eq!(txs, ["a", "b"]);
+        assert!(fallback.requests()[0].contains("project_id: backupproject"));
+        assert_eq!(api.last_base_url(), fallback.url);
+    }
+
+    #[test]
+    fn test_cardano_network() {
+        let api = BlockfrostAPI::new(
+            "preprodxvMK4xOpp5mHJgihi055KDLU64JJv2be",
+            Default::default(),
+        );
+
+        assert_eq!(api.cardano_network(), Some(Network::Preprod));
+        assert_eq!(MockServer::start(vec![]).api().cardano_network(), None);
+    }
+
+    #[tokio::test]
+    async fn test_flush() {
+        let server = MockServer::start(vec![]);
+        let api = server.api();
+
+        assert!(api.flush().await.is_ok());
+        assert!(api.flush().await.is_ok());
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn test_try_new_validates_project_id() {
+        let mut settings = BlockFrostSettings::new();
+        settings.set_base_url(crate::CARDANO_PREPROD_URL);
+        assert!(
+            BlockfrostAPI::try_new("preprodxvMK4xOpp5mHJgihi055KDLU64JJv2be", settings).is_ok()
+        );
+
+        let mut settings = BlockFrostSettings::new();
+        settings.set_base_url(crate::CARDANO_PREPROD_URL);
+        let mismatched =
+            BlockfrostAPI::try_new("mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be", settings);
+        assert!(matches!(
+            mismatched,
+            Err(BlockfrostError::Configuration { .. })
+        ));
+
+        let malformed = BlockfrostAPI::try_new("xvMK4xOpp5m", BlockFrostSettings::new());
+        assert!(matches!(
+            malformed,
+            Err(BlockfrostError::Configuration { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_skips_custom_base_url_and_opt_out() {
+        let mut settings = BlockFrostSettings::new();
+        settings.set_base_url("http://localhost:3000/api/v0");
+        assert!(settings.validate("self-hosted key").is_ok());
+
+        let mut settings = BlockFrostSettings::new();
+        settings.set_validate_project_id(false);
+        assert!(settings.validate("xvMK4xOpp5m").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_extra_headers() {
+        let server = MockServer::start(vec![MockResponse::new(200, "[]")]);
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-gateway-token", HeaderValue::from_static("secret"));
+        headers.insert("project_id", HeaderValue::from_static("clobbered"));
+        let mut settings = BlockFrostSettings::new();
+        settings.set_extra_headers(headers);
+
+        assert!(matches!(
+            settings.validate("mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be"),
+            Err(BlockfrostError::Configuration { .. })
+        ));
+
+        let api = server.api_with_settings(settings);
+        api.blocks_latest_txs(Pagination::default()).await.unwrap();
+
+        let request = server.requests()[0].to_lowercase();
+        assert!(request.contains("x-gateway-token: secret"));
+        assert!(request.contains("project_id: mainnetxvmk4xopp5mhjgihi055kdlu64jjv2be"));
+        assert!(!request.contains("clobbered"));
+    }
+
+    #[tokio::test]
+    async fn test_get_custom_path() {
+        let server = MockServer::start(vec![MockResponse::new(200, r#"[{"start":0}]"#)]);
+        let api = server.api();
+
+        let eras: Vec<serde_json::Value> = api.get("/network/eras", None).await.unwrap();
+        let paged: Vec<serde_json::Value> = api
+            .get("/network/eras", Some(Pagination::new(Order::Desc, 2, 10)))
+            .await
+            .unwrap();
+
+        assert_eq!(eras, paged);
+        assert!(server.requests()[0].starts_with("GET /network/eras "));
+        assert!(server.requests()[1].starts_with("GET /network/eras?page=2&count=10&order=desc "));
+    }
+
+    #[tokio::test]
+    async fn test_paged_endpoint_order() {
+        let server = MockServer::start(vec![MockResponse::new(200, "[]")]);
+        let api = server.api();
+
+        api.accounts_rewards(
+            "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7",
+            Pagination::new(Order::Desc, 1, 100),
+        )
+        .await
+        .unwrap();
+
+        assert!(server.requests()[0].starts_with(
+            "GET /accounts/stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7/rewards?page=1&count=100&order=desc "
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_paged_endpoint_fetch_all() {
+        // 12 full pages then a short one, past the first batch of requests
+        let server = MockServer::start_with(|_, request| {
+            let query = request.split_whitespace().nth(1).unwrap_or_default();
+            let page: usize = query
+                .split(['?', '&'])
+                .find_map(|pair| pair.strip_prefix("page="))
+                .and_then(|page| page.parse().ok())
+                .unwrap_or_default();
+            let items = match page {
+                1..=12 => 100,
+                13 => 5,
+                _ => 0,
+            };
+            MockResponse::new(
+                200,
+                serde_json::to_string(&vec![page.to_string(); items]).unwrap(),
+            )
+        });
+        let api = server.api();
+        let pagination = Pagination {
+            order: Order::Desc,
+            ..Pagination::all()
+        };
+
+        let txs = api.blocks_latest_txs(pagination).await.unwrap();
+
+        assert_eq!(txs.len(), 1205);
+        assert_eq!(txs[1204], "13");
+        assert!(server
+            .requests()
+            .iter()
+            .all(|request| request.contains("&order=desc ")));
+    }
+
+    #[tokio::test]
+    async fn test_call_paged_endpoint_shared() {
+        let server = MockServer::start(vec![MockResponse::new(200, r#"["a","b"]"#)]);
+        let api = server.api();
+
+        let page: Arc<Vec<String>> = api
+            .call_paged_endpoint_shared("/pools", Pagination::new(Order::Asc, 3, 2))
+            .await
+            .unwrap();
+        let consumers: Vec<_> = (0..4).map(|_| Arc::clone(&page)).collect();
+
+        assert_eq!(*consumers[3], ["a", "b"]);
+        assert_eq!(Arc::strong_count(&page), 5);
+        assert!(server.requests()[0].starts_with("GET /pools?page=3&count=2&order=asc "));
+    }
+
+    #[tokio::test]
+    async fn test_validate_inputs() {
+        let hash = "6e5f825c82c1c6d6b77f2a14092f3b78c8f1b66db6f4cf8caec1555b6f967b3b";
+        let server = MockServer::start(vec![MockResponse::new(200, "[]")]);
+        let mut settings = BlockFrostSettings::new();
+        settings.set_validate_inputs(true);
+        let api = server.api_with_settings(settings);
+
+        for (hash, reason) in [
+            ("", "expected 64 hex characters, got 0"),
+            (&hash[1..], "expected 64 hex characters, got 63"),
+            (&hash.replace('e', "g"), "not hex encoded"),
+        ] {
+            assert!(matches!(
+                api.transactions_stakes(hash).await,
+                Err(BlockfrostError::InvalidInput { field, reason: actual })
+                    if field == "hash" && actual == reason
+            ));
+        }
+        assert!(matches!(
+            api.assets_policy_by_id(hash, Pagination::default()).await,
+            Err(BlockfrostError::InvalidInput { field, .. }) if field == "policy_id"
+        ));
+        assert!(server.requests().is_empty());
+
+        api.transactions_stakes(hash).await.unwrap();
+        api.assets_policy_by_id(&hash[..56], Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_inputs_not_validated_by_default() {
+        let server = MockServer::start(vec![MockResponse::new(200, "[]")]);
+
+        server.api().transactions_stakes("t").await.unwrap();
+
+        assert!(server.requests()[0].starts_with("GET /txs/t/stakes "));
+    }
+
+    #[tokio::test]
+    async fn test_post_custom_path() {
+        let server = MockServer::start(vec![MockResponse::new(200, r#"{"ok":true}"#)]);
+        let api = server.api();
+
+        let response: serde_json::Value = api
+            .post("/utils/custom", &serde_json::json!({ "key": "value" }))
+            .await
+            .unwrap();
+
+        let request = &server.requests()[0];
+        assert_eq!(response["ok"], true);
+        assert!(request.starts_with("POST /utils/custom "));
+        assert!(request.contains("content-type: application/json"));
+        assert!(request.ends_with(r#"{"key":"value"}"#));
+    }
 }
@@ -0,0 +1,173 @@
+use std::{fmt, time::Duration};
+
+/// Custom retry timing, replaces [`RetrySettings`](crate::RetrySettings) when set with
+/// [`BlockFrostSettings::set_backoff`](crate::BlockFrostSettings::set_backoff).
+///
+/// Every request works on a fresh clone of the configured backoff, so state kept in `&mut self`
+/// is per request. Retries happen on the same errors and status codes as with `RetrySettings`.
+///
+/// ```
+/// use blockfrost::Backoff;
+/// use std::time::Duration;
+///
+/// // Retry twice, the second time after a longer pause
+/// #[derive(Clone, Debug)]
+/// struct TwoShots;
+///
+/// impl Backoff for TwoShots {
+///     fn next_delay(&mut self, attempt: u32, _: Option<Duration>) -> Option<Duration> {
+///         match attempt {
+///             1 => Some(Duration::from_millis(100)),
+///             2 => Some(Duration::from_secs(2)),
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+pub trait Backoff: BackoffClone + fmt::Debug + Send + Sync {
+    /// Delay before retry number `attempt` (starting at 1), or `None` to stop retrying and
+    /// return the last response.
+    ///
+    /// `retry_after` is the server's `Retry-After` header, when it sent one.
+    fn next_delay(&mut self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration>;
+}
+
+// Lets settings holding a `Box<dyn Backoff>` derive `Clone`
+#[doc(hidden)]
+pub trait BackoffClone {
+    fn clone_box(&self) -> Box<dyn Backoff>;
+}
+
+impl<T: Backoff + Clone + 'static> BackoffClone for T {
+    fn clone_box(&self) -> Box<dyn Backoff> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Backoff> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+/// Wait the same `delay` before each retry, up to `max_retries` times.
+///
+/// A longer `Retry-After` from the server takes precedence over `delay`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConstantBackoff {
+    pub delay: Duration,
+    pub max_retries: u32,
+}
+
+impl ConstantBackoff {
+    pub fn new(delay: Duration, max_retries: u32) -> Self {
+        Self { delay, max_retries }
+    }
+}
+
+impl Backoff for ConstantBackoff {
+    fn next_delay(&mut self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        (attempt <= self.max_retries).then(|| self.delay.max(retry_after.unwrap_or_default()))
+    }
+}
+
+/// Double the delay after each retry, starting from `initial` and capped at `max_delay`, up to
+/// `max_retries` times.
+///
+/// A longer `Retry-After` from the server takes precedence over the computed delay.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ExponentialBackoff {
+    pub initial: Duration,
+    pub max_delay: Duration,
+    pub max_retries: u32,
+}
+
+impl ExponentialBackoff {
+    /// Create a new `ExponentialBackoff`, the delay is capped at one minute.
+    pub fn new(initial: Duration, max_retries: u32) -> Self {
+        Self {
+            initial,
+            max_delay: Duration::from_secs(60),
+            max_retries,
+        }
+    }
+}
+
+impl Backoff for ExponentialBackoff {
+    fn next_delay(&mut self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt > self.max_retries {
+            return None;
+        }
+
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self
+            .initial
+            .checked_mul(factor)
+            .unwrap_or(Duration::MAX)
+            .min(self.max_delay);
+
+        Some(delay.max(retry_after.unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_backoff() {
+        let mut backoff = ConstantBackoff::new(Duration::from_millis(500), 2);
+
+        assert_eq!(
+            backoff.next_delay(1, None),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            backoff.next_delay(2, None),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(backoff.next_delay(3, None), None);
+        assert_eq!(
+            backoff.next_delay(1, Some(Duration::from_secs(3))),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            backoff.next_delay(1, Some(Duration::ZERO)),
+            Some(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn test_exponential_backoff() {
+        let mut backoff = ExponentialBackoff::new(Duration::from_millis(100), 10);
+        backoff.max_delay = Duration::from_secs(1);
+
+        let delays: Vec<_> = (1..=6)
+            .map(|attempt| backoff.next_delay(attempt, None))
+            .collect();
+
+        assert_eq!(
+            delays,
+            [100, 200, 400, 800, 1000, 1000].map(|ms| Some(Duration::from_millis(ms)))
+        );
+        assert_eq!(backoff.next_delay(11, None), None);
+        assert_eq!(
+            backoff.next_delay(1, Some(Duration::from_secs(5))),
+            Some(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn test_exponential_backoff_overflow() {
+        let mut backoff = ExponentialBackoff::new(Duration::from_secs(1), u32::MAX);
+
+        assert_eq!(backoff.next_delay(200, None), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_boxed_backoff_clone() {
+        let backoff: Box<dyn Backoff> = Box::new(ConstantBackoff::new(Duration::ZERO, 1));
+
+        assert_eq!(backoff.clone().next_delay(1, None), Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,156 @@
+//! Conversions between the bech32 identifiers used by the API and their raw hex form.
+//!
+//! Pools are `pool1...` in most endpoints, while ledger data and some tools use the hex pool
+//! hash, the 28 byte hash of the pool's cold verification key.
+use crate::{utils::decode_hex, BlockfrostError};
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const POOL_PREFIX: &str = "pool";
+const POOL_HASH_LENGTH: usize = 28;
+
+/// Hex pool hash of a bech32 `pool1...` pool ID.
+pub fn pool_id_to_hex(bech32: &str) -> Result<String, BlockfrostError> {
+    let (prefix, data) = decode(bech32).ok_or_else(|| parsing_error("invalid bech32", bech32))?;
+
+    if prefix != POOL_PREFIX {
+        return Err(parsing_error("not a pool id", bech32));
+    }
+    if data.len() != POOL_HASH_LENGTH {
+        return Err(parsing_error("pool hash is not 28 bytes", bech32));
+    }
+    Ok(data.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Bech32 `pool1...` pool ID of a hex pool hash.
+pub fn pool_id_from_hex(hex: &str) -> Result<String, BlockfrostError> {
+    let data = decode_hex(hex).ok_or_else(|| parsing_error("invalid hex", hex))?;
+
+    if data.len() != POOL_HASH_LENGTH {
+        return Err(parsing_error("pool hash is not 28 bytes", hex));
+    }
+    Ok(encode(POOL_PREFIX, &data))
+}
+
+fn parsing_error(reason: &str, value: &str) -> BlockfrostError {
+    BlockfrostError::Parsing {
+        message: format!("{}: '{}'", reason, value),
+    }
+}
+
+// BIP 173 checksum over the expanded prefix and the 5 bit data
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+    values.into_iter().fold(1, |checksum, value| {
+        let top = checksum >> 25;
+        let checksum = (checksum & 0x1ffffff) << 5 ^ value as u32;
+
+        (0..5)
+            .filter(|bit| top >> bit & 1 == 1)
+            .fold(checksum, |checksum, bit| checksum ^ GENERATOR[bit])
+    })
+}
+
+fn expand_prefix(prefix: &str) -> impl Iterator<Item = u8> + '_ {
+    let bytes = prefix.bytes();
+
+    bytes
+        .clone()
+        .map(|byte| byte >> 5)
+        .chain([0])
+        .chain(bytes.map(|byte| byte & 31))
+}
+
+// Regroup bits, `None` if the padding left by 8 to 5 bit conversion is not zero
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+    let (mut accumulator, mut bits) = (0u32, 0);
+    let mask = (1 << to) - 1;
+
+    for value in data {
+        accumulator = accumulator << from | *value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            result.push((accumulator >> bits & mask) as u8);
+        }
+    }
+    if pad && bits > 0 {
+        result.push((accumulator << (to - bits) & mask) as u8);
+    } else if !pad && (bits >= from || accumulator << (to - bits) & mask != 0) {
+        return None;
+    }
+    Some(result)
+}
+
+fn encode(prefix: &str, data: &[u8]) -> String {
+    let mut values = convert_bits(data, 8, 5, true).unwrap_or_default();
+    let checksum = polymod(
+        expand_prefix(prefix)
+            .chain(values.iter().copied())
+            .chain([0; 6]),
+    ) ^ 1;
+    values.extend((0..6).map(|index| (checksum >> (5 * (5 - index)) & 31) as u8));
+
+    let data: String = values
+        .iter()
+        .map(|value| CHARSET[*value as usize] as char)
+        .collect();
+    format!("{}1{}", prefix, data)
+}
+
+// Prefix and data bytes of a bech32 string, `None` if malformed or the checksum is wrong
+fn decode(bech32: &str) -> Option<(String, Vec<u8>)> {
+    let lowercase = bech32.to_ascii_lowercase();
+    if lowercase != bech32 && bech32.to_ascii_uppercase() != bech32 {
+        return None;
+    }
+    let (prefix, data) = lowercase.rsplit_once('1')?;
+    if prefix.is_empty() || data.len() < 6 || !prefix.bytes().all(|byte| (33..=126).contains(&byte))
+    {
+        return None;
+    }
+
+    let values = data
+        .bytes()
+        .map(|byte| {
+            CHARSET
+                .iter()
+                .position(|c| *c == byte)
+                .map(|value| value as u8)
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    if polymod(expand_prefix(prefix).chain(values.iter().copied())) != 1 {
+        return None;
+    }
+
+    let data = convert_bits(&values[..values.len() - 6], 5, 8, false)?;
+    Some((prefix.to_string(), data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POOL_ID: &str = "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy";
+    const POOL_HASH: &str = "0f292fcaa02b8b2f9b3c8f9fd8e0bb21abedb692a6d5058df3ef2735";
+
+    #[test]
+    fn test_pool_id_conversions() {
+        assert_eq!(pool_id_to_hex(POOL_ID).unwrap(), POOL_HASH);
+        assert_eq!(pool_id_from_hex(POOL_HASH).unwrap(), POOL_ID);
+        assert_eq!(pool_id_to_hex(&POOL_ID.to_uppercase()).unwrap(), POOL_HASH);
+    }
+
+    #[test]
+    fn test_pool_id_errors() {
+        // Checksum no longer matches
+        assert!(pool_id_to_hex(&POOL_ID.replace("pu5", "pu6")).is_err());
+        // Valid bech32, but a stake address
+        assert!(
+            pool_id_to_hex("stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7").is_err()
+        );
+        assert!(pool_id_from_hex(&POOL_HASH[2..]).is_err());
+        assert!(pool_id_from_hex("zz").is_err());
+    }
+}
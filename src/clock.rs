@@ -0,0 +1,52 @@
+use futures::{future::BoxFuture, FutureExt};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Source of time of the polling helpers,
+/// [`addresses_wait_for_payment`](crate::BlockfrostAPI::addresses_wait_for_payment) and
+/// [`blocks_follow`](crate::BlockfrostAPI::blocks_follow), set with
+/// [`BlockFrostSettings::set_clock`](crate::BlockFrostSettings::set_clock).
+///
+/// The default is [`TokioClock`]. A custom clock lets tests check timeouts and poll intervals
+/// without waiting: its `sleep` can move its `now` forward and return right away.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Current time, deadlines are computed from it.
+    fn now(&self) -> Instant;
+
+    /// Wait for `duration`, between two polls.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// The tokio clock, the default [`Clock`].
+///
+/// Follows `tokio::time::pause` in tests, the paused clock then jumps ahead to the end of
+/// each sleep.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        tokio::time::sleep(duration).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tokio_clock_follows_pause() {
+        let clock = TokioClock;
+        let start = clock.now();
+
+        clock.sleep(Duration::from_secs(3600)).await;
+
+        assert!(clock.now() - start >= Duration::from_secs(3600));
+    }
+}
@@ -1,13 +1,24 @@
 use crate::utils;
-use reqwest::StatusCode;
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    StatusCode,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::from_str;
+use std::time::Duration;
 use thiserror::Error;
 use {reqwest::Error as ReqwestError, serde_json::Error as SerdeJsonError};
 
 pub type BlockfrostResult<T, E = BlockfrostError> = std::result::Result<T, E>;
 
+/// Error of every fallible call of this crate.
+///
+/// New variants can be added in minor releases, so matches need a wildcard arm. Prefer the
+/// classification methods ([`is_not_found`](Self::is_not_found),
+/// [`is_rate_limited`](Self::is_rate_limited), [`status_code`](Self::status_code), ...), they
+/// keep working when the error a condition is reported with changes.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum BlockfrostError {
     #[error("Reqwest error for URL {url}: {reason}")]
     Reqwest { url: String, reason: reqwest::Error },
@@ -21,22 +32,170 @@ pub enum BlockfrostError {
     Parsing { message: String },
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("TOML error: {0}")]
+    Toml(#[from] toml::de::Error),
     #[error("Response error for URL {url}: {reason}")]
     Response { url: String, reason: ResponseError },
+    #[error("Timed out: {message}")]
+    Timeout { message: String },
+    #[error("Cancelled")]
+    Cancelled,
+    #[error("Configuration error: {message}")]
+    Configuration { message: String },
+    #[error("Invalid input {field}: {reason}")]
+    InvalidInput { field: String, reason: String },
 }
 
+impl BlockfrostError {
+    /// Status code of an error response from the server.
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            BlockfrostError::Response { reason, .. } => Some(reason.status_code),
+            _ => None,
+        }
+    }
+
+    /// Whether the requested resource does not exist, a `404` response.
+    pub fn is_not_found(&self) -> bool {
+        self.status_code() == Some(404)
+    }
+
+    /// Whether the request was rejected by the server's rate limit, a `429` response.
+    ///
+    /// Only returned once the retries are exhausted, see
+    /// [`rate_limit_info`](Self::rate_limit_info) for when to try again.
+    pub fn is_rate_limited(&self) -> bool {
+        self.status_code() == Some(429)
+    }
+
+    /// Whether a listing was cancelled, see [`BlockfrostError::Cancelled`].
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, BlockfrostError::Cancelled)
+    }
+
+    /// Whether a request or a wait timed out, on the client or connecting to the server.
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            BlockfrostError::Timeout { .. } => true,
+            BlockfrostError::Reqwest { reason, .. } => reason.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// Whether an argument was rejected before sending, see [`BlockfrostError::InvalidInput`].
+    pub fn is_invalid_input(&self) -> bool {
+        matches!(self, BlockfrostError::InvalidInput { .. })
+    }
+
+    /// Rate limit headers of a `429 Too Many Requests` response.
+    pub fn rate_limit_info(&self) -> Option<&RateLimitInfo> {
+        match self {
+            BlockfrostError::Response { reason, .. } => reason.rate_limit.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the project is over its usage limit or banned, a `418` response.
+    ///
+    /// Unlike a `429` (rate limit, retried and gone after a short wait), this is an account
+    /// issue that lasts until the quota resets or the plan changes, so it is never retried.
+    pub fn is_project_over_limit(&self) -> bool {
+        self.status_code() == Some(418)
+    }
+
+    /// Whether the project id was refused, a `403` response.
+    ///
+    /// The project id is missing, invalid, or for another network than the base URL's. Fix the
+    /// configuration, retrying won't help and never happens.
+    pub fn is_unauthorized(&self) -> bool {
+        self.status_code() == Some(403)
+    }
+}
+
+/// Error response of the server.
+///
+/// New fields can be added in minor releases, build one with [`new`](Self::new).
 #[derive(Serialize, Deserialize, Error, Debug, Clone)]
+#[non_exhaustive]
 pub struct ResponseError {
     pub status_code: u16,
     pub error: String,
     pub message: String,
+    /// Parsed from the headers of `429 Too Many Requests` responses.
+    #[serde(skip)]
+    pub rate_limit: Option<Box<RateLimitInfo>>,
+}
+
+/// Rate limit headers sent along a `429 Too Many Requests` response, any of them may be missing.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RateLimitInfo {
+    /// How long to wait before retrying (`Retry-After`).
+    pub retry_after: Option<Duration>,
+    /// Requests allowed in the current window (`X-RateLimit-Limit`).
+    pub limit: Option<u64>,
+    /// Requests left in the current window (`X-RateLimit-Remaining`).
+    pub remaining: Option<u64>,
+    /// Time until the window resets (`X-RateLimit-Reset`).
+    pub reset: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let number = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                headers
+                    .get(*name)?
+                    .to_str()
+                    .ok()?
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+        };
+
+        // Only the delay in seconds form is handled, the HTTP date form is not used by Blockfrost
+        Self {
+            retry_after: number(&[RETRY_AFTER.as_str()]).map(Duration::from_secs),
+            limit: number(&["x-ratelimit-limit", "ratelimit-limit"]),
+            remaining: number(&["x-ratelimit-remaining", "ratelimit-remaining"]),
+            reset: number(&["x-ratelimit-reset", "ratelimit-reset"]).map(Duration::from_secs),
+        }
+    }
+}
+
+impl ResponseError {
+    /// Error response without rate limit headers.
+    pub fn new(status_code: u16, error: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            status_code,
+            error: error.into(),
+            message: message.into(),
+            rate_limit: None,
+        }
+    }
+
+    /// `message` parsed as JSON when it holds an object or an array, such as the ledger errors
+    /// of a rejected [`transactions_submit`](crate::BlockfrostAPI::transactions_submit).
+    pub fn message_json(&self) -> Option<serde_json::Value> {
+        from_str::<serde_json::Value>(&self.message)
+            .ok()
+            .filter(|json| json.is_object() || json.is_array())
+    }
 }
 
 impl std::fmt::Display for ResponseError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         writeln!(f, "Status code: {}", self.status_code)?;
         writeln!(f, "Error: {}", self.error)?;
-        write!(f, "Message: {}", self.message)
+        write!(f, "Message: {}", self.message)?;
+        // The project id itself is never part of the error, only a pointer to it
+        if self.status_code == 403 {
+            write!(
+                f,
+                "\nHint: check that the project id is valid and for the network of the base URL"
+            )?;
+        }
+        Ok(())
     }
 }
 
@@ -46,20 +205,28 @@ impl std::fmt::Display for ResponseError {
 // Catching a Error::Json when trying to interpret a Error::ErrorResponse
 //
 // This function can only return Error::ErrorResponse.
+//
+// Status codes Blockfrost doesn't document are reported on stderr if `warn_unexpected_status`.
 pub(crate) fn process_error_response(
-    text: &str, status_code: StatusCode, url: &str,
+    text: &str, status_code: StatusCode, headers: &HeaderMap, url: &str,
+    warn_unexpected_status: bool,
 ) -> BlockfrostError {
+    let rate_limit = (status_code == StatusCode::TOO_MANY_REQUESTS)
+        .then(|| Box::new(RateLimitInfo::from_headers(headers)));
     let status_code = status_code.as_u16();
 
     let expected_error_codes = &[400, 403, 404, 418, 429, 500];
-    if !expected_error_codes.contains(&status_code) {
+    if warn_unexpected_status && !expected_error_codes.contains(&status_code) {
         eprintln!("Warning: status code {} was not expected.", status_code);
     }
     let url = url.into();
 
     match from_str::<ResponseError>(text) {
         Ok(http_error) => BlockfrostError::Response {
-            reason: http_error,
+            reason: ResponseError {
+                rate_limit,
+                ..http_error
+            },
             url,
         },
         Err(_) => {
@@ -72,6 +239,7 @@ pub(crate) fn process_error_response(
                 status_code,
                 error: reason,
                 message: formatted_body_text,
+                rate_limit,
             };
             BlockfrostError::Response {
                 reason: http_error,
@@ -89,6 +257,18 @@ impl From<Box<dyn std::error::Error>> for BlockfrostError {
     }
 }
 
+/// Lets `?` turn the errors of your own `reqwest` calls into a [`BlockfrostError::Reqwest`].
+///
+/// The `url` is the one reqwest kept in the error, or empty when it has none. Errors from the
+/// api itself always carry the url of the request.
+impl From<ReqwestError> for BlockfrostError {
+    fn from(error: ReqwestError) -> Self {
+        let url = error.url().map(ToString::to_string).unwrap_or_default();
+
+        reqwest_error(url, error)
+    }
+}
+
 // Helper to create a Error::Reqwest
 pub(crate) fn reqwest_error(url: impl ToString, error: ReqwestError) -> BlockfrostError {
     BlockfrostError::Reqwest {
@@ -107,3 +287,101 @@ pub(crate) fn json_error(
         reason: error,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_reqwest_error() {
+        fn build(url: &str) -> Result<reqwest::Request, BlockfrostError> {
+            Ok(reqwest::Client::new().get(url).build()?)
+        }
+
+        assert!(matches!(
+            build("not a url"),
+            Err(BlockfrostError::Reqwest { url, .. }) if url.is_empty()
+        ));
+        assert!(build("https://cardano-mainnet.blockfrost.io/api/v0").is_ok());
+    }
+
+    #[test]
+    fn test_unexpected_status_without_warning() {
+        let body = r#"{"status_code": 502, "error": "Bad Gateway", "message": "upstream"}"#;
+
+        let error = process_error_response(
+            body,
+            StatusCode::BAD_GATEWAY,
+            &HeaderMap::new(),
+            "https://cardano-mainnet.blockfrost.io/api/v0/blocks/latest",
+            false,
+        );
+
+        assert_eq!(error.status_code(), Some(502));
+    }
+
+    #[test]
+    fn test_message_json() {
+        let body = serde_json::json!({
+            "status_code": 400,
+            "error": "Bad Request",
+            "message": "{\"contents\":{\"contents\":{\"contents\":{\"era\":\"ShelleyBasedEraBabbage\",\"error\":[\"ShelleyTxValidationError ShelleyBasedEraBabbage (ApplyTxError [UtxowFailure (UtxoFailure (AlonzoInBabbageUtxoPredFailure (BadInputsUTxO (fromList [TxIn (TxId {unTxId = SafeHash \\\"8de7d1ae32bc5d9c3d36ba3e8d2ab5b3e4f0ac6b4a0d4f0e8d6c1bb3c2ad3f0a\\\"}) (TxIx 0)]))))])\"],\"kind\":\"ShelleyTxValidationError\"},\"tag\":\"TxValidationErrorInCardanoMode\"},\"tag\":\"TxCmdTxSubmitValidationError\"},\"tag\":\"TxSubmitFail\"}"
+        });
+
+        let error = process_error_response(
+            &body.to_string(),
+            StatusCode::BAD_REQUEST,
+            &HeaderMap::new(),
+            "https://cardano-mainnet.blockfrost.io/api/v0/tx/submit",
+            true,
+        );
+        let BlockfrostError::Response { reason, .. } = error else {
+            panic!("not a response error");
+        };
+        let details = reason.message_json().unwrap();
+
+        assert!(reason.message.starts_with("{\"contents\""));
+        assert_eq!(details["tag"], "TxSubmitFail");
+        assert_eq!(
+            details["contents"]["contents"]["contents"]["kind"],
+            "ShelleyTxValidationError"
+        );
+        assert!(details["contents"]["contents"]["contents"]["error"][0]
+            .as_str()
+            .unwrap()
+            .contains("BadInputsUTxO"));
+
+        let plain = ResponseError {
+            message: "Invalid project token.".to_string(),
+            ..reason
+        };
+        assert_eq!(plain.message_json(), None);
+    }
+
+    #[test]
+    fn test_classification() {
+        let response = |status_code| BlockfrostError::Response {
+            url: String::new(),
+            reason: ResponseError::new(status_code, "", ""),
+        };
+
+        assert!(response(404).is_not_found());
+        assert!(!response(404).is_rate_limited());
+        assert!(response(429).is_rate_limited());
+        assert!(response(418).is_project_over_limit());
+        assert!(response(403).is_unauthorized());
+        assert!(!response(418).is_unauthorized());
+        assert_eq!(response(500).status_code(), Some(500));
+        assert!(BlockfrostError::Cancelled.is_cancelled());
+        assert_eq!(BlockfrostError::Cancelled.status_code(), None);
+        assert!(BlockfrostError::Timeout {
+            message: String::new()
+        }
+        .is_timeout());
+        assert!(BlockfrostError::InvalidInput {
+            field: "hash".into(),
+            reason: String::new()
+        }
+        .is_invalid_input());
+    }
+}
@@ -0,0 +1,301 @@
+//! Offline fee estimation from protocol parameters.
+//!
+//! Parameters come from [`epochs_parameters`](crate::BlockfrostAPI::epochs_parameters) or
+//! [`epochs_latest_parameters`](crate::BlockfrostAPI::epochs_latest_parameters), all results
+//! are in Lovelace.
+use crate::{AmountsExt, BalanceError, EpochParamContentExt, ProtocolVersion};
+use blockfrost_openapi::models::{
+    epoch_param_content::EpochParamContent,
+    tx_content_output_amount_inner::TxContentOutputAmountInner,
+};
+use std::collections::{HashMap, HashSet};
+
+// Added to the serialized size of an output, for the UTxO entry overhead
+const UTXO_ENTRY_OVERHEAD: u64 = 160;
+// Serialized base address, a 57 bytes string
+const BASE_ADDRESS_SIZE: u64 = 59;
+// Serialized datum hash, a 32 bytes string
+const DATUM_HASH_SIZE: u64 = 34;
+// Serialized policy id, a 28 bytes string
+const POLICY_ID_SIZE: u64 = 30;
+// Alonzo sizes in 8 byte words: an output without its value, a Lovelace only value and a datum
+// hash
+const ALONZO_ENTRY_WORDS: u64 = 27;
+const ALONZO_ADA_ONLY_WORDS: u64 = 2;
+const ALONZO_DATUM_HASH_WORDS: u64 = 10;
+
+/// Minimum fee of a transaction of `tx_size_bytes` bytes, using the linear fee formula
+/// `min_fee_a * size + min_fee_b`.
+pub fn min_fee(tx_size_bytes: usize, params: &EpochParamContent) -> u64 {
+    let a = params.min_fee_a.max(0) as u64;
+    let b = params.min_fee_b.max(0) as u64;
+
+    a * tx_size_bytes as u64 + b
+}
+
+/// Fee for running scripts with a total budget of `mem` memory units and `steps` CPU steps,
+/// `price_mem * mem + price_step * steps` rounded up.
+///
+/// Zero if the parameters predate Alonzo and have no script prices.
+pub fn script_fee(mem: u64, steps: u64, params: &EpochParamContent) -> u64 {
+    // Prices are decimals, compute with exact fractions like the ledger does
+    let (mem_numerator, mem_denominator) = params.price_mem.map_or((0, 1), ratio);
+    let (step_numerator, step_denominator) = params.price_step.map_or((0, 1), ratio);
+
+    let numerator = mem as u128 * mem_numerator * step_denominator
+        + steps as u128 * step_numerator * mem_denominator;
+    let denominator = mem_denominator * step_denominator;
+
+    numerator.div_ceil(denominator) as u64
+}
+
+/// Minimum Lovelace an output holding `output` must contain, `(160 + size) * coins_per_utxo_size`
+/// where `size` is the size of the serialized output.
+///
+/// The output is assumed to go to a base address and, if `has_datum`, to carry a datum hash,
+/// in the compact array format. Its Lovelace quantity is raised to the minimum when counting its
+/// size, so the result is enough whatever the output holds now.
+///
+/// This is the Babbage rule. Alonzo parameters (protocol versions 5 and 6) use the Alonzo rule,
+/// `(27 + value_words + 10 if has_datum) * coins_per_utxo_word` with the value counted in 8
+/// byte words. Parameters from before Alonzo give their fixed `min_utxo`.
+///
+/// Fails like [`AmountsExt::lovelace`] on a quantity that is not an unsigned integer.
+pub fn min_ada_for_output(
+    output: &[TxContentOutputAmountInner], has_datum: bool, params: &EpochParamContent,
+) -> Result<u64, BalanceError> {
+    let version = params.protocol_version();
+    let coins_per_size = match params.coins_per_utxo_size_lovelace() {
+        Ok(Some(coins)) if version >= ProtocolVersion::new(5, 0) => coins.0,
+        _ => return Ok(params.min_utxo_lovelace().map_or(0, |min_utxo| min_utxo.0)),
+    };
+    if version < ProtocolVersion::new(7, 0) {
+        return alonzo_min_ada(output, has_datum, coins_per_size);
+    }
+    let coins_per_byte = coins_per_size;
+
+    let size_without_coin = 1
+        + BASE_ADDRESS_SIZE
+        + value_size_without_coin(output)?
+        + if has_datum { DATUM_HASH_SIZE } else { 0 };
+    let min_ada =
+        |coin| (UTXO_ENTRY_OVERHEAD + size_without_coin + head_size(coin)) * coins_per_byte;
+
+    // A larger coin can take more bytes, which can raise the minimum again
+    let mut coin = output.lovelace()?;
+    loop {
+        let minimum = min_ada(coin);
+        if minimum <= coin || head_size(minimum) == head_size(coin) {
+            return Ok(minimum);
+        }
+        coin = minimum;
+    }
+}
+
+// Alonzo minimum, the value is counted as 6 words plus 12 bytes per asset, the bytes of the
+// asset names and 28 bytes per policy, rounded up to words
+fn alonzo_min_ada(
+    output: &[TxContentOutputAmountInner], has_datum: bool, coins_per_word: u64,
+) -> Result<u64, BalanceError> {
+    let mut policies = HashSet::new();
+    let (mut assets, mut name_bytes) = (0, 0);
+    for asset in output.assets() {
+        let (unit, _) = asset?;
+        let (policy, name) = unit.split_at(unit.len().min(56));
+        policies.insert(policy);
+        assets += 1;
+        name_bytes += name.len() as u64 / 2;
+    }
+
+    let value_words = if assets == 0 {
+        ALONZO_ADA_ONLY_WORDS
+    } else {
+        6 + (assets * 12 + name_bytes + policies.len() as u64 * 28).div_ceil(8)
+    };
+    let datum_words = if has_datum {
+        ALONZO_DATUM_HASH_WORDS
+    } else {
+        0
+    };
+
+    Ok((ALONZO_ENTRY_WORDS + value_words + datum_words) * coins_per_word)
+}
+
+// Serialized size of a value without its Lovelace quantity: nothing for Lovelace only, else
+// the array around it and the map of policies to maps of asset names to quantities
+fn value_size_without_coin(output: &[TxContentOutputAmountInner]) -> Result<u64, BalanceError> {
+    let mut policies = HashMap::<&str, Vec<(&str, u128)>>::new();
+    for asset in output.assets() {
+        let (unit, quantity) = asset?;
+        let (policy, name) = unit.split_at(unit.len().min(56));
+        policies.entry(policy).or_default().push((name, quantity));
+    }
+    if policies.is_empty() {
+        return Ok(0);
+    }
+
+    let assets: u64 = policies
+        .values()
+        .map(|assets| {
+            let assets_size: u64 = assets
+                .iter()
+                .map(|(name, quantity)| {
+                    let name_length = name.len() as u64 / 2;
+                    head_size(name_length) + name_length + head_size(*quantity as u64)
+                })
+                .sum();
+            POLICY_ID_SIZE + head_size(assets.len() as u64) + assets_size
+        })
+        .sum();
+    Ok(1 + head_size(policies.len() as u64) + assets)
+}
+
+// Bytes taken by the CBOR head of an item of `argument`: an unsigned integer, or the length or
+// entry count of a string, array or map
+fn head_size(argument: u64) -> u64 {
+    match argument {
+        0..=23 => 1,
+        24..=0xff => 2,
+        0x100..=0xffff => 3,
+        0x1_0000..=0xffff_ffff => 5,
+        _ => 9,
+    }
+}
+
+// Exact fraction of the shortest decimal representation of `price`, so 0.0577 is 577 / 10000
+fn ratio(price: f32) -> (u128, u128) {
+    let price = price.max(0.0).to_string();
+    let (integer, fraction) = price.split_once('.').unwrap_or((&price, ""));
+    let denominator = 10u128.pow(fraction.len() as u32);
+    let numerator = format!("{}{}", integer, fraction).parse().unwrap_or(0);
+
+    (numerator, denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Mainnet parameters since the Alonzo hard fork
+    fn mainnet_params() -> EpochParamContent {
+        EpochParamContent {
+            min_fee_a: 44,
+            min_fee_b: 155381,
+            price_mem: Some(0.0577),
+            price_step: Some(0.0000721),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_min_fee() {
+        let params = mainnet_params();
+
+        assert_eq!(min_fee(0, &params), 155381);
+        assert_eq!(min_fee(300, &params), 168581);
+        assert_eq!(min_fee(16384, &params), 876277);
+    }
+
+    #[test]
+    fn test_script_fee() {
+        let params = mainnet_params();
+
+        assert_eq!(script_fee(1_000_000, 500_000_000, &params), 93750);
+        assert_eq!(script_fee(14_000_000, 10_000_000_000, &params), 1528800);
+        // Rounded up
+        assert_eq!(script_fee(1, 0, &params), 1);
+        assert_eq!(script_fee(0, 0, &params), 0);
+    }
+
+    fn amounts(amounts: &[(&str, &str)]) -> Vec<TxContentOutputAmountInner> {
+        amounts
+            .iter()
+            .map(|(unit, quantity)| TxContentOutputAmountInner {
+                unit: unit.to_string(),
+                quantity: quantity.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_min_ada_for_output() {
+        const POLICY: &str = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a7";
+        let params = EpochParamContent {
+            protocol_major_ver: 8,
+            coins_per_utxo_size: Some("4310".into()),
+            ..Default::default()
+        };
+
+        // Lovelace only, the well known 0.96975 ADA
+        let ada_only = amounts(&[("lovelace", "2000000")]);
+        assert_eq!(min_ada_for_output(&ada_only, false, &params), Ok(969750));
+        assert_eq!(min_ada_for_output(&ada_only, true, &params), Ok(1116290));
+        // The quantity is raised to the minimum before counting its size
+        let empty = amounts(&[("lovelace", "0")]);
+        assert_eq!(min_ada_for_output(&empty, false, &params), Ok(969750));
+
+        let nft = amounts(&[
+            ("lovelace", "1500000"),
+            (&format!("{}6e7574636f696e", POLICY), "1"),
+        ]);
+        assert_eq!(min_ada_for_output(&nft, false, &params), Ok(1150770));
+
+        let tokens = amounts(&[
+            ("lovelace", "1500000"),
+            (&format!("{}61", POLICY), "1000000"),
+            (&format!("{}62", POLICY), "1000000"),
+        ]);
+        assert_eq!(min_ada_for_output(&tokens, false, &params), Ok(1172320));
+    }
+
+    #[test]
+    fn test_min_ada_for_output_alonzo() {
+        const POLICY: &str = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a7";
+        // Epoch 300, `min_utxo` is reported as the cost per word too
+        let params = EpochParamContent {
+            epoch: 300,
+            protocol_major_ver: 6,
+            min_utxo: "34482".into(),
+            coins_per_utxo_size: Some("34482".into()),
+            coins_per_utxo_word: Some("34482".into()),
+            ..Default::default()
+        };
+
+        let ada_only = amounts(&[("lovelace", "2000000")]);
+        assert_eq!(min_ada_for_output(&ada_only, false, &params), Ok(999978));
+        assert_eq!(min_ada_for_output(&ada_only, true, &params), Ok(1344798));
+
+        let nft = amounts(&[
+            ("lovelace", "1500000"),
+            (&format!("{}6e7574636f696e", POLICY), "1"),
+        ]);
+        assert_eq!(min_ada_for_output(&nft, false, &params), Ok(1344798));
+        assert_eq!(min_ada_for_output(&nft, true, &params), Ok(1689618));
+
+        let tokens = amounts(&[
+            ("lovelace", "1500000"),
+            (&format!("{}61", POLICY), "1000000"),
+            (&format!("{}62", POLICY), "1000000"),
+        ]);
+        assert_eq!(min_ada_for_output(&tokens, false, &params), Ok(1379280));
+    }
+
+    #[test]
+    fn test_min_ada_for_output_before_babbage() {
+        let params = EpochParamContent {
+            protocol_major_ver: 4,
+            min_utxo: "1000000".into(),
+            ..Default::default()
+        };
+
+        let output = amounts(&[("lovelace", "2000000")]);
+        assert_eq!(min_ada_for_output(&output, false, &params), Ok(1000000));
+    }
+
+    #[test]
+    fn test_script_fee_without_prices() {
+        let params = EpochParamContent::default();
+
+        assert_eq!(script_fee(1_000_000, 500_000_000, &params), 0);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
     error::{json_error, process_error_response, reqwest_error},
-    request::{send_request, send_request_unprocessed},
+    request::{send_request, send_request_unprocessed, RequestPolicy},
+    url::Url,
     utils::{build_header_map, create_client_with_project_id},
-    BlockfrostError, Integer, IpfsSettings, RetrySettings, IPFS_URL,
+    BlockfrostError, Integer, IpfsSettings, Lister, Pagination, IPFS_URL,
 };
-use blockfrost_openapi::models::_ipfs_pin_list__ipfs_path__get_200_response::IpfsPinListIpfsPathGet200Response;
+use futures::{stream, FutureExt, StreamExt};
 use reqwest::{
     multipart::{Form, Part},
     ClientBuilder,
@@ -12,6 +13,9 @@ use reqwest::{
 use serde::{Deserialize, Serialize};
 use serde_json::from_str as json_from;
 
+// Pins sent at a time by `pin_many`
+const PIN_CONCURRENCY: usize = 8;
+
 /// Provides methods for making requests to the
 /// [IPFS API](https://docs.blockfrost.io/#tag/IPFS-Add).
 #[derive(Debug, Clone)]
@@ -32,7 +36,7 @@ impl BlockfrostIPFS {
     /// [`HeaderValue`]: reqwest::header::HeaderValue
     /// [`HeaderValue::from_str`]: reqwest::header::HeaderValue::from_str
     pub fn new(project_id: &str, settings: IpfsSettings) -> Self {
-        let client = create_client_with_project_id(project_id);
+        let client = create_client_with_project_id(project_id, true);
 
         Self {
             client,
@@ -88,12 +92,18 @@ impl BlockfrostIPFS {
 
         let request = self.client.post(&url).multipart(form);
 
-        let (status, text) = send_request(request, self.settings.retry_settings)
+        let (status, headers, text) = send_request(request, self.write_policy())
             .await
             .map_err(|reason| reqwest_error(&url, reason))?;
 
         if !status.is_success() {
-            return Err(process_error_response(&text, status, &url));
+            return Err(process_error_response(
+                &text,
+                status,
+                &headers,
+                &url,
+                self.settings.warn_unexpected_status,
+            ));
         }
 
         json_from(&text).map_err(|reason| json_error(url, text, reason))
@@ -112,17 +122,24 @@ impl BlockfrostIPFS {
 
         let request = self.client.get(&url);
 
-        let response = send_request_unprocessed(request, self.retry_settings())
+        let response = send_request_unprocessed(request, self.request_policy())
             .await
             .map_err(|reason| reqwest_error(&url, reason))?;
         let status = response.status();
 
         if !status.is_success() {
+            let headers = response.headers().clone();
             let text = response
                 .text()
                 .await
                 .map_err(|reason| reqwest_error(&url, reason))?;
-            Err(process_error_response(&text, status, &url))
+            Err(process_error_response(
+                &text,
+                status,
+                &headers,
+                &url,
+                self.settings.warn_unexpected_status,
+            ))
         } else {
             let bytes = response
                 .bytes()
@@ -142,33 +159,74 @@ impl BlockfrostIPFS {
             self.base_url.clone() + &format!("/ipfs/pin/add/{IPFS_path}", IPFS_path = ipfs_path);
 
         let request = self.client.post(&url);
-        let (status, text) = send_request(request, self.settings.retry_settings)
+        let (status, headers, text) = send_request(request, self.write_policy())
             .await
             .map_err(|reason| reqwest_error(&url, reason))?;
         if !status.is_success() {
-            return Err(process_error_response(&text, status, &url));
+            return Err(process_error_response(
+                &text,
+                status,
+                &headers,
+                &url,
+                self.settings.warn_unexpected_status,
+            ));
         }
 
         json_from(&text).map_err(|reason| json_error(url, text, reason))
     }
 
+    /// [`pin_add`](Self::pin_add) each of `ipfs_paths`, such as the files of an NFT collection,
+    /// a few at a time.
+    ///
+    /// Results are in the order of `ipfs_paths`, each paired with its path: one failed pin does
+    /// not stop the others. Every pin is retried by the [`IpfsSettings`] like a single one, only
+    /// with [`retry_writes`](RetrySettings::retry_writes), and pins rejected with a `425` because
+    /// the pin queue is full can be sent again later.
+    pub async fn pin_many(
+        &self, ipfs_paths: &[&str],
+    ) -> Vec<(String, Result<IpfsPinState, BlockfrostError>)> {
+        stream::iter(ipfs_paths)
+            .map(|path| async move {
+                let state = self.pin_add(path).await.map(|pin| pin.state);
+                (path.to_string(), state)
+            })
+            .buffered(PIN_CONCURRENCY)
+            .collect()
+            .await
+    }
+
     /// List objects pinned to local storage.
     ///
     /// OpenAPI endpoint reference: [`/ipfs/pin/list`].
     ///
     /// [`/ipfs/pin/list`]: https://docs.blockfrost.io/#tag/IPFS-Pins/paths/~1ipfs~1pin~1list~1/get
-    pub async fn pin_list(
-        &self,
-    ) -> Result<Vec<IpfsPinListIpfsPathGet200Response>, BlockfrostError> {
-        let url = self.base_url.clone() + "/ipfs/pin/list";
+    pub async fn pin_list(&self) -> Result<Vec<IpfsPinList>, BlockfrostError> {
+        self.pin_list_page(Pagination::default()).await
+    }
+
+    /// Stream every page of [`pin_list`](Self::pin_list), for accounts with many pins.
+    pub fn pin_list_all(&self) -> Lister<'_, IpfsPinList> {
+        Lister::from_fn(1, move |pagination| self.pin_list_page(pagination).boxed())
+    }
+
+    async fn pin_list_page(
+        &self, pagination: Pagination,
+    ) -> Result<Vec<IpfsPinList>, BlockfrostError> {
+        let url = Url::from_paginated_endpoint(&self.base_url, "/ipfs/pin/list", pagination)?;
 
         let request = self.client.get(&url);
-        let (status, text) = send_request(request, self.settings.retry_settings)
+        let (status, headers, text) = send_request(request, self.request_policy())
             .await
             .map_err(|reason| reqwest_error(&url, reason))?;
 
         if !status.is_success() {
-            return Err(process_error_response(&text, status, &url));
+            return Err(process_error_response(
+                &text,
+                status,
+                &headers,
+                &url,
+                self.settings.warn_unexpected_status,
+            ));
         }
 
         json_from(&text).map_err(|reason| json_error(url, text, reason))
@@ -184,12 +242,18 @@ impl BlockfrostIPFS {
             self.base_url.clone() + &format!("/ipfs/pin/list/{IPFS_path}", IPFS_path = ipfs_path);
 
         let request = self.client.get(&url);
-        let (status, text) = send_request(request, self.settings.retry_settings)
+        let (status, headers, text) = send_request(request, self.request_policy())
             .await
             .map_err(|reason| reqwest_error(&url, reason))?;
 
         if !status.is_success() {
-            return Err(process_error_response(&text, status, &url));
+            return Err(process_error_response(
+                &text,
+                status,
+                &headers,
+                &url,
+                self.settings.warn_unexpected_status,
+            ));
         }
 
         json_from(&text).map_err(|reason| json_error(url, text, reason))
@@ -205,35 +269,53 @@ impl BlockfrostIPFS {
             self.base_url.clone() + &format!("/ipfs/pin/remove/{IPFS_path}", IPFS_path = ipfs_path);
 
         let request = self.client.post(&url);
-        let (status, text) = send_request(request, self.settings.retry_settings)
+        let (status, headers, text) = send_request(request, self.write_policy())
             .await
             .map_err(|reason| reqwest_error(&url, reason))?;
 
         if !status.is_success() {
-            return Err(process_error_response(&text, status, &url));
+            return Err(process_error_response(
+                &text,
+                status,
+                &headers,
+                &url,
+                self.settings.warn_unexpected_status,
+            ));
         }
 
         json_from(&text).map_err(|reason| json_error(url, text, reason))
     }
 
-    pub(crate) fn retry_settings(&self) -> RetrySettings {
-        self.settings.retry_settings
+    pub(crate) fn request_policy(&self) -> RequestPolicy<'_> {
+        RequestPolicy {
+            retry_settings: self.settings.retry_settings,
+            backoff: self.settings.backoff.as_deref(),
+            rate_limiter: None,
+            warn_unexpected_status: self.settings.warn_unexpected_status,
+        }
+    }
+
+    pub(crate) fn write_policy(&self) -> RequestPolicy<'_> {
+        self.request_policy().for_writes()
     }
 }
 
 /// Created by [`add`](IpfsApi::add) method.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct IpfsAdd {
     /// Name of the file.
     pub name: String,
     /// IPFS hash of the file.
     pub ipfs_hash: String,
     /// IPFS node size in Bytes.
-    pub size: String,
+    #[serde(with = "crate::types::string_u64")]
+    pub size: u64,
 }
 
 /// Created by [`pin_add`](IpfsApi::pin_add) method.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct IpfsPinUpdate {
     /// IPFS hash of the pinned object.
     pub ipfs_hash: String,
@@ -242,7 +324,8 @@ pub struct IpfsPinUpdate {
 }
 
 /// Created by [`pin_list`](IpfsApi::pin_list) method.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct IpfsPinList {
     /// Creation time of the IPFS object on our backends.
     pub time_created: Integer,
@@ -251,7 +334,8 @@ pub struct IpfsPinList {
     /// IPFS hash of the pinned object.
     pub ipfs_hash: String,
     /// Size of the object in Bytes.
-    pub size: String,
+    #[serde(with = "crate::types::string_u64")]
+    pub size: u64,
     /// State of the pinned object, which is `queued` when we are retriving object. If this is
     /// successful the state is changed to `pinned` or `failed` if not. The state `gc` means the
     /// pinned item has been garbage collected due to account being over storage quota or after it
@@ -260,7 +344,7 @@ pub struct IpfsPinList {
 }
 
 /// Inner enum for [`IpfsPinUpdate`].
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum IpfsPinState {
     Queued,
@@ -270,32 +354,161 @@ pub enum IpfsPinState {
     Gc,
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-
-//     test_example! { test_ipfs_add, IpfsAdd, r#"
-//     {
-//       "name": "README.md",
-//       "ipfs_hash": "QmZbHqiCxKEVX7QfijzJTkZiSi3WEVTcvANgNAWzDYgZDr",
-//       "size": "125297"
-//     }
-//     "# }
-
-//     test_example! { test_ipfs_pin_add, IpfsPinUpdate, r#"
-//     {
-//       "ipfs_hash": "QmPojRfAXYAXV92Dof7gtSgaVuxEk64xx9CKvprqu9VwA8",
-//       "state": "queued"
-//     }
-//     "# }
-
-//     test_example! { test_ipfs_pin_list_by_id, IpfsPinList, r#"
-//     {
-//       "time_created": 1615551024,
-//       "time_pinned": 1615551024,
-//       "ipfs_hash": "QmdVMnULrY95mth2XkwjxDtMHvzuzmvUPTotKE1tgqKbCx",
-//       "size": "1615551024",
-//       "state": "pinned"
-//     }
-//     "# }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{MockResponse, MockServer},
+        RetrySettings,
+    };
+    use futures::StreamExt;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn pin(state: &str) -> serde_json::Value {
+        json!({
+            "time_created": 1615551024,
+            "time_pinned": 1615551024,
+            "ipfs_hash": "QmdVMnULrY95mth2XkwjxDtMHvzuzmvUPTotKE1tgqKbCx",
+            "size": "1615551024",
+            "state": state
+        })
+    }
+
+    #[test]
+    fn test_ipfs_pin_states() {
+        let states = [
+            ("queued", IpfsPinState::Queued),
+            ("pinned", IpfsPinState::Pinned),
+            ("unpinned", IpfsPinState::Unpinned),
+            ("failed", IpfsPinState::Failed),
+            ("gc", IpfsPinState::Gc),
+        ];
+
+        for (name, state) in states {
+            let pin = serde_json::from_value::<IpfsPinList>(pin(name)).unwrap();
+
+            assert_eq!(pin.state, state);
+            assert_eq!(serde_json::to_value(state).unwrap(), json!(name));
+        }
+        assert!(serde_json::from_value::<IpfsPinList>(pin("deleted")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pin_list_all() {
+        let server = MockServer::start(vec![
+            MockResponse::new(200, json!(vec![pin("pinned"); 100]).to_string()),
+            MockResponse::new(200, json!([pin("queued")]).to_string()),
+        ]);
+        let mut ipfs =
+            BlockfrostIPFS::new("ipfsxvMK4xOpp5mHJgihi055KDLU64JJv2be", IpfsSettings::new());
+        ipfs.base_url = server.url.clone();
+
+        let pages: Vec<_> = ipfs.pin_list_all().collect().await;
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[1].as_ref().unwrap()[0].state, IpfsPinState::Queued);
+        assert!(server.requests()[1].starts_with("GET /ipfs/pin/list?page=2&count=100"));
+    }
+
+    #[tokio::test]
+    async fn test_pin_many() {
+        let server = MockServer::start_with(|_, request| {
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            match path.strip_prefix("/ipfs/pin/add/") {
+                Some("full") => MockResponse::new(
+                    425,
+                    json!({ "status_code": 425, "error": "Too Early", "message": "" }).to_string(),
+                ),
+                Some(cid) => MockResponse::new(
+                    200,
+                    json!({ "ipfs_hash": cid, "state": "queued" }).to_string(),
+                ),
+                None => MockResponse::new(404, ""),
+            }
+        });
+        let mut ipfs =
+            BlockfrostIPFS::new("ipfsxvMK4xOpp5mHJgihi055KDLU64JJv2be", IpfsSettings::new());
+        ipfs.base_url = server.url.clone();
+        let cids: Vec<String> = (0..20).map(|index| format!("Qm{}", index)).collect();
+        let mut paths: Vec<&str> = cids.iter().map(String::as_str).collect();
+        paths.insert(3, "full");
+
+        let results = ipfs.pin_many(&paths).await;
+
+        let returned: Vec<&str> = results.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(returned, paths);
+        assert!(matches!(
+            &results[3].1,
+            Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 425
+        ));
+        assert_eq!(
+            results.iter().filter(|(_, state)| state.is_ok()).count(),
+            20
+        );
+        assert_eq!(server.requests().len(), 21);
+    }
+
+    #[tokio::test]
+    async fn test_post_not_retried_by_default() {
+        // Two failures, enough for a retried POST to succeed
+        let server = MockServer::start_with(|index, _| match index {
+            0 | 1 => MockResponse::new(503, ""),
+            _ => MockResponse::new(
+                200,
+                json!({ "ipfs_hash": "Qm", "state": "queued" }).to_string(),
+            ),
+        });
+        let mut settings = IpfsSettings::new();
+        settings.retry_settings = RetrySettings {
+            amount: 2,
+            delay: Duration::ZERO,
+            ..RetrySettings::default()
+        };
+        let mut ipfs = BlockfrostIPFS::new("ipfsxvMK4xOpp5mHJgihi055KDLU64JJv2be", settings);
+        ipfs.base_url = server.url.clone();
+
+        assert!(ipfs.pin_add("Qm").await.is_err());
+        assert_eq!(server.requests().len(), 1);
+
+        ipfs.settings.retry_settings.retry_writes = true;
+        assert!(ipfs.pin_remove("Qm").await.is_ok());
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_add() {
+        let response = json!({
+            "name": "README.md",
+            "ipfs_hash": "QmZbHqiCxKEVX7QfijzJTkZiSi3WEVTcvANgNAWzDYgZDr",
+            "size": "125297"
+        });
+        let server = MockServer::start(vec![MockResponse::new(200, response.to_string())]);
+        let mut ipfs =
+            BlockfrostIPFS::new("ipfsxvMK4xOpp5mHJgihi055KDLU64JJv2be", IpfsSettings::new());
+        ipfs.base_url = server.url.clone();
+
+        let added = ipfs.add(b"# README".to_vec()).await.unwrap();
+
+        assert_eq!(added.name, "README.md");
+        assert_eq!(
+            added.ipfs_hash,
+            "QmZbHqiCxKEVX7QfijzJTkZiSi3WEVTcvANgNAWzDYgZDr"
+        );
+        assert_eq!(added.size, 125297);
+        assert!(server.requests()[0].starts_with("POST /ipfs/add "));
+    }
+
+    #[test]
+    fn test_ipfs_size_as_number() {
+        let mut json_value = pin("pinned");
+        json_value["size"] = json!(1615551024);
+
+        let pinned = serde_json::from_value::<IpfsPinList>(json_value).unwrap();
+
+        assert_eq!(pinned.size, 1615551024);
+        assert!(serde_json::to_string(&pinned)
+            .unwrap()
+            .contains(r#""size":"1615551024""#));
+    }
+}
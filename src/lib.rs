@@ -3,21 +3,43 @@
 )]
 #![doc = include_str!("../README.md")]
 mod api;
+mod backoff;
+mod clock;
 mod ipfs;
+mod lister;
+#[cfg(test)]
+mod mock;
+mod network;
 mod pagination;
+mod rate_limiter;
 mod request;
 mod settings;
+#[cfg(any(test, feature = "testing"))]
+mod stub;
 mod url;
 mod utils;
 
+pub mod bech32;
 pub mod error;
+pub mod fees;
+pub mod load;
+pub mod prelude;
+pub mod traits;
 pub mod types;
 pub use api::*;
+pub use backoff::{Backoff, ConstantBackoff, ExponentialBackoff};
+pub use clock::{Clock, TokioClock};
 pub use error::*;
 pub use ipfs::BlockfrostIPFS;
+pub use lister::{Items, Lister};
+pub use network::Network;
 pub use pagination::Order;
+pub use pagination::Page;
 pub use pagination::Pagination;
 pub use settings::*;
+#[cfg(any(test, feature = "testing"))]
+pub use stub::StubApi;
+pub use traits::{AccountsApi, BlocksApi, TransactionsApi};
 pub use types::*;
 
 pub const CARDANO_MAINNET_URL: &str = "https://cardano-mainnet.blockfrost.io/api/v0";
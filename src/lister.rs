@@ -0,0 +1,524 @@
+use crate::{BlockfrostAPI, BlockfrostError, BlockfrostResult, Order, Pagination};
+use futures::{
+    future::BoxFuture,
+    stream::{self, BoxStream},
+    FutureExt, Stream, StreamExt,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+type FetchPage<'api, T> =
+    Arc<dyn Fn(Pagination) -> BoxFuture<'api, BlockfrostResult<Vec<T>>> + Send + Sync + 'api>;
+
+/// Stream over every page of a paginated endpoint, created by the `*_all` methods.
+///
+/// Each item is one page (up to 100 elements) in ascending order, unless changed with
+/// [`order`](Lister::order). The stream ends after the first page that is not full, or right
+/// after yielding an error.
+///
+/// ```no_run
+/// # use blockfrost::{BlockfrostAPI, BlockfrostResult};
+/// use futures::StreamExt;
+///
+/// # async fn example(api: BlockfrostAPI) -> BlockfrostResult<()> {
+/// let mut pages = api.accounts_addresses_assets_all("stake1u9ylzsgxaa6xctf4juup682ar3juj85n8tx3hthnljg47zctvm3rc");
+///
+/// while let Some(page) = pages.next().await {
+///     for asset in page? {
+///         println!("{}: {}", asset.unit, asset.quantity);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Lister<'api, T> {
+    fetch_page: FetchPage<'api, T>,
+    prefetch: usize,
+    order: Order,
+    finished: bool,
+    // Resolves once the token passed to `with_cancellation` is cancelled
+    cancelled: Option<BoxFuture<'static, ()>>,
+    // Built on the first poll, so the builder methods can still change how pages are fetched
+    inner: Option<BoxStream<'api, BlockfrostResult<Vec<T>>>>,
+}
+
+impl<'api, T> Lister<'api, T>
+where
+    T: DeserializeOwned + Send + 'api,
+{
+    pub(crate) fn new(api: &'api BlockfrostAPI, endpoint: String) -> Self {
+        Self::from_fn(api.settings.prefetch, move |pagination| {
+            let endpoint = endpoint.clone();
+            async move { api.call_paged_endpoint(&endpoint, pagination).await }.boxed()
+        })
+    }
+
+    // Lister over any paginated source, `fetch_page` is called with the pagination of each page
+    pub(crate) fn from_fn(
+        prefetch: usize,
+        fetch_page: impl Fn(Pagination) -> BoxFuture<'api, BlockfrostResult<Vec<T>>>
+            + Send
+            + Sync
+            + 'api,
+    ) -> Self {
+        Self {
+            fetch_page: Arc::new(fetch_page),
+            prefetch: prefetch.max(1),
+            order: Order::Asc,
+            finished: false,
+            cancelled: None,
+            inner: None,
+        }
+    }
+
+    /// Fetch up to `pages` pages concurrently ahead of the consumer, pages are still yielded in
+    /// order. Defaults to [`BlockFrostSettings::prefetch`](crate::BlockFrostSettings::prefetch).
+    ///
+    /// Speeds up long listings at the cost of up to `pages - 1` extra requests past the last
+    /// page. Has no effect once the stream has been polled.
+    pub fn prefetch(mut self, pages: usize) -> Self {
+        self.prefetch = pages.max(1);
+        self
+    }
+
+    /// List in `order`, sent as the `order` query parameter of every page.
+    ///
+    /// Endpoints without an order, like [`blocks_next`](BlockfrostAPI::blocks_next), ignore it.
+    /// Has no effect once the stream has been polled.
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Stop once `token` is cancelled: the stream then yields [`BlockfrostError::Cancelled`]
+    /// right away, dropping the requests in flight, and ends.
+    ///
+    /// Requires the `cancellation` feature.
+    #[cfg(feature = "cancellation")]
+    pub fn with_cancellation(mut self, token: tokio_util::sync::CancellationToken) -> Self {
+        self.cancelled = Some(token.cancelled_owned().boxed());
+        self
+    }
+
+    fn build(&self) -> BoxStream<'api, BlockfrostResult<Vec<T>>> {
+        let fetch_page = self.fetch_page.clone();
+        let pagination = Pagination {
+            order: self.order,
+            ..Default::default()
+        };
+
+        stream::iter(pagination.page..)
+            .map(move |page| fetch_page(Pagination { page, ..pagination }))
+            .buffered(self.prefetch)
+            .boxed()
+    }
+
+    /// Fetch every page and concatenate them, failing on the first error.
+    pub async fn collect_all(self) -> BlockfrostResult<Vec<T>> {
+        match self.collect_all_lenient().await {
+            (_, Some(error)) => Err(error),
+            (result, None) => Ok(result),
+        }
+    }
+
+    /// Like [`collect_all`](Self::collect_all), calling `progress(fetched, total)` after each
+    /// page with the number of elements fetched so far.
+    ///
+    /// Blockfrost does not send a total count with paginated responses, so `total` is `None`
+    /// and progress can only be reported as it goes.
+    pub async fn collect_all_with_progress(
+        mut self, mut progress: impl FnMut(usize, Option<usize>),
+    ) -> BlockfrostResult<Vec<T>> {
+        let mut result = Vec::new();
+
+        while let Some(page) = self.next().await {
+            result.extend(page?);
+            progress(result.len(), None);
+        }
+
+        Ok(result)
+    }
+
+    /// Number of elements over every page, without keeping them.
+    ///
+    /// Still one request per page of 100: when a summary endpoint has the count, such as
+    /// [`pools_delegators_count`](crate::BlockfrostAPI::pools_delegators_count), prefer it.
+    pub async fn count_all(mut self) -> BlockfrostResult<usize> {
+        let mut count = 0;
+
+        while let Some(page) = self.next().await {
+            count += page?.len();
+        }
+
+        Ok(count)
+    }
+
+    /// Fetch every page and concatenate them, keeping what was fetched before an error.
+    ///
+    /// When the error is `Some` the results are incomplete: they hold every page before the
+    /// one that failed, so collection can be resumed from page `result.len() / 100 + 1`.
+    pub async fn collect_all_lenient(mut self) -> (Vec<T>, Option<BlockfrostError>) {
+        let mut result = Vec::new();
+
+        while let Some(page) = self.next().await {
+            match page {
+                Ok(data) => result.extend(data),
+                Err(error) => return (result, Some(error)),
+            }
+        }
+
+        (result, None)
+    }
+
+    /// Write every element to `writer` as newline-delimited JSON (one JSON document per line,
+    /// readable by `jq` or `pandas.read_json(lines=True)`), returning the number written.
+    ///
+    /// Elements are written as pages arrive and `writer` is flushed after each page, so memory
+    /// use stays constant: the pages being fetched ahead (see [`prefetch`](Self::prefetch))
+    /// plus one serialized page. No page is fetched past the prefetch while `writer` is busy.
+    /// On an error the lines of the pages before the failing one have been written.
+    ///
+    /// ```no_run
+    /// # use blockfrost::{BlockfrostAPI, BlockfrostResult};
+    /// # async fn example(
+    /// #     api: BlockfrostAPI, file: impl tokio::io::AsyncWrite + Unpin,
+    /// # ) -> BlockfrostResult<()> {
+    /// // `file` is e.g. a `tokio::fs::File`
+    /// let assets = api.accounts_addresses_assets_all("stake1u9ylzsgxaa6xctf4juup682ar3juj85n8tx3hthnljg47zctvm3rc");
+    ///
+    /// assets.export_ndjson(file).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn export_ndjson(
+        mut self, mut writer: impl AsyncWrite + Unpin,
+    ) -> BlockfrostResult<usize>
+    where
+        T: Serialize,
+    {
+        let mut lines = Vec::new();
+        let mut written = 0;
+
+        while let Some(page) = self.next().await {
+            lines.clear();
+            for element in page? {
+                serde_json::to_writer(&mut lines, &element).map_err(|error| {
+                    BlockfrostError::Parsing {
+                        message: error.to_string(),
+                    }
+                })?;
+                lines.push(b'\n');
+                written += 1;
+            }
+            writer.write_all(&lines).await?;
+            writer.flush().await?;
+        }
+
+        Ok(written)
+    }
+
+    /// Stream of the elements of every page instead of the pages, ending after an error.
+    ///
+    /// ```no_run
+    /// # use blockfrost::{BlockfrostAPI, BlockfrostResult};
+    /// use futures::StreamExt;
+    ///
+    /// # async fn example(api: BlockfrostAPI) -> BlockfrostResult<()> {
+    /// let mut assets = api
+    ///     .accounts_addresses_assets_all("stake1u9ylzsgxaa6xctf4juup682ar3juj85n8tx3hthnljg47zctvm3rc")
+    ///     .items();
+    ///
+    /// while let Some(asset) = assets.next().await {
+    ///     println!("{}", asset?.unit);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn items(self) -> Items<'api, T> {
+        Items {
+            inner: self
+                .flat_map(|page| match page {
+                    Ok(data) => stream::iter(data).map(Ok).left_stream(),
+                    Err(error) => stream::once(async { Err(error) }).right_stream(),
+                })
+                .boxed(),
+        }
+    }
+
+    /// [`items`](Self::items) passed through `f`.
+    pub fn map_items<U>(self, f: impl FnMut(T) -> U + Send + 'api) -> Items<'api, U>
+    where
+        U: Send + 'api,
+    {
+        self.items().map_items(f)
+    }
+
+    /// [`items`](Self::items) for which `predicate` holds.
+    pub fn filter_items(self, predicate: impl FnMut(&T) -> bool + Send + 'api) -> Items<'api, T> {
+        self.items().filter_items(predicate)
+    }
+}
+
+/// Stream over the elements of a [`Lister`], created by [`Lister::items`].
+///
+/// Errors are passed through, the stream ends right after one. The adapters chain:
+///
+/// ```no_run
+/// # use blockfrost::{BlockfrostAPI, BlockfrostResult};
+/// use futures::TryStreamExt;
+///
+/// # async fn example(api: BlockfrostAPI) -> BlockfrostResult<()> {
+/// let units: Vec<String> = api
+///     .accounts_addresses_assets_all("stake1u9ylzsgxaa6xctf4juup682ar3juj85n8tx3hthnljg47zctvm3rc")
+///     .items()
+///     .filter_items(|asset| asset.quantity != "0")
+///     .map_items(|asset| asset.unit)
+///     .try_collect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Items<'api, T> {
+    inner: BoxStream<'api, BlockfrostResult<T>>,
+}
+
+impl<'api, T> Items<'api, T>
+where
+    T: Send + 'api,
+{
+    /// Pass every element through `f`.
+    pub fn map_items<U>(self, mut f: impl FnMut(T) -> U + Send + 'api) -> Items<'api, U>
+    where
+        U: Send + 'api,
+    {
+        Items {
+            inner: self.inner.map(move |item| item.map(&mut f)).boxed(),
+        }
+    }
+
+    /// Keep the elements for which `predicate` holds.
+    pub fn filter_items(
+        self, mut predicate: impl FnMut(&T) -> bool + Send + 'api,
+    ) -> Items<'api, T> {
+        Items {
+            inner: self
+                .inner
+                .filter(move |item| {
+                    let keep = item.as_ref().map_or(true, &mut predicate);
+                    async move { keep }
+                })
+                .boxed(),
+        }
+    }
+}
+
+impl<T> Stream for Items<'_, T> {
+    type Item = BlockfrostResult<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+impl<'api, T> Stream for Lister<'api, T>
+where
+    T: DeserializeOwned + Send + 'api,
+{
+    type Item = BlockfrostResult<Vec<T>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        if let Some(cancelled) = &mut self.cancelled {
+            if cancelled.poll_unpin(cx).is_ready() {
+                self.finished = true;
+                self.inner = None;
+                return Poll::Ready(Some(Err(BlockfrostError::Cancelled)));
+            }
+        }
+        if self.inner.is_none() {
+            self.inner = Some(self.build());
+        }
+
+        let poll = self.inner.as_mut().unwrap().poll_next_unpin(cx);
+
+        // Stop after the first page that isn't full, or after an error
+        if let Poll::Ready(Some(result)) = &poll {
+            let count = Pagination::default().count;
+            self.finished = !matches!(result, Ok(data) if data.len() == count);
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_collect_all_lenient_keeps_fetched_pages() {
+        let full_page =
+            json!(vec!["4ea1ba291e8eef538635a53e59fddba7810d1679631cc3aed7c8e6c4091a516a"; 100]);
+        let error = json!({
+            "status_code": 500,
+            "error": "Internal Server Error",
+            "message": "An unexpected response was received from the backend."
+        });
+        let server = MockServer::start(vec![
+            MockResponse::new(200, full_page.to_string()),
+            MockResponse::new(500, error.to_string()),
+        ]);
+        let api = server.api();
+
+        let (result, error) = Lister::<String>::new(&api, "/pools".to_string())
+            .collect_all_lenient()
+            .await;
+
+        assert_eq!(result.len(), 100);
+        assert!(matches!(
+            error,
+            Some(BlockfrostError::Response { reason, .. }) if reason.status_code == 500
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_order() {
+        let server = MockServer::start(vec![MockResponse::new(200, json!(["b", "a"]).to_string())]);
+        let api = server.api();
+
+        Lister::<String>::new(&api, "/pools".to_string())
+            .order(Order::Desc)
+            .collect_all()
+            .await
+            .unwrap();
+
+        assert!(server.requests()[0].starts_with("GET /pools?page=1&count=100&order=desc "));
+    }
+
+    #[tokio::test]
+    async fn test_collect_all() {
+        let server = MockServer::start(vec![MockResponse::new(200, json!(["a", "b"]).to_string())]);
+        let api = server.api();
+
+        let result = Lister::<String>::new(&api, "/pools".to_string())
+            .collect_all()
+            .await
+            .unwrap();
+
+        assert_eq!(result, ["a", "b"]);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_collect_all_with_progress() {
+        let server = MockServer::start(vec![
+            MockResponse::new(200, json!(vec!["a"; 100]).to_string()),
+            MockResponse::new(200, json!(["b"]).to_string()),
+        ]);
+        let api = server.api();
+        let mut reports = Vec::new();
+
+        let result = Lister::<String>::new(&api, "/pools".to_string())
+            .collect_all_with_progress(|fetched, total| reports.push((fetched, total)))
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 101);
+        assert_eq!(reports, [(100, None), (101, None)]);
+    }
+
+    #[tokio::test]
+    async fn test_export_ndjson() {
+        let server = MockServer::start(vec![
+            MockResponse::new(200, json!(vec!["a"; 100]).to_string()),
+            MockResponse::new(200, json!(["b\nc"]).to_string()),
+        ]);
+        let api = server.api();
+        let mut output = Vec::new();
+
+        let written = Lister::<String>::new(&api, "/pools".to_string())
+            .export_ndjson(&mut output)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(written, 101);
+        assert_eq!(lines.len(), 101);
+        assert_eq!(lines[0], "\"a\"");
+        assert_eq!(lines[100], "\"b\\nc\"");
+        assert!(output.ends_with('\n'));
+    }
+
+    #[tokio::test]
+    async fn test_items() {
+        let server = MockServer::start(vec![
+            MockResponse::new(200, json!(vec!["a"; 100]).to_string()),
+            MockResponse::new(200, json!(["bb", "c", "dd"]).to_string()),
+        ]);
+        let api = server.api();
+
+        let items: Vec<_> = Lister::<String>::new(&api, "/pools".to_string())
+            .filter_items(|item| item != "c")
+            .map_items(|item| item.len())
+            .collect()
+            .await;
+
+        let lengths: Vec<_> = items.into_iter().map(Result::unwrap).collect();
+        assert_eq!(lengths.len(), 102);
+        assert_eq!(lengths[99..], [1, 2, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_items_end_after_error() {
+        let error = json!({
+            "status_code": 500,
+            "error": "Internal Server Error",
+            "message": "An unexpected response was received from the backend."
+        });
+        let server = MockServer::start(vec![
+            MockResponse::new(200, json!(vec!["a"; 100]).to_string()),
+            MockResponse::new(500, error.to_string()),
+        ]);
+        let api = server.api();
+
+        let items: Vec<_> = Lister::<String>::new(&api, "/pools".to_string())
+            .items()
+            .collect()
+            .await;
+
+        assert_eq!(items.len(), 101);
+        assert!(items[..100].iter().all(Result::is_ok));
+        assert!(items[100].is_err());
+    }
+
+    #[cfg(feature = "cancellation")]
+    #[tokio::test]
+    async fn test_with_cancellation() {
+        let server = MockServer::start(vec![MockResponse::new(
+            200,
+            json!(vec!["a"; 100]).to_string(),
+        )]);
+        let api = server.api();
+        let token = tokio_util::sync::CancellationToken::new();
+        let mut pages =
+            Lister::<String>::new(&api, "/pools".to_string()).with_cancellation(token.clone());
+
+        assert!(pages.next().await.unwrap().is_ok());
+        token.cancel();
+
+        assert!(matches!(
+            pages.next().await,
+            Some(Err(BlockfrostError::Cancelled))
+        ));
+        assert!(pages.next().await.is_none());
+    }
+}
@@ -0,0 +1,116 @@
+//! Project ids from environment variables.
+use crate::{BlockfrostError, BlockfrostResult, Network};
+use std::env;
+
+/// Project id from `BLOCKFROST_PROJECT_ID`, or else `BLOCKFROST_API_KEY`.
+pub fn load_project_id() -> BlockfrostResult<String> {
+    find_project_id(None, |name| env::var(name).ok())
+}
+
+/// Project id for `network`, so several keys can be configured side by side.
+///
+/// Reads the network's own variable first (`BLOCKFROST_MAINNET_PROJECT_ID`,
+/// `BLOCKFROST_PREPROD_PROJECT_ID`, `BLOCKFROST_PREVIEW_PROJECT_ID` or
+/// `BLOCKFROST_TESTNET_PROJECT_ID`), then the variables of [`load_project_id`]. Those are
+/// skipped when their key is prefixed with another network, like a `mainnet...` key asked for
+/// `Preprod`.
+pub fn load_project_id_for(network: Network) -> BlockfrostResult<String> {
+    find_project_id(Some(network), |name| env::var(name).ok())
+}
+
+// Variables read for `network`, in order
+fn project_id_vars(network: Option<Network>) -> Vec<String> {
+    let network = network.map(|network| match network {
+        Network::Mainnet => "MAINNET",
+        Network::Preprod => "PREPROD",
+        Network::Preview => "PREVIEW",
+        Network::Testnet => "TESTNET",
+    });
+
+    network
+        .map(|name| format!("BLOCKFROST_{}_PROJECT_ID", name))
+        .into_iter()
+        .chain(["BLOCKFROST_PROJECT_ID".into(), "BLOCKFROST_API_KEY".into()])
+        .collect()
+}
+
+// First non-empty variable, among those whose key isn't for a network other than `network`
+fn find_project_id(
+    network: Option<Network>, lookup: impl Fn(&str) -> Option<String>,
+) -> BlockfrostResult<String> {
+    let vars = project_id_vars(network);
+
+    vars.iter()
+        .filter_map(|name| lookup(name))
+        .map(|project_id| project_id.trim().to_string())
+        .filter(|project_id| !project_id.is_empty())
+        .find(
+            |project_id| match (network, Network::from_project_id(project_id)) {
+                (Some(network), Some(key_network)) => network == key_network,
+                _ => true,
+            },
+        )
+        .ok_or_else(|| BlockfrostError::Configuration {
+            message: format!("no project id found, checked {}", vars.join(", ")),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const MAINNET_KEY: &str = "mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be";
+    const PREPROD_KEY: &str = "preprodxvMK4xOpp5mHJgihi055KDLU64JJv2be";
+
+    fn find(vars: &[(&str, &str)], network: Option<Network>) -> BlockfrostResult<String> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+
+        find_project_id(network, |name| {
+            vars.get(name).map(|value| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_load_project_id() {
+        assert_eq!(
+            find(&[("BLOCKFROST_API_KEY", MAINNET_KEY)], None).unwrap(),
+            MAINNET_KEY
+        );
+        assert_eq!(
+            find(
+                &[
+                    ("BLOCKFROST_PROJECT_ID", PREPROD_KEY),
+                    ("BLOCKFROST_API_KEY", MAINNET_KEY)
+                ],
+                None
+            )
+            .unwrap(),
+            PREPROD_KEY
+        );
+    }
+
+    #[test]
+    fn test_load_project_id_for() {
+        let vars = [
+            ("BLOCKFROST_PREPROD_PROJECT_ID", PREPROD_KEY),
+            ("BLOCKFROST_PROJECT_ID", MAINNET_KEY),
+        ];
+
+        assert_eq!(find(&vars, Some(Network::Preprod)).unwrap(), PREPROD_KEY);
+        assert_eq!(find(&vars, Some(Network::Mainnet)).unwrap(), MAINNET_KEY);
+        // The generic key is for mainnet
+        assert!(find(&vars, Some(Network::Preview)).is_err());
+    }
+
+    #[test]
+    fn test_missing_project_id() {
+        let error = find(&[("BLOCKFROST_API_KEY", " ")], Some(Network::Preview)).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Configuration error: no project id found, checked BLOCKFROST_PREVIEW_PROJECT_ID, \
+             BLOCKFROST_PROJECT_ID, BLOCKFROST_API_KEY"
+        );
+    }
+}
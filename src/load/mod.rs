@@ -0,0 +1,202 @@
+//! Configuration read from outside the program.
+//!
+//! [`from_toml`] and [`from_json`] read a project id and [`BlockFrostSettings`] from a file,
+//! every key is optional and missing ones keep the [`BlockFrostSettings::new`] defaults:
+//!
+//! ```toml
+//! project_id = "mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be"
+//! base_url = "https://cardano-mainnet.blockfrost.io/api/v0"
+//! timeout_secs = 30
+//! prefetch = 4
+//! compression = true
+//!
+//! [retry]
+//! amount = 3
+//! delay_ms = 500
+//! respect_rate_limiter = true
+//! retry_writes = false
+//!
+//! [rate_limit]
+//! requests_per_second = 10
+//! burst = 500
+//! ```
+//!
+//! The JSON form has the same keys, with `retry` and `rate_limit` as nested objects.
+use crate::{BlockFrostSettings, BlockfrostError, BlockfrostResult, RateLimit, RetrySettings};
+use serde::Deserialize;
+use std::{fs, path::Path, time::Duration};
+
+pub mod env;
+
+/// Project id and settings read from a configuration file.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// `None` when the file has no `project_id`, to read it from the environment instead.
+    pub project_id: Option<String>,
+    pub settings: BlockFrostSettings,
+}
+
+/// Read a TOML configuration file, see the [module docs](self) for its keys.
+pub fn from_toml(path: impl AsRef<Path>) -> BlockfrostResult<Config> {
+    let text = fs::read_to_string(path)?;
+    let file: ConfigFile = toml::from_str(&text)?;
+
+    Ok(file.into())
+}
+
+/// Read a JSON configuration file, with the same keys as [`from_toml`].
+pub fn from_json(path: impl AsRef<Path>) -> BlockfrostResult<Config> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)?;
+    let file: ConfigFile =
+        serde_json::from_str(&text).map_err(|reason| BlockfrostError::Parsing {
+            message: format!("invalid configuration {}: {}", path.display(), reason),
+        })?;
+
+    Ok(file.into())
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    project_id: Option<String>,
+    base_url: Option<String>,
+    timeout_secs: Option<u64>,
+    prefetch: Option<usize>,
+    compression: Option<bool>,
+    retry: Option<RetryFile>,
+    rate_limit: Option<RateLimitFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RetryFile {
+    amount: u64,
+    #[serde(default)]
+    delay_ms: u64,
+    #[serde(default)]
+    respect_rate_limiter: bool,
+    #[serde(default)]
+    retry_writes: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateLimitFile {
+    requests_per_second: u32,
+    burst: u32,
+}
+
+impl From<ConfigFile> for Config {
+    fn from(file: ConfigFile) -> Self {
+        let mut settings = BlockFrostSettings::new();
+
+        settings.base_url = file.base_url;
+        settings.timeout = file.timeout_secs.map(Duration::from_secs);
+        settings.rate_limit = file
+            .rate_limit
+            .map(|limit| RateLimit::new(limit.requests_per_second, limit.burst));
+        if let Some(prefetch) = file.prefetch {
+            settings.prefetch = prefetch;
+        }
+        if let Some(compression) = file.compression {
+            settings.compression = compression;
+        }
+        if let Some(retry) = file.retry {
+            settings.retry_settings = RetrySettings {
+                amount: retry.amount,
+                delay: Duration::from_millis(retry.delay_ms),
+                respect_rate_limiter: retry.respect_rate_limiter,
+                retry_writes: retry.retry_writes,
+            };
+        }
+
+        Config {
+            project_id: file.project_id,
+            settings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn write(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("blockfrost-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let config = from_toml(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/examples/blockfrost.toml"
+        ))
+        .unwrap();
+        let settings = &config.settings;
+
+        assert_eq!(
+            config.project_id.as_deref(),
+            Some("mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be")
+        );
+        assert_eq!(
+            settings.base_url.as_deref(),
+            Some(crate::CARDANO_MAINNET_URL)
+        );
+        assert_eq!(settings.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(settings.prefetch, 4);
+        assert_eq!(
+            settings.retry_settings,
+            RetrySettings {
+                amount: 3,
+                delay: Duration::from_millis(500),
+                respect_rate_limiter: true,
+                retry_writes: false,
+            }
+        );
+        assert_eq!(settings.rate_limit, Some(RateLimit::blockfrost()));
+    }
+
+    #[test]
+    fn test_toml_and_json_agree() {
+        let toml = write(
+            "config.toml",
+            "project_id = \"preprodxvMK4xOpp5mHJgihi055KDLU64JJv2be\"\n\
+             [retry]\namount = 2\ndelay_ms = 100\n",
+        );
+        let json = write(
+            "config.json",
+            r#"{
+                "project_id": "preprodxvMK4xOpp5mHJgihi055KDLU64JJv2be",
+                "retry": { "amount": 2, "delay_ms": 100 }
+            }"#,
+        );
+
+        let from_toml = from_toml(&toml).unwrap();
+        let from_json = from_json(&json).unwrap();
+
+        assert_eq!(from_toml.project_id, from_json.project_id);
+        assert_eq!(
+            format!("{:?}", from_toml.settings),
+            format!("{:?}", from_json.settings)
+        );
+        assert_eq!(from_toml.settings.timeout, None);
+        fs::remove_file(toml).unwrap();
+        fs::remove_file(json).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let path = write("typo.toml", "timeout_sec = 30\n");
+
+        assert!(matches!(from_toml(&path), Err(BlockfrostError::Toml(_))));
+        assert!(matches!(
+            from_toml("/nonexistent/blockfrost.toml"),
+            Err(BlockfrostError::Io(_))
+        ));
+        fs::remove_file(path).unwrap();
+    }
+}
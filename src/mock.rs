@@ -0,0 +1,131 @@
+// Minimal HTTP/1.1 server used by unit tests that need real responses.
+//
+// Every connection is answered with the next queued response (the last one repeats) and then
+// closed, raw requests are recorded so tests can assert on what was sent.
+use crate::{BlockFrostSettings, BlockfrostAPI};
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+    time::Instant,
+};
+
+#[derive(Clone, Debug)]
+pub(crate) struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+pub(crate) struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<(Instant, String)>>>,
+}
+
+impl MockServer {
+    pub fn start(responses: Vec<MockResponse>) -> Self {
+        Self::start_with(move |index, _| responses[index.min(responses.len() - 1)].clone())
+    }
+
+    /// Answer each request with `handler(index, raw_request)`.
+    pub fn start_with(handler: impl Fn(usize, &str) -> MockResponse + Send + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        thread::spawn(move || {
+            for (index, stream) in listener.incoming().enumerate() {
+                let Ok(mut stream) = stream else { break };
+                let request = read_request(&mut stream);
+                let response = handler(index, &request);
+                recorded.lock().unwrap().push((Instant::now(), request));
+
+                let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+                for (name, value) in &response.headers {
+                    head.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                head.push_str(&format!(
+                    "Content-Length: {}\r\nConnection: close\r\n\r\n",
+                    response.body.len()
+                ));
+
+                let _ = stream.write_all(head.as_bytes());
+                let _ = stream.write_all(&response.body);
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    /// Api client pointed at this server.
+    pub fn api(&self) -> BlockfrostAPI {
+        self.api_with_settings(BlockFrostSettings::new())
+    }
+
+    pub fn api_with_settings(&self, settings: BlockFrostSettings) -> BlockfrostAPI {
+        let mut api = BlockfrostAPI::new("mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be", settings);
+        api.base_url = self.url.clone();
+        api
+    }
+
+    /// Raw requests received so far (request line, headers and body).
+    pub fn requests(&self) -> Vec<String> {
+        let requests = self.requests.lock().unwrap();
+        requests
+            .iter()
+            .map(|(_, request)| request.clone())
+            .collect()
+    }
+
+    /// When each request was received, in the same order as [`requests`](Self::requests).
+    pub fn arrivals(&self) -> Vec<Instant> {
+        let requests = self.requests.lock().unwrap();
+        requests.iter().map(|(arrival, _)| *arrival).collect()
+    }
+}
+
+fn read_request(stream: &mut impl Read) -> String {
+    let mut data = Vec::new();
+    let mut buffer = [0; 4096];
+
+    loop {
+        let read = stream.read(&mut buffer).unwrap_or(0);
+        data.extend_from_slice(&buffer[..read]);
+
+        let text = String::from_utf8_lossy(&data).to_string();
+        if let Some(head_end) = text.find("\r\n\r\n") {
+            let content_length = text[..head_end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+
+            if data.len() >= head_end + 4 + content_length {
+                return text;
+            }
+        }
+        if read == 0 {
+            return text;
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{CARDANO_MAINNET_URL, CARDANO_PREPROD_URL, CARDANO_PREVIEW_URL, CARDANO_TESTNET_URL};
+
+/// Cardano network an api talks to, see
+/// [`BlockfrostAPI::cardano_network`](crate::BlockfrostAPI::cardano_network).
+///
+/// Gives the constants transaction builders and address validators need for each network.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Network {
+    Mainnet,
+    Preprod,
+    Preview,
+    /// The legacy testnet, shut down in favor of `Preprod` and `Preview`.
+    Testnet,
+}
+
+impl Network {
+    /// Network of one of the Blockfrost base urls, like [`CARDANO_PREPROD_URL`].
+    ///
+    /// `None` for other urls, such as self-hosted instances.
+    pub fn from_base_url(base_url: &str) -> Option<Self> {
+        let base_url = base_url.trim_end_matches('/');
+
+        [
+            Network::Mainnet,
+            Network::Preprod,
+            Network::Preview,
+            Network::Testnet,
+        ]
+        .into_iter()
+        .find(|network| network.base_url() == base_url)
+    }
+
+    /// Network a Blockfrost project id belongs to, from its prefix (`mainnet...`, `preprod...`).
+    ///
+    /// `None` if the id has no known prefix or anything but ASCII letters and digits after it.
+    pub fn from_project_id(project_id: &str) -> Option<Self> {
+        [
+            (Network::Mainnet, "mainnet"),
+            (Network::Preprod, "preprod"),
+            (Network::Preview, "preview"),
+            (Network::Testnet, "testnet"),
+        ]
+        .into_iter()
+        .find_map(|(network, prefix)| {
+            let key = project_id.strip_prefix(prefix)?;
+            let well_formed = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric());
+            well_formed.then_some(network)
+        })
+    }
+
+    /// Blockfrost base url of this network.
+    pub fn base_url(&self) -> &'static str {
+        match self {
+            Network::Mainnet => CARDANO_MAINNET_URL,
+            Network::Preprod => CARDANO_PREPROD_URL,
+            Network::Preview => CARDANO_PREVIEW_URL,
+            Network::Testnet => CARDANO_TESTNET_URL,
+        }
+    }
+
+    /// Bech32 prefix of payment addresses, `addr` or `addr_test`.
+    pub fn bech32_address_prefix(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "addr",
+            _ => "addr_test",
+        }
+    }
+
+    /// Bech32 prefix of stake addresses, `stake` or `stake_test`.
+    pub fn bech32_stake_prefix(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "stake",
+            _ => "stake_test",
+        }
+    }
+
+    /// Network id in address headers, 1 for mainnet and 0 for the testnets.
+    pub fn network_id(&self) -> u8 {
+        match self {
+            Network::Mainnet => 1,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mainnet_and_preprod() {
+        assert_eq!(Network::Mainnet.bech32_address_prefix(), "addr");
+        assert_eq!(Network::Mainnet.bech32_stake_prefix(), "stake");
+        assert_eq!(Network::Mainnet.network_id(), 1);
+
+        assert_eq!(Network::Preprod.bech32_address_prefix(), "addr_test");
+        assert_eq!(Network::Preprod.bech32_stake_prefix(), "stake_test");
+        assert_eq!(Network::Preprod.network_id(), 0);
+    }
+
+    #[test]
+    fn test_from_base_url() {
+        assert_eq!(
+            Network::from_base_url(CARDANO_MAINNET_URL),
+            Some(Network::Mainnet)
+        );
+        assert_eq!(
+            Network::from_base_url("https://cardano-preview.blockfrost.io/api/v0/"),
+            Some(Network::Preview)
+        );
+        assert_eq!(Network::from_base_url("http://localhost:3000"), None);
+    }
+
+    #[test]
+    fn test_from_project_id() {
+        assert_eq!(
+            Network::from_project_id("preprodxvMK4xOpp5mHJgihi055KDLU64JJv2be"),
+            Some(Network::Preprod)
+        );
+        assert_eq!(Network::from_project_id("preprod"), None);
+        assert_eq!(Network::from_project_id("preprod xvMK4xOpp5m"), None);
+        assert_eq!(Network::from_project_id("xvMK4xOpp5mHJgihi055"), None);
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Pagination {
     pub fetch_all: bool,
     pub count: usize,
@@ -34,6 +34,33 @@ impl Pagination {
         }
     }
 
+    /// Wrap the `items` of a request made with this pagination in a [`Page`], for manual
+    /// pagination loops.
+    ///
+    /// ```no_run
+    /// # async fn run(api: blockfrost::BlockfrostAPI) -> blockfrost::BlockfrostResult<()> {
+    /// use blockfrost::{Order, Pagination};
+    ///
+    /// let mut pagination = Pagination::new(Order::Asc, 1, 100);
+    /// loop {
+    ///     let page = pagination.page(api.blocks_latest_txs(pagination).await?);
+    ///     // ...
+    ///     if page.is_last {
+    ///         break;
+    ///     }
+    ///     pagination.page += 1;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn page<T>(&self, items: Vec<T>) -> Page<T> {
+        Page {
+            is_last: self.fetch_all || items.len() < self.count,
+            page_number: self.page,
+            items,
+        }
+    }
+
     pub fn order_to_string(&self) -> String {
         match self.order {
             Order::Asc => "asc".to_string(),
@@ -42,8 +69,39 @@ impl Pagination {
     }
 }
 
-#[derive(Clone, Copy)]
+/// One page of a listing, created by [`Pagination::page`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Number of this page, starting at 1.
+    pub page_number: usize,
+    /// Whether this page has fewer items than the requested `count`, or holds every page.
+    ///
+    /// When the last page happens to be exactly full this is `false`, and the next page is
+    /// empty (and last).
+    pub is_last: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Order {
     Asc,
     Desc,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page() {
+        let pagination = Pagination::new(Order::Asc, 3, 2);
+
+        let page = pagination.page(vec![1, 2]);
+        assert_eq!(page.page_number, 3);
+        assert!(!page.is_last);
+
+        assert!(pagination.page(vec![1]).is_last);
+        assert!(pagination.page(Vec::<i32>::new()).is_last);
+        assert!(Pagination::all().page(vec![0; 100]).is_last);
+    }
+}
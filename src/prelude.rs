@@ -0,0 +1,28 @@
+//! The items most programs need, in one import.
+//!
+//! ```no_run
+//! use blockfrost::prelude::*;
+//!
+//! # async fn example() -> BlockfrostResult<()> {
+//! let api = BlockfrostAPI::new("mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be", BlockFrostSettings::new());
+//! let mut stakes = api.epochs_stakes_all(500).items();
+//!
+//! while let Some(stake) = stakes.next().await {
+//!     let stake = stake?;
+//!     println!("{} delegates {} to {}", stake.stake_address, stake.amount, stake.pool_id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Besides the clients, their settings and the error types, it brings the extension traits
+//! into scope, so their methods can be called on the responses, and [`StreamExt`] for
+//! [`Lister`] and [`ChainEvent`] streams. Everything here is also exported at the crate root.
+pub use crate::{
+    AccountsApi, AddressContentTotalExt, AmountExt, AmountsExt, AssetExt, BlockContentExt,
+    BlockFrostSettings, BlockfrostAPI, BlockfrostError, BlockfrostIPFS, BlockfrostResult,
+    BlocksApi, ChainEvent, DatumExt, EpochContentExt, EpochParamContentExt, IpfsSettings, Lister,
+    Network, Order, Pagination, ReferenceScriptExt, TransactionsApi, TxContentExt,
+    TxContentUtxoExt,
+};
+pub use futures::StreamExt;
@@ -0,0 +1,95 @@
+use crate::RateLimit;
+use std::{sync::Mutex, time::Duration};
+use tokio::time::{sleep, Instant};
+
+// Token bucket implementing a `RateLimit`.
+//
+// Tokens are reserved ahead of time: a request that finds the bucket empty takes a token anyway
+// (leaving the bucket negative) and sleeps until its token would have been refilled, so waiting
+// requests are released in order, one every `1 / requests_per_second` seconds.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    rate_limit: RateLimit,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(rate_limit: RateLimit) -> Self {
+        Self {
+            rate_limit,
+            state: Mutex::new(State {
+                tokens: rate_limit.burst.max(1) as f64,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a request is allowed to be sent.
+    pub(crate) async fn acquire(&self) {
+        let wait = self.reserve();
+
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+
+    fn reserve(&self) -> Duration {
+        let rate = self.rate_limit.requests_per_second.max(1) as f64;
+        let burst = self.rate_limit.burst.max(1) as f64;
+        // Safety: the lock is never held across an await or a panic.
+        let mut state = self.state.lock().unwrap();
+
+        let now = Instant::now();
+        let refilled = now.duration_since(state.updated).as_secs_f64() * rate;
+        state.tokens = (state.tokens + refilled).min(burst) - 1.0;
+        state.updated = now;
+
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_then_spaced_out() {
+        let limiter = RateLimiter::new(RateLimit::new(10, 3));
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(500));
+        assert!(start.elapsed() < Duration::from_millis(600));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bucket_refills() {
+        let limiter = RateLimiter::new(RateLimit::new(2, 2));
+
+        limiter.acquire().await;
+        limiter.acquire().await;
+        sleep(Duration::from_secs(1)).await;
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+}
@@ -1,131 +1,364 @@
 use crate::{
-    json_error, pagination::Pagination, process_error_response, reqwest_error, url::Url,
-    BlockfrostError, RetrySettings,
+    json_error, process_error_response, rate_limiter::RateLimiter, reqwest_error, Backoff,
+    BlockfrostError, RateLimitInfo, RetrySettings,
 };
-use futures::future;
-use reqwest::{Client, RequestBuilder, Response, StatusCode};
-use serde::de::DeserializeOwned;
+use reqwest::{header::HeaderMap, RequestBuilder, Response, StatusCode};
 use serde_json::from_str;
-use std::{future::Future, thread};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+// How requests are retried and throttled and their errors reported, borrowed from the settings
+// of the api sending them.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct RequestPolicy<'a> {
+    pub retry_settings: RetrySettings,
+    // Replaces `retry_settings` when set
+    pub backoff: Option<&'a dyn Backoff>,
+    pub rate_limiter: Option<&'a RateLimiter>,
+    pub warn_unexpected_status: bool,
+}
+
+impl RequestPolicy<'_> {
+    // Policy of requests that aren't safe to repeat, retried only with `retry_writes`
+    pub(crate) fn for_writes(self) -> Self {
+        if self.retry_settings.retry_writes {
+            return self;
+        }
+
+        RequestPolicy {
+            retry_settings: RetrySettings {
+                amount: 0,
+                ..self.retry_settings
+            },
+            backoff: None,
+            ..self
+        }
+    }
+}
 
 // Used only for simple and common GET requests.
 // Functions that require extra logic may not call this.
-pub(crate) fn send_get_request<T>(
-    client: &Client, url: String, retry_settings: RetrySettings,
-) -> impl Future<Output = Result<T, BlockfrostError>> + Send
+pub(crate) async fn send_get_request<T>(
+    request: RequestBuilder, url: String, policy: RequestPolicy<'_>,
+) -> Result<T, BlockfrostError>
 where
     T: serde::de::DeserializeOwned,
 {
-    let request = client.get(&url);
+    let (status, headers, text) = send_request(request, policy)
+        .await
+        .map_err(|reason| reqwest_error(&url, reason))?;
 
-    async move {
-        let (status, text) = send_request(request, retry_settings)
-            .await
-            .map_err(|reason| reqwest_error(&url, reason))?;
-
-        if !status.is_success() {
-            return Err(process_error_response(&text, status, &url));
-        }
-
-        from_str::<T>(&text).map_err(|reason| json_error(url, text, reason))
+    if !status.is_success() {
+        return Err(process_error_response(
+            &text,
+            status,
+            &headers,
+            &url,
+            policy.warn_unexpected_status,
+        ));
     }
+
+    from_str::<T>(&text).map_err(|reason| json_error(url, text, reason))
 }
 
-// Send requests with delayed retries, cloning the request builder only when necessary.
+// Send requests with delayed retries.
+//
+// Every attempt waits for the rate limiter, retries only do so if `respect_rate_limiter` is set.
 pub(crate) async fn send_request_unprocessed(
-    request: RequestBuilder, retry_settings: RetrySettings,
+    request: RequestBuilder, policy: RequestPolicy<'_>,
 ) -> reqwest::Result<Response> {
-    for _ in 1..retry_settings.amount {
-        let request = clone_request(&request);
-        let response = request.send().await;
-        let retry_codes = [
-            StatusCode::REQUEST_TIMEOUT,
-            StatusCode::PAYLOAD_TOO_LARGE,
-            StatusCode::TOO_MANY_REQUESTS,
-            StatusCode::INTERNAL_SERVER_ERROR,
-            StatusCode::BAD_GATEWAY,
-            StatusCode::SERVICE_UNAVAILABLE,
-            StatusCode::GATEWAY_TIMEOUT,
-        ];
-
-        if let Err(err) = &response {
-            if let Some(status) = err.status() {
-                if retry_codes.contains(&status) {
-                    thread::sleep(retry_settings.delay);
-                    continue;
-                }
+    let RequestPolicy {
+        retry_settings,
+        rate_limiter,
+        ..
+    } = policy;
+    let mut backoff = policy.backoff.map(|backoff| backoff.clone_box());
+    let mut attempt = 0;
+
+    loop {
+        if let Some(rate_limiter) = rate_limiter {
+            if attempt == 0 || retry_settings.respect_rate_limiter {
+                rate_limiter.acquire().await;
             }
+        }
 
+        let response = match request.try_clone() {
+            Some(request) => request.send().await,
+            // Multipart uploads stream their body and can't be cloned, they are sent only once
+            None => return request.send().await,
+        };
+
+        if !should_retry(&response) {
             return response;
-        } else {
-            return response;
         }
+
+        attempt += 1;
+        let delay = match &mut backoff {
+            Some(backoff) => backoff.next_delay(attempt, retry_after(&response)),
+            None => (u64::from(attempt) <= retry_settings.amount)
+                .then(|| retry_delay(retry_settings.delay)),
+        };
+
+        match delay {
+            Some(delay) => tokio::time::sleep(delay).await,
+            None => return response,
+        }
+    }
+}
+
+const RETRY_CODES: [StatusCode; 7] = [
+    StatusCode::REQUEST_TIMEOUT,
+    StatusCode::PAYLOAD_TOO_LARGE,
+    StatusCode::TOO_MANY_REQUESTS,
+    StatusCode::INTERNAL_SERVER_ERROR,
+    StatusCode::BAD_GATEWAY,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::GATEWAY_TIMEOUT,
+];
+
+fn should_retry(response: &reqwest::Result<Response>) -> bool {
+    match response {
+        Ok(response) => RETRY_CODES.contains(&response.status()),
+        Err(err) => err.is_timeout() || err.is_connect(),
     }
+}
+
+// Same conditions as `should_retry`, once the response has been turned into an error
+pub(crate) fn is_retryable_error(error: &BlockfrostError) -> bool {
+    match error {
+        BlockfrostError::Response { reason, .. } => StatusCode::from_u16(reason.status_code)
+            .is_ok_and(|status| RETRY_CODES.contains(&status)),
+        BlockfrostError::Reqwest { reason, .. } => reason.is_timeout() || reason.is_connect(),
+        _ => false,
+    }
+}
 
-    request.send().await
+fn retry_after(response: &reqwest::Result<Response>) -> Option<Duration> {
+    RateLimitInfo::from_headers(response.as_ref().ok()?.headers()).retry_after
+}
+
+// The delay plus a random jitter of up to the delay itself.
+fn retry_delay(delay: Duration) -> Duration {
+    // RandomState is randomly seeded, good enough for spreading retries without a rand dependency
+    let random = RandomState::new().build_hasher().finish();
+    let jitter = delay.as_nanos() as u64 / 1_000 * (random % 1_000) / 1_000;
+
+    delay + Duration::from_micros(jitter)
 }
 
 // Calls send_request_unprocessed but break is down
 pub(crate) async fn send_request(
-    request: RequestBuilder, retry_settings: RetrySettings,
-) -> reqwest::Result<(StatusCode, String)> {
-    let response = send_request_unprocessed(request, retry_settings).await?;
+    request: RequestBuilder, policy: RequestPolicy<'_>,
+) -> reqwest::Result<(StatusCode, HeaderMap, String)> {
+    let response = send_request_unprocessed(request, policy).await?;
     let status = response.status();
+    let headers = response.headers().clone();
     let text = response.text().await?;
 
-    Ok((status, text))
+    Ok((status, headers, text))
 }
 
-fn clone_request(request: &RequestBuilder) -> RequestBuilder {
-    // Safety:
-    //     Requests in this crate never use streams.
-    //     .try_clone() will always succeed.
-    request.try_clone().unwrap()
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{MockResponse, MockServer},
+        BlockFrostSettings, ConstantBackoff, Pagination, RateLimit,
+    };
+    use futures::future;
 
-pub(crate) async fn fetch_all_pages<T: DeserializeOwned>(
-    client: &Client, url: String, retry_settings: RetrySettings, pagination: Pagination,
-) -> Result<Vec<T>, BlockfrostError> {
-    const BATCH_SIZE: usize = 10;
-
-    let mut page_start: usize = 1;
-    let mut is_end = false;
-    let mut result = Vec::new();
-
-    while !is_end {
-        let batch = Url::generate_batch(url.as_str(), BATCH_SIZE, page_start, pagination)?;
-        let responses: Vec<Result<Vec<T>, BlockfrostError>> =
-            future::join_all(batch.into_iter().map(|url| {
-                let client = client.clone();
-                async move {
-                    let request = client.get(&url);
-                    let (status, text) = send_request(request, retry_settings)
-                        .await
-                        .map_err(|reason| reqwest_error(&url, reason))?;
-
-                    if !status.is_success() {
-                        return Err(process_error_response(&text, status, &url));
-                    }
-
-                    from_str::<Vec<T>>(&text).map_err(|reason| json_error(url, text, reason))
-                }
-            }))
-            .await;
-
-        for response in responses {
-            match response {
-                Ok(data) => {
-                    if data.len() < pagination.count {
-                        is_end = true;
-                    }
-                    result.extend(data);
-                }
-                Err(err) => return Err(err),
-            }
-        }
+    #[test]
+    fn test_retry_delay_jitter() {
+        let delay = Duration::from_millis(100);
+        let delays: Vec<Duration> = (0..100).map(|_| retry_delay(delay)).collect();
+
+        assert!(delays.iter().all(|d| *d >= delay && *d < delay * 2));
+        assert!(delays.iter().any(|d| *d != delays[0]));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_rate_limited_retries_are_spread() {
+        let too_many = MockResponse::new(429, r#"{"status_code":429,"error":"","message":""}"#);
+        let mut responses = vec![too_many; 100];
+        responses.push(MockResponse::new(200, "[]"));
+        let server = MockServer::start(responses);
 
-        page_start += BATCH_SIZE;
+        let mut settings = BlockFrostSettings::new();
+        settings.retry_settings = RetrySettings::new(1, Duration::from_millis(100));
+        let api = server.api_with_settings(settings);
+
+        let results =
+            future::join_all((0..100).map(|_| api.blocks_latest_txs(Pagination::default()))).await;
+
+        assert!(results.iter().all(Result::is_ok));
+        let retries = &server.arrivals()[100..];
+        let first = retries.iter().min().unwrap();
+        let last = retries.iter().max().unwrap();
+        assert_eq!(retries.len(), 100);
+        assert!(last.duration_since(*first) > Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_retries_go_through_rate_limiter() {
+        let server = MockServer::start(vec![
+            MockResponse::new(503, ""),
+            MockResponse::new(503, ""),
+            MockResponse::new(200, "[]"),
+        ]);
+
+        let mut settings = BlockFrostSettings::new();
+        settings.retry_settings = RetrySettings {
+            amount: 2,
+            delay: Duration::ZERO,
+            respect_rate_limiter: true,
+            retry_writes: false,
+        };
+        settings.rate_limit = Some(RateLimit::new(20, 1));
+        let api = server.api_with_settings(settings);
+
+        api.blocks_latest_txs(Pagination::default()).await.unwrap();
+
+        let arrivals = server.arrivals();
+        assert_eq!(arrivals.len(), 3);
+        assert!(arrivals[2].duration_since(arrivals[0]) >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn test_no_retries_by_default() {
+        let server = MockServer::start(vec![
+            MockResponse::new(503, ""),
+            MockResponse::new(200, "[]"),
+        ]);
+        let api = server.api();
+
+        assert!(api.blocks_latest_txs(Pagination::default()).await.is_err());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_backoff_replaces_retry_settings() {
+        let server = MockServer::start(vec![
+            MockResponse::new(503, ""),
+            MockResponse::new(503, ""),
+            MockResponse::new(503, ""),
+            MockResponse::new(200, "[]"),
+        ]);
+
+        let mut settings = BlockFrostSettings::new();
+        settings.retry_settings = RetrySettings::new(5, Duration::ZERO);
+        settings.set_backoff(Box::new(ConstantBackoff::new(Duration::ZERO, 2)));
+        let api = server.api_with_settings(settings);
+
+        assert!(api.blocks_latest_txs(Pagination::default()).await.is_err());
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_backoff_gets_retry_after() {
+        let server = MockServer::start(vec![
+            MockResponse::new(429, "").header("Retry-After", "1"),
+            MockResponse::new(200, "[]"),
+        ]);
+
+        let mut settings = BlockFrostSettings::new();
+        settings.set_backoff(Box::new(ConstantBackoff::new(Duration::ZERO, 1)));
+        let api = server.api_with_settings(settings);
+
+        api.blocks_latest_txs(Pagination::default()).await.unwrap();
+
+        let arrivals = server.arrivals();
+        assert!(arrivals[1].duration_since(arrivals[0]) >= Duration::from_secs(1));
     }
 
-    Ok(result)
+    #[tokio::test]
+    async fn test_rate_limit_info() {
+        let server = MockServer::start(vec![MockResponse::new(
+            429,
+            r#"{"status_code":429,"error":"Project Over Limit","message":"Usage is over limit."}"#,
+        )
+        .header("Retry-After", "7")
+        .header("X-RateLimit-Limit", "10")
+        .header("X-RateLimit-Remaining", "0")]);
+        let api = server.api();
+
+        let error = api
+            .blocks_latest_txs(Pagination::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.rate_limit_info(),
+            Some(&RateLimitInfo {
+                retry_after: Some(Duration::from_secs(7)),
+                limit: Some(10),
+                remaining: Some(0),
+                reset: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_project_over_limit_not_retried() {
+        let server = MockServer::start(vec![
+            MockResponse::new(
+                418,
+                r#"{"status_code":418,"error":"Requested Banned","message":"IP has been auto-banned for flooding."}"#,
+            ),
+            MockResponse::new(200, "[]"),
+        ]);
+        let mut settings = BlockFrostSettings::new();
+        settings.retry_settings = RetrySettings::new(3, Duration::ZERO);
+        let api = server.api_with_settings(settings);
+
+        let error = api
+            .blocks_latest_txs(Pagination::default())
+            .await
+            .unwrap_err();
+
+        assert!(error.is_project_over_limit());
+        assert!(!is_retryable_error(&error));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_not_retried() {
+        let server = MockServer::start(vec![
+            MockResponse::new(
+                403,
+                r#"{"status_code":403,"error":"Forbidden","message":"Invalid project token."}"#,
+            ),
+            MockResponse::new(200, "[]"),
+        ]);
+        let mut settings = BlockFrostSettings::new();
+        settings.retry_settings = RetrySettings::new(3, Duration::ZERO);
+        let api = server.api_with_settings(settings);
+
+        let error = api
+            .blocks_latest_txs(Pagination::default())
+            .await
+            .unwrap_err();
+        let message = error.to_string();
+
+        assert!(error.is_unauthorized());
+        assert!(!is_retryable_error(&error));
+        assert_eq!(server.requests().len(), 1);
+        assert!(message.contains("check that the project id is valid"));
+        assert!(!message.contains("mainnetxvMK4xOpp5mHJgihi055KDLU64JJv2be"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_info_only_on_429() {
+        let server = MockServer::start(vec![MockResponse::new(503, "").header("Retry-After", "7")]);
+        let api = server.api();
+
+        let error = api
+            .blocks_latest_txs(Pagination::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.rate_limit_info(), None);
+    }
 }
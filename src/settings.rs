@@ -1,21 +1,295 @@
-use std::time::Duration;
+use crate::{Backoff, BlockfrostError, Clock, ExponentialBackoff, Network, TokioClock};
+use reqwest::header::{HeaderMap, USER_AGENT};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct BlockFrostSettings {
     pub retry_settings: RetrySettings,
+    /// Request `gzip`/`brotli` compressed responses and decode them transparently.
+    ///
+    /// Enabled by default, large list responses (`blocks_txs`, metadata) shrink considerably.
+    pub compression: bool,
+    /// Client-side rate limit shared by every clone of the api, disabled by default.
+    pub rate_limit: Option<RateLimit>,
+    /// Custom retry timing, replaces `retry_settings` when set.
+    pub backoff: Option<Box<dyn Backoff>>,
+    /// Timeout of each request attempt, none by default.
+    pub timeout: Option<Duration>,
+    /// Pages fetched concurrently by the `*_all` listers, see [`Lister::prefetch`].
+    ///
+    /// [`Lister::prefetch`]: crate::Lister::prefetch
+    pub prefetch: usize,
+    /// API URL to use instead of the one picked from the project id, for self-hosted instances.
+    pub base_url: Option<String>,
+    /// Redirects are not followed by default, the 3xx response is returned as an error.
+    pub redirect_policy: RedirectPolicy,
+    /// `(base_url, project_id)` pairs tried in order when a request fails, see
+    /// [`set_fallback_urls`](Self::set_fallback_urls).
+    pub fallbacks: Vec<(String, String)>,
+    /// Check the project id against the base url in [`validate`](Self::validate), enabled by
+    /// default.
+    pub validate_project_id: bool,
+    /// Headers sent with every request, see [`set_extra_headers`](Self::set_extra_headers).
+    pub extra_headers: HeaderMap,
+    /// Version of the backend API, see [`set_api_version`](Self::set_api_version).
+    pub api_version: Option<String>,
+    /// Reject malformed hashes before sending them, see
+    /// [`set_validate_inputs`](Self::set_validate_inputs).
+    pub validate_inputs: bool,
+    /// Addresses used for these hosts instead of asking DNS, see [`resolve`](Self::resolve).
+    pub resolve_overrides: Vec<(String, SocketAddr)>,
+    /// Print a warning to stderr for undocumented error status codes, and once for a `404`
+    /// when the backend version is known, enabled by default.
+    pub warn_unexpected_status: bool,
+    /// Print a warning to stderr for block fields read as `None` because of an unexpected
+    /// value, enabled by default.
+    pub warn_lenient_fields: bool,
+    /// Time source of the polling helpers, [`TokioClock`] by default.
+    pub clock: Arc<dyn Clock>,
 }
 
 impl BlockFrostSettings {
+    /// Create bare [`BlockFrostSettings`]: no retries, no rate limit, no timeout, compression on
+    /// and one page fetched at a time.
     pub fn new() -> Self {
         Self {
             retry_settings: RetrySettings::default(),
+            compression: true,
+            rate_limit: None,
+            backoff: None,
+            timeout: None,
+            prefetch: 1,
+            base_url: None,
+            redirect_policy: RedirectPolicy::None,
+            fallbacks: Vec::new(),
+            validate_project_id: true,
+            extra_headers: HeaderMap::new(),
+            api_version: None,
+            validate_inputs: false,
+            resolve_overrides: Vec::new(),
+            warn_unexpected_status: true,
+            warn_lenient_fields: true,
+            clock: Arc::new(TokioClock),
         }
     }
+
+    /// Settings for services that must stay up without exceeding the API limits.
+    ///
+    /// - Rate limit: [`RateLimit::blockfrost`], retries included.
+    /// - Retries: [`ExponentialBackoff`] starting at 500ms, up to 3 retries.
+    /// - Timeout: 30 seconds.
+    /// - Prefetch: 1 page.
+    pub fn production() -> Self {
+        Self {
+            retry_settings: RetrySettings {
+                respect_rate_limiter: true,
+                ..RetrySettings::default()
+            },
+            rate_limit: Some(RateLimit::blockfrost()),
+            backoff: Some(Box::new(ExponentialBackoff::new(
+                Duration::from_millis(500),
+                3,
+            ))),
+            timeout: Some(Duration::from_secs(30)),
+            ..Self::new()
+        }
+    }
+
+    /// Settings for indexers walking large listings, trading latency for throughput.
+    ///
+    /// - Rate limit: [`RateLimit::blockfrost`], retries included.
+    /// - Retries: [`ExponentialBackoff`] starting at 1s, up to 8 retries.
+    /// - Timeout: 2 minutes.
+    /// - Prefetch: 10 pages.
+    pub fn indexer() -> Self {
+        Self {
+            retry_settings: RetrySettings {
+                respect_rate_limiter: true,
+                ..RetrySettings::default()
+            },
+            rate_limit: Some(RateLimit::blockfrost()),
+            backoff: Some(Box::new(ExponentialBackoff::new(Duration::from_secs(1), 8))),
+            timeout: Some(Duration::from_secs(120)),
+            prefetch: 10,
+            ..Self::new()
+        }
+    }
+
+    /// Enable or disable response compression (sent as the `Accept-Encoding` header).
+    pub fn set_compression(&mut self, compression: bool) -> &mut Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Use a custom [`Backoff`] to decide when and how often requests are retried.
+    pub fn set_backoff(&mut self, backoff: Box<dyn Backoff>) -> &mut Self {
+        self.backoff = Some(backoff);
+        self
+    }
+
+    /// Use a custom [`Clock`] for the deadlines and sleeps of the polling helpers.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) -> &mut Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Send requests to `base_url` (such as `http://localhost:3000/api/v0`), with or without a
+    /// trailing slash.
+    pub fn set_base_url(&mut self, base_url: impl Into<String>) -> &mut Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Fail over to other instances, given as `(base_url, project_id)` pairs.
+    ///
+    /// When a GET request still fails after its retries with a retryable error (a timeout, a
+    /// connection error or one of the status codes listed in [`RetrySettings`]), it is sent again
+    /// to the next fallback, in the given order. Other errors such as a `404` are returned right
+    /// away. Every request starts from the primary base url,
+    /// [`last_base_url`](crate::BlockfrostAPI::last_base_url) tells which one answered last.
+    ///
+    /// Each page of a `Pagination::all()` listing fails over on its own. Submitting transactions
+    /// only uses the primary base url.
+    pub fn set_fallback_urls(&mut self, fallbacks: Vec<(String, String)>) -> &mut Self {
+        self.fallbacks = fallbacks;
+        self
+    }
+
+    /// Choose whether redirects are followed, see [`RedirectPolicy`].
+    pub fn set_redirect_policy(&mut self, redirect_policy: RedirectPolicy) -> &mut Self {
+        self.redirect_policy = redirect_policy;
+        self
+    }
+
+    /// Send `headers` with every request, such as the auth token of an API gateway in front of
+    /// Blockfrost.
+    ///
+    /// The `project_id` and `User-Agent` headers set by the api always take precedence,
+    /// [`validate`](Self::validate) reports extra headers that try to replace them.
+    pub fn set_extra_headers(&mut self, headers: HeaderMap) -> &mut Self {
+        self.extra_headers = headers;
+        self
+    }
+
+    /// Version of the backend API (like `"0.1.60"`) returned by
+    /// [`backend_version`](crate::BlockfrostAPI::backend_version) without asking the root
+    /// endpoint, and named in the warning about a `404` from an older self-hosted backend.
+    pub fn set_api_version(&mut self, api_version: impl Into<String>) -> &mut Self {
+        self.api_version = Some(api_version.into());
+        self
+    }
+
+    /// Check the hashes passed to the api before sending them, disabled by default.
+    ///
+    /// Transaction and datum hashes must be 64 hex characters, policy IDs and script hashes 56.
+    /// Malformed ones fail with [`BlockfrostError::InvalidInput`] without using a request,
+    /// instead of a `400` or `404` response.
+    pub fn set_validate_inputs(&mut self, validate_inputs: bool) -> &mut Self {
+        self.validate_inputs = validate_inputs;
+        self
+    }
+
+    /// Connect to `address` for every request to `host` instead of resolving it with DNS, for
+    /// split-horizon DNS or to pin the API to an IP. Can be called once per host.
+    ///
+    /// Affects all requests of the api, fallback urls included. The port of `address` is
+    /// ignored, requests use the port of the url (the default of its scheme if it has none).
+    /// TLS is still verified against `host`.
+    pub fn resolve(&mut self, host: impl Into<String>, address: SocketAddr) -> &mut Self {
+        self.resolve_overrides.push((host.into(), address));
+        self
+    }
+
+    /// Enable or disable the stderr warning printed when an error response has a status code
+    /// Blockfrost doesn't document, or the first `404` when the
+    /// [backend version](Self::set_api_version) is known. Turn it off when the application
+    /// handles its own logging.
+    ///
+    /// Only the warning is affected, such responses still fail with
+    /// [`BlockfrostError::Response`].
+    pub fn set_warn_unexpected_status(&mut self, warn_unexpected_status: bool) -> &mut Self {
+        self.warn_unexpected_status = warn_unexpected_status;
+        self
+    }
+
+    /// Enable or disable the stderr warning printed when `output`, `fees` or `block_vrf` of a
+    /// block has an unexpected value, read as `None` either way.
+    pub fn set_warn_lenient_fields(&mut self, warn_lenient_fields: bool) -> &mut Self {
+        self.warn_lenient_fields = warn_lenient_fields;
+        self
+    }
+
+    /// Enable or disable the project id check of [`validate`](Self::validate), turn it off for
+    /// self-hosted instances with their own keys.
+    pub fn set_validate_project_id(&mut self, validate_project_id: bool) -> &mut Self {
+        self.validate_project_id = validate_project_id;
+        self
+    }
+
+    /// Check that `project_id` is well formed and belongs to the network of the base url, and
+    /// that the [extra headers](Self::set_extra_headers) don't include `project_id` or
+    /// `User-Agent`.
+    ///
+    /// A key for the wrong network is otherwise only noticed when Blockfrost answers `403`.
+    /// Custom base urls that are not one of the Blockfrost networks, such as self-hosted
+    /// instances, are not checked. Called by [`BlockfrostAPI::try_new`].
+    ///
+    /// [`BlockfrostAPI::try_new`]: crate::BlockfrostAPI::try_new
+    pub fn validate(&self, project_id: &str) -> Result<(), BlockfrostError> {
+        if let Some(name) = ["project_id", USER_AGENT.as_str()]
+            .into_iter()
+            .find(|name| self.extra_headers.contains_key(*name))
+        {
+            return Err(BlockfrostError::Configuration {
+                message: format!("extra header '{}' is set by the api itself", name),
+            });
+        }
+        if !self.validate_project_id {
+            return Ok(());
+        }
+        let expected = match &self.base_url {
+            Some(base_url) => match Network::from_base_url(base_url) {
+                Some(network) => Some(network),
+                None => return Ok(()),
+            },
+            None => None,
+        };
+
+        let network =
+            Network::from_project_id(project_id).ok_or_else(|| BlockfrostError::Configuration {
+                message: "malformed project_id, expected a network prefix such as 'mainnet' \
+                          followed by letters and digits"
+                    .to_string(),
+            })?;
+
+        match expected {
+            Some(expected) if expected != network => Err(BlockfrostError::Configuration {
+                message: format!(
+                    "project_id is for {:?} but the base url is for {:?}",
+                    network, expected
+                ),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Give up on a request attempt after `timeout`, timeouts are retried like other errors.
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct IpfsSettings {
     pub retry_settings: RetrySettings,
+    /// Custom retry timing, replaces `retry_settings` when set.
+    pub backoff: Option<Box<dyn Backoff>>,
+    /// Print a warning to stderr for undocumented error status codes, and once for a `404`
+    /// when the backend version is known, enabled by default.
+    pub warn_unexpected_status: bool,
 }
 
 impl IpfsSettings {
@@ -29,8 +303,23 @@ impl IpfsSettings {
     pub fn new() -> Self {
         Self {
             retry_settings: RetrySettings::default(),
+            backoff: None,
+            warn_unexpected_status: true,
         }
     }
+
+    /// Use a custom [`Backoff`] to decide when and how often requests are retried.
+    pub fn set_backoff(&mut self, backoff: Box<dyn Backoff>) -> &mut Self {
+        self.backoff = Some(backoff);
+        self
+    }
+
+    /// Enable or disable the stderr warning for undocumented status codes, see
+    /// [`BlockFrostSettings::set_warn_unexpected_status`].
+    pub fn set_warn_unexpected_status(&mut self, warn_unexpected_status: bool) -> &mut Self {
+        self.warn_unexpected_status = warn_unexpected_status;
+        self
+    }
 }
 
 /// Uses the default network [`CARDANO_MAINNET_NETWORK`].
@@ -47,22 +336,109 @@ impl Default for IpfsSettings {
     }
 }
 
+/// How HTTP redirects are handled, maps to [`reqwest::redirect::Policy`].
+///
+/// Blockfrost never redirects, so [`None`](RedirectPolicy::None) is the default: an unexpected
+/// redirect (a misconfigured proxy in front of a self-hosted instance, say) fails with
+/// [`BlockfrostError::Response`](crate::BlockfrostError::Response) instead of being followed.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RedirectPolicy {
+    /// Don't follow redirects.
+    #[default]
+    None,
+    /// Follow up to this many redirects, then fail.
+    Limited(usize),
+}
+
+impl From<RedirectPolicy> for reqwest::redirect::Policy {
+    fn from(redirect_policy: RedirectPolicy) -> Self {
+        match redirect_policy {
+            RedirectPolicy::None => reqwest::redirect::Policy::none(),
+            // reqwest counts the original URL too
+            RedirectPolicy::Limited(max) => {
+                reqwest::redirect::Policy::limited(max.saturating_add(1))
+            }
+        }
+    }
+}
+
 /// Settings for retrying when API rate limit is reached.
 ///
 /// Amount and delay are set to zero by default, you will need to change both to enable retrying.
 ///
+/// Requests are retried on timeouts, connection errors and on the status codes 408, 413, 429,
+/// 500, 502, 503 and 504. A random jitter of up to `delay` is added to every wait, so concurrent
+/// requests that failed together don't all retry at the same instant.
+///
 /// Check different BlockFrost plans and their limits at <https://blockfrost.io/#pricing>.
 ///
+/// Only reads are retried by default: submitting a transaction again after a timeout could
+/// broadcast it twice, so [`transactions_submit`], [`utils_tx_evaluate`] and
+/// [`post`] are sent once unless [`retry_writes`](Self::retry_writes) is set, like the IPFS
+/// [`add`], [`pin_add`] and [`pin_remove`]. The same goes for a
+/// [`backoff`](BlockFrostSettings::backoff).
+///
 /// Note: You can disable delay between retries with [`Duration::ZERO`].
+///
+/// [`transactions_submit`]: crate::BlockfrostAPI::transactions_submit
+/// [`utils_tx_evaluate`]: crate::BlockfrostAPI::utils_tx_evaluate
+/// [`post`]: crate::BlockfrostAPI::post
+/// [`add`]: crate::BlockfrostIPFS::add
+/// [`pin_add`]: crate::BlockfrostIPFS::pin_add
+/// [`pin_remove`]: crate::BlockfrostIPFS::pin_remove
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub struct RetrySettings {
+    /// Maximum number of retries after the first attempt.
     pub amount: u64,
     pub delay: Duration,
+    /// Make retries wait for the [`rate limit`](BlockFrostSettings::rate_limit) like any other
+    /// request, instead of only waiting for `delay`.
+    pub respect_rate_limiter: bool,
+    /// Retry POST requests too, off by default. Only enable this if submitting the same
+    /// transaction twice is harmless for you, see
+    /// [`transactions_submit_idempotent`](crate::BlockfrostAPI::transactions_submit_idempotent).
+    pub retry_writes: bool,
 }
 
 impl RetrySettings {
     /// Create a new `RetrySettings`, with retry amount and delay.
     pub fn new(amount: u64, delay: Duration) -> Self {
-        Self { amount, delay }
+        Self {
+            amount,
+            delay,
+            respect_rate_limiter: false,
+            retry_writes: false,
+        }
+    }
+
+    /// Choose whether POST requests are retried, see [`retry_writes`](Self::retry_writes).
+    pub fn retry_writes(mut self, retry_writes: bool) -> Self {
+        self.retry_writes = retry_writes;
+        self
+    }
+}
+
+/// Token bucket limiting how many requests are sent, checked before every request.
+///
+/// Up to `burst` requests are sent right away, after that they are spaced out to
+/// `requests_per_second`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RateLimit {
+    pub requests_per_second: u32,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+        }
+    }
+
+    /// Limits enforced by the Blockfrost servers: 10 requests per second with bursts of 500.
+    pub fn blockfrost() -> Self {
+        Self::new(10, 500)
     }
 }
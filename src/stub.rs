@@ -0,0 +1,231 @@
+use crate::{
+    json_error, Account, AccountsApi, BlockfrostError, BlockfrostResult, BlocksApi, JsonValue,
+    Pagination, ResponseError, ToJson, TransactionsApi,
+};
+use blockfrost_openapi::models::{
+    account_addresses_content_inner::AccountAddressesContentInner,
+    account_delegation_content_inner::AccountDelegationContentInner,
+    account_history_content_inner::AccountHistoryContentInner,
+    account_reward_content_inner::AccountRewardContentInner, block_content::BlockContent,
+    tx_content::TxContent, tx_content_metadata_inner::TxContentMetadataInner,
+    tx_content_redeemers_inner::TxContentRedeemersInner, tx_content_utxo::TxContentUtxo,
+};
+use futures::{future::BoxFuture, FutureExt};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+
+/// Implementation of the [endpoint traits](crate::traits) answering from canned responses,
+/// for tests. Requires the `testing` feature.
+///
+/// Responses are set per endpoint path, the one [`BlockfrostAPI`](crate::BlockfrostAPI) would
+/// request (`/blocks/latest`, `/txs/{hash}/utxos`, ...). Paths without a response fail like a
+/// `404` from the server, so the default `StubApi` is a no-op implementation where nothing
+/// exists. Pagination is ignored, a listing returns its whole response. A response that fails to
+/// serialize makes its path fail with [`BlockfrostError::Parsing`].
+///
+/// ```
+/// # #[cfg(feature = "testing")]
+/// # async fn example() {
+/// # use blockfrost::{BlocksApi, StubApi};
+/// # use blockfrost_openapi::models::block_content::BlockContent;
+/// let api = StubApi::new().with(
+///     "/blocks/latest",
+///     BlockContent { height: Some(8929261), ..Default::default() },
+/// );
+///
+/// assert_eq!(api.tip().await.unwrap().height, Some(8929261));
+/// assert!(api.blocks_by_id("1").await.unwrap_err().is_not_found());
+/// # }
+/// ```
+#[derive(Default, Clone, Debug)]
+pub struct StubApi {
+    responses: HashMap<String, Result<JsonValue, String>>,
+}
+
+impl StubApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer `path` with `response`, replacing the previous response of that path.
+    pub fn with(mut self, path: &str, response: impl Serialize) -> Self {
+        let response = response.to_json().map_err(|error| error.to_string());
+
+        self.responses.insert(path.to_string(), response);
+        self
+    }
+
+    fn respond<T: DeserializeOwned + Send + 'static>(
+        &self, path: String,
+    ) -> BoxFuture<'static, BlockfrostResult<T>> {
+        let result = match self.responses.get(&path) {
+            Some(Ok(response)) => serde_json::from_value(response.clone())
+                .map_err(|error| json_error(&path, response, error)),
+            Some(Err(error)) => Err(BlockfrostError::Parsing {
+                message: format!("stub response of {}: {}", path, error),
+            }),
+            None => Err(BlockfrostError::Response {
+                reason: ResponseError::new(
+                    404,
+                    "Not Found",
+                    "The requested component has not been found.",
+                ),
+                url: path,
+            }),
+        };
+        futures::future::ready(result).boxed()
+    }
+}
+
+impl BlocksApi for StubApi {
+    fn blocks_latest(&self) -> BoxFuture<'_, BlockfrostResult<BlockContent>> {
+        self.respond("/blocks/latest".to_string())
+    }
+
+    fn blocks_by_id<'a>(
+        &'a self, hash_or_number: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<BlockContent>> {
+        self.respond(format!("/blocks/{}", hash_or_number))
+    }
+
+    fn blocks_slot(&self, slot_number: i64) -> BoxFuture<'_, BlockfrostResult<BlockContent>> {
+        self.respond(format!("/blocks/slot/{}", slot_number))
+    }
+
+    fn blocks_next<'a>(
+        &'a self, hash_or_number: &'a str, _pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<BlockContent>>> {
+        self.respond(format!("/blocks/{}/next", hash_or_number))
+    }
+
+    fn blocks_previous<'a>(
+        &'a self, hash_or_number: &'a str, _pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<BlockContent>>> {
+        self.respond(format!("/blocks/{}/previous", hash_or_number))
+    }
+
+    fn blocks_txs<'a>(
+        &'a self, hash_or_number: &'a str, _pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<String>>> {
+        self.respond(format!("/blocks/{}/txs", hash_or_number))
+    }
+}
+
+impl TransactionsApi for StubApi {
+    fn transaction_by_hash<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<TxContent>> {
+        self.respond(format!("/txs/{}", hash))
+    }
+
+    fn transactions_utxos<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<TxContentUtxo>> {
+        self.respond(format!("/txs/{}/utxos", hash))
+    }
+
+    fn transactions_metadata<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<TxContentMetadataInner>>> {
+        self.respond(format!("/txs/{}/metadata", hash))
+    }
+
+    fn transactions_redeemers<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<TxContentRedeemersInner>>> {
+        self.respond(format!("/txs/{}/redeemers", hash))
+    }
+
+    /// Answers with the response of `/tx/submit`, the transaction is not looked at.
+    fn transactions_submit(&self, _: Vec<u8>) -> BoxFuture<'_, BlockfrostResult<String>> {
+        self.respond("/tx/submit".to_string())
+    }
+}
+
+impl AccountsApi for StubApi {
+    fn accounts_by_stake_address<'a>(
+        &'a self, stake_address: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Account>> {
+        self.respond(format!("/accounts/{}", stake_address))
+    }
+
+    fn accounts_rewards<'a>(
+        &'a self, stake_address: &'a str, _pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountRewardContentInner>>> {
+        self.respond(format!("/accounts/{}/rewards", stake_address))
+    }
+
+    fn accounts_history<'a>(
+        &'a self, stake_address: &'a str, _pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountHistoryContentInner>>> {
+        self.respond(format!("/accounts/{}/history", stake_address))
+    }
+
+    fn accounts_delegations<'a>(
+        &'a self, stake_address: &'a str, _pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountDelegationContentInner>>> {
+        self.respond(format!("/accounts/{}/delegations", stake_address))
+    }
+
+    fn accounts_addresses<'a>(
+        &'a self, stake_address: &'a str, _pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountAddressesContentInner>>> {
+        self.respond(format!("/accounts/{}/addresses", stake_address))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const STAKE_ADDRESS: &str = "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7";
+
+    #[tokio::test]
+    async fn test_stub_api() {
+        let api = StubApi::new()
+            .with(
+                "/blocks/8929261/txs",
+                ["8788591983aa73981fc92d6cddbbe643959f5a784e84b8bee0db15823f575a5b"],
+            )
+            .with(
+                &format!("/accounts/{}", STAKE_ADDRESS),
+                json!({
+                    "stake_address": STAKE_ADDRESS,
+                    "active": true,
+                    "active_epoch": 412,
+                    "controlled_amount": "619154618165",
+                    "rewards_sum": "0",
+                    "withdrawals_sum": "0",
+                    "reserves_sum": "0",
+                    "treasury_sum": "0",
+                    "withdrawable_amount": "0",
+                    "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy"
+                }),
+            )
+            .with("/txs/00/utxos", json!({ "hash": 42 }))
+            .with("/blocks/slot/1", HashMap::from([((0, 0), 0)]));
+
+        let txs = api
+            .blocks_txs("8929261", Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(txs.len(), 1);
+        assert_eq!(
+            api.accounts_current_pool(STAKE_ADDRESS)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy")
+        );
+        assert!(api.blocks_latest().await.unwrap_err().is_not_found());
+        assert!(matches!(
+            api.transactions_utxos("00").await,
+            Err(BlockfrostError::Json { url, .. }) if url == "/txs/00/utxos"
+        ));
+        assert!(matches!(
+            api.blocks_slot(1).await,
+            Err(BlockfrostError::Parsing { .. })
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+//! Traits over groups of endpoints, to code against instead of [`BlockfrostAPI`].
+//!
+//! Business logic taking an `impl BlocksApi` (or a `&dyn BlocksApi`) can be tested with a fake
+//! implementation instead of a server:
+//!
+//! ```no_run
+//! use blockfrost::{BlocksApi, BlockfrostResult};
+//!
+//! async fn blocks_since(api: &impl BlocksApi, height: i32) -> BlockfrostResult<i32> {
+//!     let tip = api.tip().await?;
+//!
+//!     Ok(tip.height.unwrap_or_default() - height)
+//! }
+//! ```
+//!
+//! [`BlockfrostAPI`] implements every trait by calling its method of the same name. The
+//! `testing` feature adds `StubApi`, answering from canned JSON. Only the main endpoints of each
+//! group are part of the traits, the other methods stay on the concrete type.
+use crate::{Account, BlockfrostAPI, BlockfrostResult, Pagination, Tip};
+use blockfrost_openapi::models::{
+    account_addresses_content_inner::AccountAddressesContentInner,
+    account_delegation_content_inner::AccountDelegationContentInner,
+    account_history_content_inner::AccountHistoryContentInner,
+    account_reward_content_inner::AccountRewardContentInner, block_content::BlockContent,
+    tx_content::TxContent, tx_content_metadata_inner::TxContentMetadataInner,
+    tx_content_redeemers_inner::TxContentRedeemersInner, tx_content_utxo::TxContentUtxo,
+};
+use futures::{future::BoxFuture, FutureExt};
+
+/// Block endpoints, see the [module documentation](crate::traits).
+pub trait BlocksApi: Send + Sync {
+    fn blocks_latest(&self) -> BoxFuture<'_, BlockfrostResult<BlockContent>>;
+
+    fn blocks_by_id<'a>(
+        &'a self, hash_or_number: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<BlockContent>>;
+
+    fn blocks_slot(&self, slot_number: i64) -> BoxFuture<'_, BlockfrostResult<BlockContent>>;
+
+    fn blocks_next<'a>(
+        &'a self, hash_or_number: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<BlockContent>>>;
+
+    fn blocks_previous<'a>(
+        &'a self, hash_or_number: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<BlockContent>>>;
+
+    fn blocks_txs<'a>(
+        &'a self, hash_or_number: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<String>>>;
+
+    /// The latest block as a [`Tip`], from [`blocks_latest`](Self::blocks_latest).
+    fn tip(&self) -> BoxFuture<'_, BlockfrostResult<Tip>> {
+        self.blocks_latest()
+            .map(|block| block.map(Tip::from))
+            .boxed()
+    }
+}
+
+/// Transaction endpoints, see the [module documentation](crate::traits).
+pub trait TransactionsApi: Send + Sync {
+    fn transaction_by_hash<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<TxContent>>;
+
+    fn transactions_utxos<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<TxContentUtxo>>;
+
+    fn transactions_metadata<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<TxContentMetadataInner>>>;
+
+    fn transactions_redeemers<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<TxContentRedeemersInner>>>;
+
+    fn transactions_submit(
+        &self, transaction_data: Vec<u8>,
+    ) -> BoxFuture<'_, BlockfrostResult<String>>;
+}
+
+/// Account endpoints, see the [module documentation](crate::traits).
+pub trait AccountsApi: Send + Sync {
+    fn accounts_by_stake_address<'a>(
+        &'a self, stake_address: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Account>>;
+
+    fn accounts_rewards<'a>(
+        &'a self, stake_address: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountRewardContentInner>>>;
+
+    fn accounts_history<'a>(
+        &'a self, stake_address: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountHistoryContentInner>>>;
+
+    fn accounts_delegations<'a>(
+        &'a self, stake_address: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountDelegationContentInner>>>;
+
+    fn accounts_addresses<'a>(
+        &'a self, stake_address: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountAddressesContentInner>>>;
+
+    /// Pool the account currently delegates to, like
+    /// [`BlockfrostAPI::accounts_current_pool`].
+    fn accounts_current_pool<'a>(
+        &'a self, stake_address: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Option<String>>> {
+        self.accounts_by_stake_address(stake_address)
+            .map(|account| account.map(|account| account.pool_id.filter(|_| account.active)))
+            .boxed()
+    }
+}
+
+// The inherent methods take precedence, these calls don't recurse
+impl BlocksApi for BlockfrostAPI {
+    fn blocks_latest(&self) -> BoxFuture<'_, BlockfrostResult<BlockContent>> {
+        self.blocks_latest().boxed()
+    }
+
+    fn blocks_by_id<'a>(
+        &'a self, hash_or_number: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<BlockContent>> {
+        self.blocks_by_id(hash_or_number).boxed()
+    }
+
+    fn blocks_slot(&self, slot_number: i64) -> BoxFuture<'_, BlockfrostResult<BlockContent>> {
+        self.blocks_slot(slot_number).boxed()
+    }
+
+    fn blocks_next<'a>(
+        &'a self, hash_or_number: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<BlockContent>>> {
+        self.blocks_next(hash_or_number, pagination).boxed()
+    }
+
+    fn blocks_previous<'a>(
+        &'a self, hash_or_number: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<BlockContent>>> {
+        self.blocks_previous(hash_or_number, pagination).boxed()
+    }
+
+    fn blocks_txs<'a>(
+        &'a self, hash_or_number: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<String>>> {
+        self.blocks_txs(hash_or_number, pagination).boxed()
+    }
+
+    fn tip(&self) -> BoxFuture<'_, BlockfrostResult<Tip>> {
+        self.tip().boxed()
+    }
+}
+
+impl TransactionsApi for BlockfrostAPI {
+    fn transaction_by_hash<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<TxContent>> {
+        self.transaction_by_hash(hash).boxed()
+    }
+
+    fn transactions_utxos<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<TxContentUtxo>> {
+        self.transactions_utxos(hash).boxed()
+    }
+
+    fn transactions_metadata<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<TxContentMetadataInner>>> {
+        self.transactions_metadata(hash).boxed()
+    }
+
+    fn transactions_redeemers<'a>(
+        &'a self, hash: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<TxContentRedeemersInner>>> {
+        self.transactions_redeemers(hash).boxed()
+    }
+
+    fn transactions_submit(
+        &self, transaction_data: Vec<u8>,
+    ) -> BoxFuture<'_, BlockfrostResult<String>> {
+        self.transactions_submit(transaction_data).boxed()
+    }
+}
+
+impl AccountsApi for BlockfrostAPI {
+    fn accounts_by_stake_address<'a>(
+        &'a self, stake_address: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Account>> {
+        self.accounts_by_stake_address(stake_address).boxed()
+    }
+
+    fn accounts_rewards<'a>(
+        &'a self, stake_address: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountRewardContentInner>>> {
+        self.accounts_rewards(stake_address, pagination).boxed()
+    }
+
+    fn accounts_history<'a>(
+        &'a self, stake_address: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountHistoryContentInner>>> {
+        self.accounts_history(stake_address, pagination).boxed()
+    }
+
+    fn accounts_delegations<'a>(
+        &'a self, stake_address: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountDelegationContentInner>>> {
+        self.accounts_delegations(stake_address, pagination).boxed()
+    }
+
+    fn accounts_addresses<'a>(
+        &'a self, stake_address: &'a str, pagination: Pagination,
+    ) -> BoxFuture<'a, BlockfrostResult<Vec<AccountAddressesContentInner>>> {
+        self.accounts_addresses(stake_address, pagination).boxed()
+    }
+
+    fn accounts_current_pool<'a>(
+        &'a self, stake_address: &'a str,
+    ) -> BoxFuture<'a, BlockfrostResult<Option<String>>> {
+        self.accounts_current_pool(stake_address).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+    use serde_json::json;
+
+    async fn latest_height(api: &dyn BlocksApi) -> BlockfrostResult<Option<i32>> {
+        Ok(api.tip().await?.height)
+    }
+
+    #[tokio::test]
+    async fn test_blockfrost_api_implements_traits() {
+        let block = BlockContent {
+            hash: "4ea1ba291e8eef538635a53e59fddba7810d1679631cc3aed7c8e6c4091a516a".to_string(),
+            height: Some(8929261),
+            ..Default::default()
+        };
+        let server = MockServer::start(vec![MockResponse::new(200, json!(block).to_string())]);
+        let api = server.api();
+
+        assert_eq!(latest_height(&api).await.unwrap(), Some(8929261));
+        assert_eq!(
+            BlocksApi::blocks_by_id(&api, "8929261").await.unwrap(),
+            block
+        );
+        assert_eq!(
+            server.requests()[1].split_whitespace().nth(1),
+            Some("/blocks/8929261")
+        );
+    }
+}
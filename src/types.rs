@@ -1,7 +1,41 @@
 // Use this module as an interface to export all types declared inside of endpoints/
 //
 // These are not used in here, just exporting
+pub use crate::api::endpoints::{
+    accounts::{
+        Account, AccountEpochSummary, AccountEvent, AccountFullHistory, AccountMir,
+        AccountWithdrawal,
+    },
+    addresses::{
+        balance_diff, AddressContentTotalExt, AmountExt, AmountsExt, Balance, BalanceError,
+    },
+    assets::{format_asset_amount, AssetExt},
+    blocks::{BlockContentExt, ChainEvent, SlotLeader, Tip},
+    epochs::{EpochContentExt, EpochParamContentExt, EpochStake, Era, ProtocolVersion},
+    health::{HealthStatus, SmokeCheck, SmokeReport, SmokeTestIds},
+    pools::{PoolUpdate, PoolUpdateAction},
+    scripts::{NativeScript, ReferenceScriptExt, ScriptInfo, ScriptType},
+    transactions::{
+        Cip25Asset, Cip25Metadata, Datum, DatumExt, TxContentExt, TxContentUtxoExt, TxMetadataJson,
+    },
+    utils::{EvaluationResult, ExUnits},
+};
+#[cfg(feature = "decimal")]
+pub use crate::api::endpoints::{epochs::EpochParamRates, ledger::GenesisRates, pools::PoolRates};
 pub use crate::ipfs::{IpfsAdd, IpfsPinList, IpfsPinState, IpfsPinUpdate};
+use crate::{BlockfrostError, BlockfrostResult};
+/// Kind of a reward: as a pool member, as the pool leader, or a pool deposit refund.
+pub use blockfrost_openapi::models::account_reward_content_inner::RHashType as RewardType;
+/// Era of an address, Byron (legacy) or Shelley.
+pub use blockfrost_openapi::models::address_content::RHashType as AddressType;
+use blockfrost_openapi::models::{
+    address_utxo_content_inner::AddressUtxoContentInner,
+    tx_content_output_amount_inner::TxContentOutputAmountInner,
+    tx_content_utxo_inputs_inner::TxContentUtxoInputsInner,
+    tx_content_utxo_outputs_inner::TxContentUtxoOutputsInner,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
 
 /// Enum for any possible JSON value.
 ///
@@ -29,11 +63,667 @@ pub type JsonValue = serde_json::Value;
 pub type Integer = i128;
 
 /// Float used in other types.
+///
+/// Rates and ratios of the `blockfrost-openapi` models are parsed as `f32` by those models, not
+/// through this alias, so they are not exact decimals:
+///
+/// - `active_slots_coefficient` of `GenesisContent`.
+/// - `live_saturation` and `margin_cost` of `Pool`.
+/// - `a0`, `rho`, `tau`, `decentralisation_param`, `price_mem` and `price_step` of
+///   `EpochParamContent`.
+///
+/// The `decimal` feature reads all of them exactly as a `Decimal`, with
+/// `genesis_rates`, `pools_rates` and `epochs_parameters_rates`.
 pub type Float = f64;
 
+/// Amount of Lovelace, 1 ADA is 1 000 000 Lovelace.
+///
+/// The API sends amounts as strings ("42000000"), this type serializes back to that same string
+/// form, and also accepts plain JSON numbers when deserializing.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lovelace(pub u64);
+
+impl fmt::Display for Lovelace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Lovelace {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Lovelace)
+    }
+}
+
+impl From<u64> for Lovelace {
+    fn from(amount: u64) -> Self {
+        Lovelace(amount)
+    }
+}
+
+// Amount sent by the API as a string, failing with a `Parsing` error
+pub(crate) fn parse_lovelace(amount: &str) -> BlockfrostResult<Lovelace> {
+    amount.parse().map_err(|reason| BlockfrostError::Parsing {
+        message: format!("invalid Lovelace amount '{}': {}", amount, reason),
+    })
+}
+
+impl Serialize for Lovelace {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Lovelace {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        string_u64::deserialize(deserializer).map(Lovelace)
+    }
+}
+
+// Integer sent by the API as a string, use with `#[serde(with = "crate::types::string_u64")]`.
+//
+// Serializes back to a string, and also accepts plain JSON numbers when deserializing.
+pub(crate) mod string_u64 {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr<'a> {
+            Number(u64),
+            String(std::borrow::Cow<'a, str>),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(value) => Ok(value),
+            Repr::String(value) => value.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+/// Exact decimal number, for the rates and ratios the `blockfrost-openapi` models parse as `f32`.
+/// Requires the `decimal` feature.
+///
+/// It is parsed from the text of the JSON number (or of a string holding one), never through a
+/// float, and normalized without trailing zeros: `0.050` equals `0.05`. The endpoints returning
+/// it are [`genesis_rates`](crate::BlockfrostAPI::genesis_rates),
+/// [`pools_rates`](crate::BlockfrostAPI::pools_rates) and
+/// [`epochs_parameters_rates`](crate::BlockfrostAPI::epochs_parameters_rates).
+#[cfg(feature = "decimal")]
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+#[cfg(feature = "decimal")]
+impl Decimal {
+    /// `mantissa / 10^scale`, `None` when `scale` is above 38, the most digits an `i128` holds.
+    pub fn new(mantissa: i128, scale: u32) -> Option<Self> {
+        if scale > 38 {
+            return None;
+        }
+        let (mut mantissa, mut scale) = (mantissa, scale);
+
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+
+        Some(Decimal { mantissa, scale })
+    }
+
+    /// Digits of the number without the decimal point, the value is `mantissa / 10^scale`.
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    /// Number of digits after the decimal point.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// The exact value as a reduced `(numerator, denominator)` fraction.
+    pub fn to_fraction(&self) -> (i128, u128) {
+        let numerator = self.mantissa;
+        let denominator = 10u128.pow(self.scale);
+        let (mut a, mut b) = (numerator.unsigned_abs(), denominator);
+
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        let gcd = a.max(1);
+
+        (numerator / gcd as i128, denominator / gcd)
+    }
+
+    /// Nearest `f64`, for display or approximate math.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl FromStr for Decimal {
+    type Err = BlockfrostError;
+
+    /// Parses the JSON number syntax, exponents included (`-1.5`, `0.003`, `3e-3`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BlockfrostError::Parsing {
+            message: format!("invalid decimal '{}'", s),
+        };
+        let (number, exponent) = match s.find(['e', 'E']) {
+            Some(index) => (
+                &s[..index],
+                s[index + 1..].parse::<i32>().map_err(|_| invalid())?,
+            ),
+            None => (s, 0),
+        };
+        let (negative, number) = match number.strip_prefix('-') {
+            Some(number) => (true, number),
+            None => (false, number),
+        };
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        if integer.is_empty() || (number.contains('.') && fraction.is_empty()) {
+            return Err(invalid());
+        }
+
+        let mut mantissa: i128 = 0;
+        for digit in integer.bytes().chain(fraction.bytes()) {
+            if !digit.is_ascii_digit() {
+                return Err(invalid());
+            }
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|mantissa| mantissa.checked_add((digit - b'0') as i128))
+                .ok_or_else(invalid)?;
+        }
+        if negative {
+            mantissa = -mantissa;
+        }
+
+        let scale = fraction.len() as i64 - exponent as i64;
+        if scale < 0 {
+            let factor = u32::try_from(-scale)
+                .ok()
+                .and_then(|exponent| 10i128.checked_pow(exponent))
+                .ok_or_else(invalid)?;
+            mantissa = mantissa.checked_mul(factor).ok_or_else(invalid)?;
+        }
+
+        u32::try_from(scale.max(0))
+            .ok()
+            .and_then(|scale| Decimal::new(mantissa, scale))
+            .ok_or_else(invalid)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // The raw text of the number, serde_json would otherwise go through an f64
+        let raw = Box::<serde_json::value::RawValue>::deserialize(deserializer)?;
+        let text = match raw.get() {
+            text if text.starts_with('"') => {
+                serde_json::from_str::<String>(text).map_err(serde::de::Error::custom)?
+            }
+            text => text.to_string(),
+        };
+
+        text.trim().parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Transaction output, the shape shared by the inputs and outputs of
+/// [`transactions_utxos`](crate::BlockfrostAPI::transactions_utxos), the UTxOs of
+/// [`addresses_utxos`](crate::BlockfrostAPI::addresses_utxos) and the outputs of
+/// [`mempool_hash`](crate::BlockfrostAPI::mempool_hash).
+///
+/// Each endpoint still returns its own model, they all convert into this type so code handling
+/// outputs is written once, and the `*_outputs` variants such as
+/// [`addresses_utxos_outputs`](crate::BlockfrostAPI::addresses_utxos_outputs) return it
+/// directly. It also deserializes from the payload of any of them, skipping the fields that only
+/// one endpoint has (the `tx_hash` and `block` of address UTxOs, ...). For that reason the
+/// `strict` feature doesn't apply to it.
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+    pub address: String,
+    pub amount: Vec<TxContentOutputAmountInner>,
+    pub data_hash: Option<String>,
+    pub inline_datum: Option<String>,
+    /// Whether this is a collateral, always `false` for address UTxOs which don't send it.
+    #[serde(default)]
+    pub collateral: bool,
+    pub reference_script_hash: Option<String>,
+}
+
+impl From<TxContentUtxoOutputsInner> for Output {
+    fn from(output: TxContentUtxoOutputsInner) -> Self {
+        Self {
+            address: output.address,
+            amount: output.amount,
+            data_hash: output.data_hash,
+            inline_datum: output.inline_datum,
+            collateral: output.collateral,
+            reference_script_hash: output.reference_script_hash,
+        }
+    }
+}
+
+impl From<TxContentUtxoInputsInner> for Output {
+    fn from(input: TxContentUtxoInputsInner) -> Self {
+        Self {
+            address: input.address,
+            amount: input.amount,
+            data_hash: input.data_hash,
+            inline_datum: input.inline_datum,
+            collateral: input.collateral,
+            reference_script_hash: input.reference_script_hash,
+        }
+    }
+}
+
+impl From<AddressUtxoContentInner> for Output {
+    fn from(utxo: AddressUtxoContentInner) -> Self {
+        Self {
+            address: utxo.address,
+            amount: utxo.amount,
+            data_hash: utxo.data_hash,
+            inline_datum: utxo.inline_datum,
+            collateral: false,
+            reference_script_hash: utxo.reference_script_hash,
+        }
+    }
+}
+
+/// Conversion of a response back into JSON, to forward it from a web service for instance.
+///
+/// Implemented for every `Serialize` type, so it is not part of the [`prelude`](crate::prelude),
+/// import it where it is needed. The crate's types and the `blockfrost-openapi` models serialize
+/// to the API's schema: amounts like [`Lovelace`] stay strings and no field is renamed, so the
+/// result matches the payload they were parsed from.
+///
+/// [`Integer`] fields outside the `i64` and `u64` range become floats and lose precision, unless
+/// the `arbitrary_precision` feature of `serde_json` is enabled.
+pub trait ToJson {
+    /// JSON value of `self`, the error of its `Serialize` implementation if it fails.
+    fn to_json(&self) -> Result<JsonValue, serde_json::Error>;
+}
+
+impl<T: Serialize + ?Sized> ToJson for T {
+    fn to_json(&self) -> Result<JsonValue, serde_json::Error> {
+        // `to_value` rejects `i128`, types with `Integer` fields go through the text form
+        serde_json::to_value(self)
+            .or_else(|_| serde_json::to_vec(self).and_then(|bytes| serde_json::from_slice(&bytes)))
+    }
+}
+
 /// JSON Map (or JSON object) made of key-value pairs.
 ///
 /// Used in types:
 /// [`EpochParameters`]
 /// [`AssetDetails`]
 pub type JsonMap = serde_json::Map<String, JsonValue>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockfrost_openapi::models::{
+        account_content::AccountContent, address_utxo_content_inner::AddressUtxoContentInner,
+        block_content::BlockContent, epoch_content::EpochContent, tx_content::TxContent,
+    };
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::json;
+
+    // Deserializing and serializing back must reproduce the API payload, so responses can be
+    // cached or forwarded as-is.
+    fn assert_round_trip<T: Serialize + DeserializeOwned>(json_value: JsonValue) {
+        let text = serde_json::to_string(&json_value).unwrap();
+        let parsed = serde_json::from_str::<T>(&text).unwrap();
+        let serialized = serde_json::to_string(&parsed).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<JsonValue>(&serialized).unwrap(),
+            json_value
+        );
+    }
+
+    #[test]
+    fn test_lovelace_as_key_and_sorted() {
+        use std::collections::{BTreeSet, HashMap};
+
+        let mut outputs_by_amount: HashMap<Lovelace, usize> = HashMap::new();
+        for amount in [Lovelace(42000000), Lovelace(1000000), Lovelace(42000000)] {
+            *outputs_by_amount.entry(amount).or_default() += 1;
+        }
+        assert_eq!(outputs_by_amount[&Lovelace(42000000)], 2);
+
+        let mut amounts = vec![Lovelace(42000000), Lovelace(0), Lovelace(1000000)];
+        amounts.sort();
+        assert_eq!(
+            amounts,
+            [Lovelace(0), Lovelace(1000000), Lovelace(42000000)]
+        );
+        assert_eq!(amounts.iter().max(), Some(&Lovelace(42000000)));
+
+        let unique: BTreeSet<_> = amounts.into_iter().chain([Lovelace(0)]).collect();
+        assert_eq!(unique.len(), 3);
+    }
+
+    #[test]
+    fn test_output_from_each_endpoint() {
+        let amount = json!([
+            { "unit": "lovelace", "quantity": "42000000" },
+            {
+                "unit": "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e",
+                "quantity": "12"
+            }
+        ]);
+        let address = "addr1qxqs59lphg8g6qndelq8xwqn60ag3aeyfcp33c2kdp46a09re5df3pzwwmyq946axfcejy5n4x0y99wqpgtp2gd0k09qsgy6pz";
+        let data_hash = "9e478573ab81ea7a8e31891ce0648b81229f408d596a3483e6f4f9b92d3cf710";
+        let expected = Output {
+            address: address.to_string(),
+            amount: serde_json::from_value(amount.clone()).unwrap(),
+            data_hash: Some(data_hash.to_string()),
+            inline_datum: None,
+            collateral: false,
+            reference_script_hash: None,
+        };
+
+        // `transactions_utxos` and `mempool_hash` outputs
+        let tx_output = json!({
+            "address": address,
+            "amount": amount,
+            "output_index": 0,
+            "data_hash": data_hash,
+            "inline_datum": null,
+            "collateral": false,
+            "reference_script_hash": null,
+            "consumed_by_tx": null
+        });
+        // `transactions_utxos` inputs
+        let tx_input = json!({
+            "address": address,
+            "amount": amount,
+            "tx_hash": "1a0570af966fb355a7160e4f82d5a80b8681b7955f5d44bec0dce628516157f0",
+            "output_index": 0,
+            "data_hash": data_hash,
+            "inline_datum": null,
+            "reference_script_hash": null,
+            "collateral": false,
+            "reference": false
+        });
+        // `addresses_utxos`, without `collateral`
+        let address_utxo = json!({
+            "address": address,
+            "tx_hash": "39a7a284c2a0948189dc45dec670211cd4d72f7b66c5726c08d9b3df11e44d58",
+            "tx_index": 0,
+            "output_index": 0,
+            "amount": amount,
+            "block": "7eb8e27d18686c7db9a18f8bbcfe34e3fed6e047afaa2d969904d15e934847e6",
+            "data_hash": data_hash,
+            "inline_datum": null,
+            "reference_script_hash": null
+        });
+
+        for json_value in [&tx_output, &tx_input, &address_utxo] {
+            assert_eq!(
+                serde_json::from_value::<Output>(json_value.clone()).unwrap(),
+                expected
+            );
+        }
+        assert_eq!(
+            Output::from(serde_json::from_value::<TxContentUtxoOutputsInner>(tx_output).unwrap()),
+            expected
+        );
+        assert_eq!(
+            Output::from(serde_json::from_value::<TxContentUtxoInputsInner>(tx_input).unwrap()),
+            expected
+        );
+        assert_eq!(
+            Output::from(serde_json::from_value::<AddressUtxoContentInner>(address_utxo).unwrap()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        assert_round_trip::<BlockContent>(json!({
+            "time": 1641338934,
+            "height": 15243593,
+            "hash": "4ea1ba291e8eef538635a53e59fddba7810d1679631cc3aed7c8e6c4091a516a",
+            "slot": 412162133,
+            "epoch": 425,
+            "epoch_slot": 12,
+            "slot_leader": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2qnikdy",
+            "size": 3,
+            "tx_count": 1,
+            "output": "128314491794",
+            "fees": "592661",
+            "block_vrf": "vrf_vk1wf2k6lhujezqcfe00l6zetxpnmh9n6mwhpmhm0dvfh3fxgmdnrfqkms8ty",
+            "op_cert": null,
+            "op_cert_counter": "1",
+            "previous_block": "43ebccb3ac72c7cebd0d9b755a4b08412c9f5dcb81b8a0ad1e3c197d29d47b05",
+            "next_block": "8367f026cf4b03e116ff8ee5daf149b55ba5a6ec6dec04803b8dc317721d15fa",
+            "confirmations": 4698
+        }));
+    }
+
+    #[test]
+    fn test_transaction_round_trip() {
+        assert_round_trip::<TxContent>(json!({
+            "hash": "1e043f100dce12d107f679685acd2fc0610e10f72a92d412794c9773d11d8477",
+            "block": "356b7d7dbb696ccd12775c016941057a9dc70898d87a63fc752271bb46856940",
+            "block_height": 123456,
+            "block_time": 1635505891,
+            "slot": 42000000,
+            "index": 1,
+            "output_amount": [
+                { "unit": "lovelace", "quantity": "42000000" },
+                {
+                    "unit": "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e",
+                    "quantity": "12"
+                }
+            ],
+            "fees": "182485",
+            "deposit": "0",
+            "size": 433,
+            "invalid_before": null,
+            "invalid_hereafter": "13885913",
+            "utxo_count": 4,
+            "withdrawal_count": 0,
+            "mir_cert_count": 0,
+            "delegation_count": 0,
+            "stake_cert_count": 0,
+            "pool_update_count": 0,
+            "pool_retire_count": 0,
+            "asset_mint_or_burn_count": 0,
+            "redeemer_count": 0,
+            "valid_contract": true
+        }));
+    }
+
+    #[test]
+    fn test_account_round_trip() {
+        assert_round_trip::<AccountContent>(json!({
+            "stake_address": "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7",
+            "active": true,
+            "active_epoch": 412,
+            "controlled_amount": "619154618165",
+            "rewards_sum": "319154618165",
+            "withdrawals_sum": "12125369253",
+            "reserves_sum": "319154618165",
+            "treasury_sum": "12000000",
+            "withdrawable_amount": "319154618165",
+            "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy"
+        }));
+    }
+
+    #[test]
+    fn test_address_utxo_round_trip() {
+        assert_round_trip::<Vec<AddressUtxoContentInner>>(json!([{
+            "address": "addr1qxqs59lphg8g6qndelq8xwqn60ag3aeyfcp33c2kdp46a09re5df3pzwwmyq946axfcejy5n4x0y99wqpgtp2gd0k09qsgy6pz",
+            "tx_hash": "39a7a284c2a0948189dc45dec670211cd4d72f7b66c5726c08d9b3df11e44d58",
+            "tx_index": 0,
+            "output_index": 0,
+            "amount": [{ "unit": "lovelace", "quantity": "42000000" }],
+            "block": "7eb8e27d18686c7db9a18f8bbcfe34e3fed6e047afaa2d969904d15e934847e6",
+            "data_hash": null,
+            "inline_datum": null,
+            "reference_script_hash": null
+        }]));
+    }
+
+    #[test]
+    fn test_epoch_round_trip() {
+        assert_round_trip::<EpochContent>(json!({
+            "epoch": 225,
+            "start_time": 1603403091,
+            "end_time": 1603835086,
+            "first_block_time": 1603403092,
+            "last_block_time": 1603835084,
+            "block_count": 21298,
+            "tx_count": 17856,
+            "output": "7849943934049314",
+            "fees": "4203312194",
+            "active_stake": "784953934049314"
+        }));
+    }
+
+    #[test]
+    fn test_lovelace() {
+        let lovelace = serde_json::from_value::<Lovelace>(json!("42000000")).unwrap();
+
+        assert_eq!(lovelace, Lovelace(42000000));
+        assert_eq!(
+            serde_json::from_value::<Lovelace>(json!(42)).unwrap(),
+            Lovelace(42)
+        );
+        assert_eq!(serde_json::to_value(lovelace).unwrap(), json!("42000000"));
+        assert!(serde_json::from_value::<Lovelace>(json!("-1")).is_err());
+        assert!(serde_json::from_value::<Lovelace>(json!("1.5")).is_err());
+    }
+
+    #[test]
+    fn test_to_json_keeps_amounts_as_strings() {
+        assert_eq!(Lovelace(42000000).to_json().unwrap(), json!("42000000"));
+        assert_eq!(
+            [Lovelace(0), Lovelace(u64::MAX)].to_json().unwrap(),
+            json!(["0", "18446744073709551615"])
+        );
+
+        let payload = json!({
+            "stake_address": "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7",
+            "active": true,
+            "active_epoch": 412,
+            "controlled_amount": "619154618165",
+            "rewards_sum": "319154618165",
+            "withdrawals_sum": "12125369253",
+            "reserves_sum": "319154618165",
+            "treasury_sum": "12000000",
+            "withdrawable_amount": "319154618165",
+            "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy"
+        });
+        let account = serde_json::from_value::<Account>(payload.clone()).unwrap();
+        assert_eq!(account.to_json().unwrap(), payload);
+
+        let output = Output {
+            address: "addr1qxqs59lphg8g6qndelq8xwqn60ag3aeyfcp33c2kdp46a09re5df3pzwwmyq946axfcejy5n4x0y99wqpgtp2gd0k09qsgy6pz".to_string(),
+            amount: vec![TxContentOutputAmountInner {
+                unit: "lovelace".to_string(),
+                quantity: "42000000".to_string(),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            output.to_json().unwrap()["amount"],
+            json!([{ "unit": "lovelace", "quantity": "42000000" }])
+        );
+
+        // JSON keys are strings, a map keyed by tuples can't be converted
+        let tuple_keys = std::collections::HashMap::from([((0, 0), 0)]);
+        assert!(tuple_keys.to_json().is_err());
+    }
+
+    #[test]
+    fn test_ipfs_round_trip() {
+        assert_round_trip::<IpfsAdd>(json!({
+            "name": "README.md",
+            "ipfs_hash": "QmZbHqiCxKEVX7QfijzJTkZiSi3WEVTcvANgNAWzDYgZDr",
+            "size": "125297"
+        }));
+        assert_round_trip::<IpfsPinList>(json!({
+            "time_created": 1615551024,
+            "time_pinned": 1615551024,
+            "ipfs_hash": "QmdVMnULrY95mth2XkwjxDtMHvzuzmvUPTotKE1tgqKbCx",
+            "size": "1615551024",
+            "state": "pinned"
+        }));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal() {
+        let decimal: Decimal = "0.0000721".parse().unwrap();
+        assert_eq!((decimal.mantissa(), decimal.scale()), (721, 7));
+        assert_eq!(decimal.to_fraction(), (721, 10000000));
+        assert_eq!(decimal.to_string(), "0.0000721");
+
+        assert_eq!(
+            "0.050".parse::<Decimal>().unwrap(),
+            Decimal::new(5, 2).unwrap()
+        );
+        assert_eq!("0.05".parse::<Decimal>().unwrap().to_fraction(), (1, 20));
+        assert_eq!("-1.5".parse::<Decimal>().unwrap().to_string(), "-1.5");
+        assert_eq!(
+            "3e-3".parse::<Decimal>().unwrap(),
+            Decimal::new(3, 3).unwrap()
+        );
+        assert_eq!("1.2E2".parse::<Decimal>().unwrap().to_string(), "120");
+        assert_eq!(Decimal::new(1, 39), None);
+        for invalid in ["", "-", ".5", "1.", "1.2.3", "0x10", "1e", "1e-39"] {
+            assert!(invalid.parse::<Decimal>().is_err(), "{}", invalid);
+        }
+
+        // More digits than an f64 holds, from a number or a string
+        let decimals: Vec<Decimal> =
+            serde_json::from_str(r#"[0.1000000000000000055511151231257827, "0.3"]"#).unwrap();
+        assert_eq!(
+            decimals[0].to_string(),
+            "0.1000000000000000055511151231257827"
+        );
+        assert_eq!(decimals[1].to_fraction(), (3, 10));
+        assert_eq!(serde_json::to_string(&decimals[1]).unwrap(), r#""0.3""#);
+    }
+}
@@ -1,6 +1,4 @@
-use crate::{
-    pagination::Pagination, CARDANO_MAINNET_URL, CARDANO_PREPROD_URL, CARDANO_PREVIEW_URL,
-};
+use crate::{pagination::Pagination, Network};
 use std::error::Error;
 use url::{form_urlencoded, Url as UrlI};
 
@@ -31,39 +29,18 @@ impl Url {
         Ok(url.to_string())
     }
 
-    pub fn generate_batch(
-        url: &str, batch_size: usize, start: usize, pagination: Pagination,
-    ) -> Result<Vec<String>, Box<dyn Error>> {
-        let mut result = Vec::new();
-        let mut url = UrlI::parse(url)?;
-
-        for page in start..batch_size {
-            let mut query_pairs = form_urlencoded::Serializer::new(String::new());
-
-            query_pairs.append_pair("page", page.to_string().as_str());
-            query_pairs.append_pair("count", pagination.count.to_string().as_str());
-            query_pairs.append_pair("order", pagination.order_to_string().as_str());
-
-            let query = query_pairs.finish();
-
-            url.set_query(Some(&query));
-
-            result.push(url.to_string());
-        }
-
-        Ok(result)
-    }
-
     pub fn get_base_url_from_project_id(project_id: &str) -> String {
         match project_id {
-            id if id.starts_with("mainnet") => CARDANO_MAINNET_URL,
-            id if id.starts_with("preview") => CARDANO_PREVIEW_URL,
-            id if id.starts_with("preprod") => CARDANO_PREPROD_URL,
-            _ => CARDANO_MAINNET_URL,
+            id if id.starts_with("mainnet") => Network::Mainnet,
+            id if id.starts_with("preview") => Network::Preview,
+            id if id.starts_with("preprod") => Network::Preprod,
+            _ => Network::Mainnet,
         }
+        .base_url()
         .to_string()
     }
 
+    // Joins `endpoint_url` after the whole path of `base_url`, with or without slashes between
     fn create_base_url(base_url: &str, endpoint_url: &str) -> Result<reqwest::Url, Box<dyn Error>> {
         let mut url = UrlI::parse(base_url)?;
         let endpoint = endpoint_url.strip_prefix('/').unwrap_or(endpoint_url);
@@ -89,4 +66,32 @@ mod tests {
         let result = Url::from_endpoint(base_url, endpoint_url).unwrap();
         assert_eq!(result, expected_url);
     }
+
+    #[test]
+    fn test_from_endpoint_base_url_slashes() {
+        let expected_url = "http://localhost:3000/api/v0/blocks/latest";
+
+        for base_url in [
+            "http://localhost:3000/api/v0",
+            "http://localhost:3000/api/v0/",
+        ] {
+            for endpoint_url in ["/blocks/latest", "blocks/latest"] {
+                let result = Url::from_endpoint(base_url, endpoint_url).unwrap();
+                assert_eq!(result, expected_url);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_paginated_endpoint_subpath() {
+        let base_url = "https://example.com/cardano/mainnet/api/v0/";
+        let pagination = Pagination::new(crate::Order::Desc, 2, 50);
+
+        let result = Url::from_paginated_endpoint(base_url, "/epochs/latest", pagination).unwrap();
+
+        assert_eq!(
+            result,
+            "https://example.com/cardano/mainnet/api/v0/epochs/latest?page=2&count=50&order=desc"
+        );
+    }
 }
@@ -1,25 +1,59 @@
-use crate::USER_AGENT;
+use crate::{BlockFrostSettings, USER_AGENT};
 use reqwest::{
     header::{HeaderMap, HeaderValue},
-    Client,
+    Client, ClientBuilder,
 };
 use serde_json::{from_str as json_from, Value as JsonValue};
 
+// Bytes of a hex string, `None` unless it has an even length and only hex digits
+pub(crate) fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
 pub(crate) fn try_formatting_json(text: &str) -> serde_json::Result<String> {
     let json = json_from::<JsonValue>(text)?;
 
     serde_json::to_string_pretty(&json)
 }
 
-pub(crate) fn create_client_with_project_id(project_id: impl AsRef<str>) -> Client {
+pub(crate) fn create_client_with_project_id(
+    project_id: impl AsRef<str>, compression: bool,
+) -> Client {
     let header_map = build_header_map(project_id.as_ref());
     // Safety: This unwrap is guaranteed to never fail if we only call .default_headers()
-    Client::builder()
+    // and toggle decompression
+    with_compression(Client::builder(), compression)
         .default_headers(header_map)
         .build()
         .unwrap()
 }
 
+// Enabling a decoder also makes reqwest send the matching `Accept-Encoding` header.
+pub(crate) fn with_compression(client_builder: ClientBuilder, compression: bool) -> ClientBuilder {
+    client_builder.gzip(compression).brotli(compression)
+}
+
+pub(crate) fn configure_client(
+    client_builder: ClientBuilder, settings: &BlockFrostSettings,
+) -> ClientBuilder {
+    let client_builder = settings.resolve_overrides.iter().fold(
+        with_compression(client_builder, settings.compression)
+            .redirect(settings.redirect_policy.into()),
+        |client_builder, (host, address)| client_builder.resolve(host, *address),
+    );
+
+    match settings.timeout {
+        Some(timeout) => client_builder.timeout(timeout),
+        None => client_builder,
+    }
+}
+
 pub(crate) fn build_header_map(project_id: &str) -> HeaderMap {
     let mut header_map = HeaderMap::new();
     let mut project_id = HeaderValue::from_str(project_id).unwrap_or_else(|_| {